@@ -0,0 +1,174 @@
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+mod pool;
+
+pub use pool::{clear_scratch, ScratchVec};
+
+/// typed index of a node in an `Arena`
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug, Default)]
+pub struct NodeId(u32);
+
+impl NodeId {
+    /// the first node allocated, tree structures keep their root or sentinel there \
+    /// so as a child link it means no child
+    pub const NULL: NodeId = NodeId(0);
+
+    pub fn new(i: usize) -> Self {
+        debug_assert!(i <= u32::MAX as usize, "node id overflow!");
+        Self(i as u32)
+    }
+
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+
+    pub fn is_null(self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl fmt::Display for NodeId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+const CHUNK_BITS: usize = 10;
+const CHUNK: usize = 1 << CHUNK_BITS;
+
+/// nodes stored in fixed-capacity chunks, growing never moves a node \
+/// freed slots are reused by later allocations; clear() drops every node
+/// but keeps the chunks for reuse
+pub struct Arena<T> {
+    chunks: Vec<Vec<T>>,
+    len: usize,
+    free: Vec<NodeId>,
+}
+
+impl<T> Default for Arena<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Arena<T> {
+    pub fn new() -> Self {
+        Self {
+            chunks: vec![],
+            len: 0,
+            free: vec![],
+        }
+    }
+
+    /// reserve room for n nodes up front
+    pub fn with_capacity(n: usize) -> Self {
+        let mut res = Self::new();
+        res.chunks = (0..n.div_ceil(CHUNK))
+            .map(|_| Vec::with_capacity(CHUNK))
+            .collect();
+        res
+    }
+
+    /// store v, reusing a freed slot if there is one
+    pub fn alloc(&mut self, v: T) -> NodeId {
+        if let Some(x) = self.free.pop() {
+            self[x] = v;
+            return x;
+        }
+        let c = self.len >> CHUNK_BITS;
+        if c == self.chunks.len() {
+            self.chunks.push(Vec::with_capacity(CHUNK));
+        }
+        self.chunks[c].push(v);
+        self.len += 1;
+        NodeId::new(self.len - 1)
+    }
+
+    /// mark x as reusable, its value stays until the slot is allocated again
+    pub fn free(&mut self, x: NodeId) {
+        debug_assert!(x.index() < self.len, "node id out of range!");
+        self.free.push(x);
+    }
+
+    /// number of slots handed out, freed ones included
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// number of slots in use
+    pub fn live(&self) -> usize {
+        self.len - self.free.len()
+    }
+
+    /// slots waiting for reuse
+    pub fn freed(&self) -> &[NodeId] {
+        &self.free
+    }
+
+    pub fn clear(&mut self) {
+        for c in &mut self.chunks {
+            c.clear();
+        }
+        self.len = 0;
+        self.free.clear();
+    }
+}
+
+impl<T> Index<NodeId> for Arena<T> {
+    type Output = T;
+
+    fn index(&self, x: NodeId) -> &T {
+        let i = x.index();
+        &self.chunks[i >> CHUNK_BITS][i & (CHUNK - 1)]
+    }
+}
+
+impl<T> IndexMut<NodeId> for Arena<T> {
+    fn index_mut(&mut self, x: NodeId) -> &mut T {
+        let i = x.index();
+        &mut self.chunks[i >> CHUNK_BITS][i & (CHUNK - 1)]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Arena, NodeId, ScratchVec};
+
+    #[test]
+    fn arena_test() {
+        let mut a = Arena::with_capacity(100);
+        let ids: Vec<NodeId> = (0..5000).map(|i| a.alloc(i)).collect();
+        assert!(ids
+            .iter()
+            .enumerate()
+            .all(|(i, &x)| x.index() == i && a[x] == i as i64));
+        a.free(ids[3000]);
+        a.free(ids[7]);
+        assert_eq!(a.live(), 4998);
+        assert_eq!(a.alloc(-1), ids[7]);
+        assert_eq!(a.alloc(-2), ids[3000]);
+        assert_eq!((a[ids[3000]], a.len()), (-2, 5000));
+        a.clear();
+        assert!(a.is_empty());
+        assert_eq!(a.alloc(9), NodeId::NULL);
+    }
+
+    #[test]
+    fn scratch_vec_test() {
+        let mut a = ScratchVec::from_elem(7u32, 1000);
+        a[3] = 1;
+        let p = a.as_ptr() as usize;
+        drop(a);
+        // same size and alignment, so the buffer comes back
+        let b = ScratchVec::<i32>::with_capacity(10);
+        assert_eq!((b.as_ptr() as usize, b.len()), (p, 0));
+        assert!(b.capacity() >= 1000);
+        let c = ScratchVec::from_elem(vec![1u8], 5);
+        assert_eq!(c.into_vec(), vec![vec![1]; 5]);
+    }
+}
@@ -0,0 +1,156 @@
+use std::alloc::{dealloc, Layout};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::mem::{self, ManuallyDrop};
+use std::ops::{Deref, DerefMut};
+
+// an empty allocation for cap elements of any type with this size and alignment
+struct RawBuf {
+    ptr: *mut u8,
+    cap: usize,
+    size: usize,
+    align: usize,
+}
+
+impl Drop for RawBuf {
+    fn drop(&mut self) {
+        // the layout Vec allocated it with
+        unsafe {
+            dealloc(
+                self.ptr,
+                Layout::from_size_align_unchecked(self.cap * self.size, self.align),
+            )
+        }
+    }
+}
+
+// buffers kept per (size, align), the smallest ones are freed beyond this
+const KEEP: usize = 8;
+
+thread_local! {
+    static POOL: RefCell<HashMap<(usize, usize), Vec<RawBuf>>> = RefCell::new(HashMap::new());
+}
+
+/// free every buffer pooled by this thread
+pub fn clear_scratch() {
+    POOL.with(|p| p.borrow_mut().clear());
+}
+
+/// a Vec borrowed from a thread-local pool, handed back emptied on drop \
+/// buffers are shared by all element types of the same size and alignment,
+/// so a routine called many times allocates only on its first calls
+pub struct ScratchVec<T> {
+    v: ManuallyDrop<Vec<T>>,
+}
+
+impl<T> ScratchVec<T> {
+    /// empty, with room for at least cap elements
+    pub fn with_capacity(cap: usize) -> Self {
+        let (size, align) = (mem::size_of::<T>(), mem::align_of::<T>());
+        let buf = if size == 0 {
+            None
+        } else {
+            POOL.try_with(|p| {
+                let mut p = p.borrow_mut();
+                let bufs = p.get_mut(&(size, align))?;
+                // the smallest buffer that is large enough, otherwise the largest one
+                let i = (0..bufs.len())
+                    .filter(|&i| bufs[i].cap >= cap)
+                    .min_by_key(|&i| bufs[i].cap)
+                    .or_else(|| (0..bufs.len()).max_by_key(|&i| bufs[i].cap))?;
+                Some(bufs.swap_remove(i))
+            })
+            .ok()
+            .flatten()
+        };
+        let mut v = match buf {
+            Some(b) => {
+                let b = ManuallyDrop::new(b);
+                // same size and alignment as the type it was allocated for, and empty
+                unsafe { Vec::from_raw_parts(b.ptr as *mut T, 0, b.cap) }
+            }
+            None => Vec::new(),
+        };
+        v.reserve(cap);
+        Self {
+            v: ManuallyDrop::new(v),
+        }
+    }
+
+    /// n clones of x, like vec!\[x; n\]
+    pub fn from_elem(x: T, n: usize) -> Self
+    where
+        T: Clone,
+    {
+        let mut res = Self::with_capacity(n);
+        res.v.resize(n, x);
+        res
+    }
+
+    /// keep the Vec, it won't return to the pool
+    pub fn into_vec(mut self) -> Vec<T> {
+        let v = unsafe { ManuallyDrop::take(&mut self.v) };
+        mem::forget(self);
+        v
+    }
+}
+
+impl<T> Drop for ScratchVec<T> {
+    fn drop(&mut self) {
+        let mut v = unsafe { ManuallyDrop::take(&mut self.v) };
+        v.clear();
+        let (size, align) = (mem::size_of::<T>(), mem::align_of::<T>());
+        if size == 0 || v.capacity() == 0 {
+            return;
+        }
+        let mut v = ManuallyDrop::new(v);
+        let b = RawBuf {
+            ptr: v.as_mut_ptr() as *mut u8,
+            cap: v.capacity(),
+            size,
+            align,
+        };
+        // after the pool is gone at thread exit, b frees itself
+        let _ = POOL.try_with(|p| {
+            let mut p = p.borrow_mut();
+            let bufs = p.entry((size, align)).or_default();
+            bufs.push(b);
+            if bufs.len() > KEEP {
+                let i = (0..bufs.len()).min_by_key(|&i| bufs[i].cap).unwrap();
+                bufs.swap_remove(i);
+            }
+        });
+    }
+}
+
+impl<T> Deref for ScratchVec<T> {
+    type Target = Vec<T>;
+
+    fn deref(&self) -> &Vec<T> {
+        &self.v
+    }
+}
+
+impl<T> DerefMut for ScratchVec<T> {
+    fn deref_mut(&mut self) -> &mut Vec<T> {
+        &mut self.v
+    }
+}
+
+impl<'a, T> IntoIterator for &'a ScratchVec<T> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.v.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut ScratchVec<T> {
+    type Item = &'a mut T;
+    type IntoIter = std::slice::IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.v.iter_mut()
+    }
+}
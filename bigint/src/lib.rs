@@ -0,0 +1,326 @@
+mod mag;
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Rem, Sub, SubAssign};
+use std::str::FromStr;
+
+use mag::BASE;
+
+/// byte position in the input where parsing failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseBigIntError {
+    pub pos: usize,
+}
+
+/// signed arbitrary precision integer in base 1e9 limbs \
+/// / and % truncate toward zero like the primitive integers
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub struct BigInt {
+    neg: bool,
+    mag: Vec<u32>, // little-endian, no leading zero limbs, empty and not neg for 0
+}
+
+impl BigInt {
+    fn from_parts(neg: bool, mag: Vec<u32>) -> Self {
+        let neg = neg && !mag.is_empty();
+        Self { neg, mag }
+    }
+
+    pub fn zero() -> Self {
+        Self::default()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.mag.is_empty()
+    }
+
+    /// -1, 0 or 1
+    pub fn signum(&self) -> i32 {
+        if self.neg {
+            -1
+        } else {
+            !self.is_zero() as i32
+        }
+    }
+
+    pub fn abs(&self) -> Self {
+        Self::from_parts(false, self.mag.clone())
+    }
+
+    /// self^e, 0^0 = 1
+    pub fn pow(&self, mut e: u32) -> Self {
+        let (mut a, mut res) = (self.clone(), Self::from(1));
+        while e > 0 {
+            if e & 1 == 1 {
+                res = &res * &a;
+            }
+            e >>= 1;
+            if e > 0 {
+                a = &a * &a;
+            }
+        }
+        res
+    }
+
+    /// (self / rhs, self % rhs), panics if rhs is 0
+    pub fn div_rem(&self, rhs: &Self) -> (Self, Self) {
+        assert!(!rhs.is_zero(), "division by zero!");
+        let (q, r) = mag::divmod(&self.mag, &rhs.mag);
+        (
+            Self::from_parts(self.neg != rhs.neg, q),
+            Self::from_parts(self.neg, r),
+        )
+    }
+
+    /// None if the value doesn't fit
+    pub fn to_i128(&self) -> Option<i128> {
+        let mut res: i128 = 0;
+        for &x in self.mag.iter().rev() {
+            res = res.checked_mul(BASE as i128)?;
+            res = if self.neg {
+                res.checked_sub(x as i128)?
+            } else {
+                res.checked_add(x as i128)?
+            };
+        }
+        Some(res)
+    }
+}
+
+impl From<i128> for BigInt {
+    fn from(v: i128) -> Self {
+        let mut x = v.unsigned_abs();
+        let mut mag = vec![];
+        while x > 0 {
+            mag.push((x % BASE as u128) as u32);
+            x /= BASE as u128;
+        }
+        Self::from_parts(v < 0, mag)
+    }
+}
+
+macro_rules! impl_from {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for BigInt {
+                fn from(v: $t) -> Self {
+                    Self::from(v as i128)
+                }
+            }
+        )*
+    };
+}
+
+impl_from!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+impl FromStr for BigInt {
+    type Err = ParseBigIntError;
+
+    /// decimal with an optional sign
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let b = s.as_bytes();
+        let start = matches!(b.first(), Some(b'+' | b'-')) as usize;
+        if start == b.len() {
+            return Err(ParseBigIntError { pos: b.len() });
+        }
+        if let Some(i) = (start..b.len()).find(|&i| !b[i].is_ascii_digit()) {
+            return Err(ParseBigIntError { pos: i });
+        }
+        let mut mag = vec![];
+        let mut end = b.len();
+        while end > start {
+            let lo = end.saturating_sub(9).max(start);
+            mag.push(s[lo..end].parse::<u32>().unwrap());
+            end = lo;
+        }
+        mag::trim(&mut mag);
+        Ok(Self::from_parts(b[0] == b'-', mag))
+    }
+}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut s = String::new();
+        match self.mag.split_last() {
+            None => s.push('0'),
+            Some((top, rest)) => {
+                s += &top.to_string();
+                for x in rest.iter().rev() {
+                    s += &format!("{x:09}");
+                }
+            }
+        }
+        f.pad_integral(!self.neg, "", &s)
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.neg, other.neg) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => mag::cmp(&self.mag, &other.mag),
+            (true, true) => mag::cmp(&other.mag, &self.mag),
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Neg for &BigInt {
+    type Output = BigInt;
+    fn neg(self) -> BigInt {
+        BigInt::from_parts(!self.neg, self.mag.clone())
+    }
+}
+
+impl Neg for BigInt {
+    type Output = BigInt;
+    fn neg(self) -> BigInt {
+        BigInt::from_parts(!self.neg, self.mag)
+    }
+}
+
+impl Add for &BigInt {
+    type Output = BigInt;
+    fn add(self, rhs: &BigInt) -> BigInt {
+        if self.neg == rhs.neg {
+            return BigInt::from_parts(self.neg, mag::add(&self.mag, &rhs.mag));
+        }
+        match mag::cmp(&self.mag, &rhs.mag) {
+            Ordering::Less => BigInt::from_parts(rhs.neg, mag::sub(&rhs.mag, &self.mag)),
+            _ => BigInt::from_parts(self.neg, mag::sub(&self.mag, &rhs.mag)),
+        }
+    }
+}
+
+impl Sub for &BigInt {
+    type Output = BigInt;
+    fn sub(self, rhs: &BigInt) -> BigInt {
+        self + &-rhs
+    }
+}
+
+impl Mul for &BigInt {
+    type Output = BigInt;
+    fn mul(self, rhs: &BigInt) -> BigInt {
+        BigInt::from_parts(self.neg != rhs.neg, mag::mul(&self.mag, &rhs.mag))
+    }
+}
+
+impl Div for &BigInt {
+    type Output = BigInt;
+    fn div(self, rhs: &BigInt) -> BigInt {
+        self.div_rem(rhs).0
+    }
+}
+
+impl Rem for &BigInt {
+    type Output = BigInt;
+    fn rem(self, rhs: &BigInt) -> BigInt {
+        self.div_rem(rhs).1
+    }
+}
+
+// the owned forms forward to the reference ones
+macro_rules! forward_binop {
+    ($($tr:ident $f:ident),*) => {
+        $(
+            impl $tr for BigInt {
+                type Output = BigInt;
+                fn $f(self, rhs: BigInt) -> BigInt {
+                    (&self).$f(&rhs)
+                }
+            }
+
+            impl $tr<&BigInt> for BigInt {
+                type Output = BigInt;
+                fn $f(self, rhs: &BigInt) -> BigInt {
+                    (&self).$f(rhs)
+                }
+            }
+
+            impl $tr<BigInt> for &BigInt {
+                type Output = BigInt;
+                fn $f(self, rhs: BigInt) -> BigInt {
+                    self.$f(&rhs)
+                }
+            }
+        )*
+    };
+}
+
+forward_binop!(Add add, Sub sub, Mul mul, Div div, Rem rem);
+
+macro_rules! forward_assign {
+    ($($tr:ident $f:ident $op:ident),*) => {
+        $(
+            impl $tr<&BigInt> for BigInt {
+                fn $f(&mut self, rhs: &BigInt) {
+                    *self = (&*self).$op(rhs);
+                }
+            }
+
+            impl $tr for BigInt {
+                fn $f(&mut self, rhs: BigInt) {
+                    *self = (&*self).$op(&rhs);
+                }
+            }
+        )*
+    };
+}
+
+forward_assign!(AddAssign add_assign add, SubAssign sub_assign sub, MulAssign mul_assign mul);
+
+#[cfg(test)]
+mod test {
+    use crate::{BigInt, ParseBigIntError};
+    use random::Random;
+
+    #[test]
+    fn bigint_test() {
+        let mut rd = Random::new(9);
+        let mut r = || rd.gen() as i64 as i128 >> rd.next(64);
+        for _ in 0..10000 {
+            let (a, b) = (r() * r(), r());
+            let (x, y) = (BigInt::from(a), BigInt::from(b));
+            assert_eq!(
+                a.to_string().parse::<BigInt>().unwrap().to_string(),
+                a.to_string()
+            );
+            assert_eq!((&x + &y).to_i128(), Some(a + b));
+            assert_eq!((&x - &y).to_i128(), Some(a - b));
+            assert_eq!(x.cmp(&y), a.cmp(&b));
+            if b != 0 {
+                assert_eq!((&x / &y).to_i128(), Some(a / b));
+                assert_eq!((&x % &y).to_i128(), Some(a % b));
+            }
+        }
+        // long operands take the convolution and the Knuth division paths
+        let digits = |rd: &mut Random, n: usize| -> String {
+            (0..n)
+                .map(|_| char::from(b'0' + rd.next(10) as u8))
+                .collect()
+        };
+        for len in [10, 100, 5000] {
+            let a: BigInt = format!("1{}", digits(&mut rd, len)).parse().unwrap();
+            let b: BigInt = format!("-7{}", digits(&mut rd, len / 3)).parse().unwrap();
+            let (q, r) = a.div_rem(&b);
+            assert!(r.abs() < b.abs() && r.signum() >= 0);
+            assert_eq!(&q * &b + &r, a);
+            assert_eq!(&a * &b / &a, b);
+        }
+        let fact = (1..=100).fold(BigInt::from(1), |acc, i| acc * BigInt::from(i));
+        assert!(fact.to_string().starts_with("93326215443944152681"));
+        assert_eq!(fact.to_string().len(), 158);
+        assert_eq!(BigInt::from(-3).pow(41), -BigInt::from(3i128.pow(41)));
+        assert_eq!(format!("{:>5}", BigInt::from(-42)), "  -42");
+        assert_eq!("12a".parse::<BigInt>(), Err(ParseBigIntError { pos: 2 }));
+        assert_eq!("-0".parse::<BigInt>().unwrap(), BigInt::zero());
+    }
+}
@@ -0,0 +1,181 @@
+// magnitudes as little-endian base 1e9 limbs without leading zero limbs, zero is empty
+
+use std::cmp::Ordering;
+
+use convolution::convolution_i64;
+
+pub const BASE: u64 = 1_000_000_000;
+
+pub fn trim(a: &mut Vec<u32>) {
+    while a.last() == Some(&0) {
+        a.pop();
+    }
+}
+
+pub fn cmp(a: &[u32], b: &[u32]) -> Ordering {
+    a.len()
+        .cmp(&b.len())
+        .then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+pub fn add(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let (a, b) = if a.len() < b.len() { (b, a) } else { (a, b) };
+    let mut res = Vec::with_capacity(a.len() + 1);
+    let mut carry = 0;
+    for (i, &x) in a.iter().enumerate() {
+        let s = x as u64 + b.get(i).copied().unwrap_or(0) as u64 + carry;
+        res.push((s % BASE) as u32);
+        carry = s / BASE;
+    }
+    if carry > 0 {
+        res.push(carry as u32);
+    }
+    res
+}
+
+/// a - b for a >= b
+pub fn sub(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut res = Vec::with_capacity(a.len());
+    let mut borrow = 0;
+    for (i, &x) in a.iter().enumerate() {
+        let mut d = x as i64 - b.get(i).copied().unwrap_or(0) as i64 - borrow;
+        borrow = (d < 0) as i64;
+        if d < 0 {
+            d += BASE as i64;
+        }
+        res.push(d as u32);
+    }
+    trim(&mut res);
+    res
+}
+
+pub fn mul_small(a: &[u32], m: u32) -> Vec<u32> {
+    let mut res = Vec::with_capacity(a.len() + 1);
+    let mut carry = 0;
+    for &x in a {
+        let s = x as u64 * m as u64 + carry;
+        res.push((s % BASE) as u32);
+        carry = s / BASE;
+    }
+    if carry > 0 {
+        res.push(carry as u32);
+    }
+    trim(&mut res);
+    res
+}
+
+pub fn mul(a: &[u32], b: &[u32]) -> Vec<u32> {
+    if a.is_empty() || b.is_empty() {
+        return vec![];
+    }
+    if a.len().min(b.len()) <= 40 {
+        let mut acc = vec![0u64; a.len() + b.len()];
+        for (i, &x) in a.iter().enumerate() {
+            let mut carry = 0;
+            for (j, &y) in b.iter().enumerate() {
+                let s = acc[i + j] + x as u64 * y as u64 + carry;
+                acc[i + j] = s % BASE;
+                carry = s / BASE;
+            }
+            acc[i + b.len()] += carry;
+        }
+        let mut res: Vec<u32> = acc.into_iter().map(|x| x as u32).collect();
+        trim(&mut res);
+        return res;
+    }
+    // split into base 1e3 digits so every coefficient fits in i64
+    let split = |v: &[u32]| -> Vec<i64> {
+        v.iter()
+            .flat_map(|&x| [x % 1000, x / 1000 % 1000, x / 1_000_000].map(|d| d as i64))
+            .collect()
+    };
+    let c = convolution_i64(&split(a), &split(b));
+    let mut res = Vec::with_capacity(c.len() / 3 + 2);
+    let mut carry = 0i64;
+    for ch in c.chunks(3) {
+        let mut limb = 0;
+        for (k, &x) in ch.iter().enumerate() {
+            let s = x + carry;
+            limb += (s % 1000) as u32 * [1, 1000, 1_000_000][k];
+            carry = s / 1000;
+        }
+        res.push(limb);
+    }
+    while carry > 0 {
+        res.push((carry % BASE as i64) as u32);
+        carry /= BASE as i64;
+    }
+    trim(&mut res);
+    res
+}
+
+/// (a / m, a % m)
+pub fn divmod_small(a: &[u32], m: u32) -> (Vec<u32>, u32) {
+    let mut q = vec![0; a.len()];
+    let mut r = 0u64;
+    for i in (0..a.len()).rev() {
+        let cur = r * BASE + a[i] as u64;
+        q[i] = (cur / m as u64) as u32;
+        r = cur % m as u64;
+    }
+    trim(&mut q);
+    (q, r as u32)
+}
+
+/// (a / b, a % b) for nonzero b, Knuth's algorithm D
+pub fn divmod(a: &[u32], b: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    if cmp(a, b) == Ordering::Less {
+        return (vec![], a.to_vec());
+    }
+    if b.len() == 1 {
+        let (q, r) = divmod_small(a, b[0]);
+        return (q, if r == 0 { vec![] } else { vec![r] });
+    }
+    // normalize so that the top limb of the divisor is at least BASE / 2
+    let d = (BASE / (b[b.len() - 1] as u64 + 1)) as u32;
+    let mut u = mul_small(a, d);
+    let v = mul_small(b, d);
+    let n = v.len();
+    u.resize(a.len() + 1, 0);
+    let m = u.len() - n - 1;
+    let mut q = vec![0u32; m + 1];
+    let (vt, vs) = (v[n - 1] as u64, v[n - 2] as u64);
+    for j in (0..=m).rev() {
+        let num = u[j + n] as u64 * BASE + u[j + n - 1] as u64;
+        let (mut qh, mut rh) = (num / vt, num % vt);
+        while qh >= BASE || qh * vs > rh * BASE + u[j + n - 2] as u64 {
+            qh -= 1;
+            rh += vt;
+            if rh >= BASE {
+                break;
+            }
+        }
+        // u[j..=j + n] -= qh * v
+        let (mut borrow, mut carry) = (0i64, 0u64);
+        for i in 0..=n {
+            let p = qh * v.get(i).copied().unwrap_or(0) as u64 + carry;
+            carry = p / BASE;
+            let mut t = u[i + j] as i64 - (p % BASE) as i64 - borrow;
+            borrow = (t < 0) as i64;
+            if t < 0 {
+                t += BASE as i64;
+            }
+            u[i + j] = t as u32;
+        }
+        if borrow > 0 {
+            // qh was one too large, add v back
+            qh -= 1;
+            let mut c = 0;
+            for i in 0..=n {
+                let s = u[i + j] as u64 + v.get(i).copied().unwrap_or(0) as u64 + c;
+                u[i + j] = (s % BASE) as u32;
+                c = s / BASE;
+            }
+        }
+        q[j] = qh as u32;
+    }
+    trim(&mut q);
+    u.truncate(n);
+    trim(&mut u);
+    (q, divmod_small(&u, d).0)
+}
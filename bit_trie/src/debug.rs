@@ -0,0 +1,38 @@
+use std::fmt::{self, Debug, Formatter};
+
+use arena::NodeId;
+
+use crate::{BitTrie, ROOT};
+
+impl BitTrie {
+    fn dump(&self, f: &mut Formatter<'_>, x: NodeId, prefix: usize, bits: usize) -> fmt::Result {
+        let depth = self.k - bits;
+        writeln!(
+            f,
+            "{:w$}[{}, {}] count {}",
+            "",
+            prefix << bits,
+            ((prefix + 1) << bits) - 1,
+            self.data[x].val,
+            w = 2 * depth
+        )?;
+        if bits == 0 {
+            return Ok(());
+        }
+        for bit in 0..2 {
+            let y = self.data[x].son[bit];
+            if !y.is_null() && self.data[y].val != 0 {
+                self.dump(f, y, prefix << 1 | bit, bits - 1)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// every nonempty node with the range of values below it and its count
+impl Debug for BitTrie {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "BitTrie ({} bits)", self.k)?;
+        self.dump(f, ROOT, 0, self.k)
+    }
+}
@@ -1,35 +1,51 @@
+#[cfg(feature = "debug")]
+mod debug;
+#[cfg(feature = "validate")]
+mod validate;
+
+use arena::{Arena, NodeId};
+
+#[derive(Clone, Copy)]
 struct Node {
-    son: [usize; 2],
+    son: [NodeId; 2],
     val: usize,
 }
 
+const EMPTY: Node = Node {
+    son: [NodeId::NULL; 2],
+    val: 0,
+};
+
+// the root is allocated first and so takes the slot of NodeId::NULL,
+// no son ever points back at it, so a NULL son still means "no child"
+const ROOT: NodeId = NodeId::NULL;
+
 pub struct BitTrie {
-    data: Vec<Node>,
+    data: Arena<Node>,
     k: usize,
 }
 
 impl BitTrie {
     // by default 1e5
     pub fn new(k: usize) -> Self {
-        let mut data = Vec::with_capacity(100000);
-        data.push(Node {
-            son: [0, 0],
-            val: 0,
-        });
+        let mut data = Arena::with_capacity(100000);
+        data.alloc(EMPTY);
         Self { data, k }
     }
 
+    /// remove every value, keeping the allocated memory
+    pub fn clear(&mut self) {
+        self.data.clear();
+        self.data.alloc(EMPTY);
+    }
+
     pub fn insert(&mut self, x: usize) {
-        let mut now = 0;
-        self.data[0].val += 1;
+        let mut now = ROOT;
+        self.data[ROOT].val += 1;
         for i in (0..self.k).rev() {
             let bit = (x >> i) & 1;
-            if self.data[now].son[bit] == 0 {
-                self.data.push(Node {
-                    son: [0, 0],
-                    val: 0,
-                });
-                self.data[now].son[bit] = self.data.len() - 1;
+            if self.data[now].son[bit].is_null() {
+                self.data[now].son[bit] = self.data.alloc(EMPTY);
             }
             now = self.data[now].son[bit];
             self.data[now].val += 1;
@@ -37,14 +53,14 @@ impl BitTrie {
     }
 
     pub fn contains(&self, x: usize) -> bool {
-        if self.data[0].val == 0 {
+        if self.data[ROOT].val == 0 {
             return false;
         }
-        let mut now = 0;
+        let mut now = ROOT;
         for i in (0..self.k).rev() {
             let bit = (x >> i) & 1;
             let nxt = self.data[now].son[bit];
-            if nxt != 0 && self.data[nxt].val != 0 {
+            if !nxt.is_null() && self.data[nxt].val != 0 {
                 now = nxt;
             } else {
                 return false;
@@ -55,8 +71,8 @@ impl BitTrie {
 
     // unchecked, only remove one when multiple value!
     pub fn remove(&mut self, x: usize) {
-        let mut now = 0;
-        self.data[0].val -= 1;
+        let mut now = ROOT;
+        self.data[ROOT].val -= 1;
         for i in (0..self.k).rev() {
             let bit = (x >> i) & 1;
             now = self.data[now].son[bit];
@@ -66,18 +82,23 @@ impl BitTrie {
 
     // use when at least one element!
     pub fn find_nearest(&self, x: usize) -> usize {
-        self.find_nearst_from(0, 0, self.k, x)
+        self.try_find_nearest(x).expect("no enough elements!")
+    }
+
+    /// None if the trie is empty
+    pub fn try_find_nearest(&self, x: usize) -> Option<usize> {
+        (self.data[ROOT].val != 0).then(|| self.find_nearst_from(ROOT, 0, self.k, x))
     }
 
     // nearest from node now, bit l
-    fn find_nearst_from(&self, mut now: usize, mut val: usize, l: usize, x: usize) -> usize {
+    fn find_nearst_from(&self, mut now: NodeId, mut val: usize, l: usize, x: usize) -> usize {
         if self.data[now].val == 0 {
             panic!("no enough elements!");
         }
         for i in (0..l).rev() {
             let bit = (x >> i) & 1;
             let nxt = self.data[now].son[bit];
-            if nxt != 0 && self.data[nxt].val != 0 {
+            if !nxt.is_null() && self.data[nxt].val != 0 {
                 now = nxt;
                 val += bit << i;
             } else {
@@ -91,17 +112,17 @@ impl BitTrie {
 
     pub fn find_sub_nearests(&self, x: usize) -> Vec<usize> {
         let mut res = Vec::with_capacity(self.k);
-        let mut now = 0;
+        let mut now = ROOT;
         let mut val = 0;
         let mut last = true;
         for i in (0..self.k).rev() {
             let bit = (x >> i) & 1;
             let nxt = self.data[now].son[bit];
             let other = self.data[now].son[bit ^ 1];
-            if other != 0 && self.data[other].val != 0 {
+            if !other.is_null() && self.data[other].val != 0 {
                 res.push(self.find_nearst_from(other, val + ((bit ^ 1) << i), i, x));
             }
-            if nxt != 0 && self.data[nxt].val != 0 {
+            if !nxt.is_null() && self.data[nxt].val != 0 {
                 now = nxt;
                 val += bit << i;
             } else {
@@ -115,3 +136,101 @@ impl BitTrie {
         res
     }
 }
+
+#[cfg(test)]
+mod test {
+    use random::Random;
+
+    use crate::BitTrie;
+
+    // the nearest value whose highest bit differing from x is i, for each i from high to low
+    fn brute_sub_nearests(a: &[usize], x: usize, k: usize) -> Vec<usize> {
+        let mut res: Vec<usize> = (0..k)
+            .rev()
+            .filter_map(|i| {
+                a.iter()
+                    .copied()
+                    .filter(|y| (y ^ x) >> i == 1)
+                    .min_by_key(|y| y ^ x)
+            })
+            .collect();
+        if a.contains(&x) {
+            res.push(x);
+        }
+        res
+    }
+
+    #[test]
+    fn try_find_nearest_test() {
+        let mut tr = BitTrie::new(4);
+        assert_eq!(tr.try_find_nearest(5), None);
+        tr.insert(9);
+        tr.insert(9);
+        assert_eq!(tr.try_find_nearest(5), Some(9));
+        tr.remove(9);
+        assert_eq!(tr.try_find_nearest(0), Some(9));
+        tr.remove(9);
+        assert_eq!(tr.try_find_nearest(9), None);
+        tr.insert(2);
+        tr.clear();
+        assert_eq!(tr.try_find_nearest(2), None);
+    }
+
+    #[test]
+    fn differential_test() {
+        let mut rd = Random::new(1);
+        for k in [1, 3, 6] {
+            let mut tr = BitTrie::new(k);
+            let mut a: Vec<usize> = vec![];
+            for _ in 0..5000 {
+                let x = rd.next(1 << k) as usize;
+                match rd.next(4) {
+                    0 => {
+                        tr.insert(x);
+                        a.push(x);
+                    }
+                    1 => {
+                        // remove is unchecked, only remove present values
+                        if let Some(i) = a.iter().position(|&y| y == x) {
+                            tr.remove(x);
+                            a.swap_remove(i);
+                        }
+                    }
+                    2 => {
+                        assert_eq!(tr.contains(x), a.contains(&x));
+                        let e = a.iter().map(|y| y ^ x).min().map(|d| d ^ x);
+                        assert_eq!(tr.try_find_nearest(x), e);
+                        if let Some(e) = e {
+                            assert_eq!(tr.find_nearest(x), e);
+                        }
+                    }
+                    _ => assert_eq!(tr.find_sub_nearests(x), brute_sub_nearests(&a, x, k)),
+                }
+                #[cfg(feature = "validate")]
+                tr.validate();
+            }
+            // empty the trie by remove, the nodes stay allocated with count 0
+            a.drain(..).for_each(|x| tr.remove(x));
+            assert!((0..1 << k).all(|x| tr.try_find_nearest(x).is_none() && !tr.contains(x)));
+        }
+    }
+
+    #[cfg(feature = "debug")]
+    #[test]
+    fn debug_test() {
+        let mut tr = BitTrie::new(2);
+        tr.insert(1);
+        tr.insert(3);
+        assert_eq!(
+            format!("{:?}", tr),
+            concat!(
+                "BitTrie (2 bits)\n",
+                "[0, 3] count 2\n",
+                "  [0, 1] count 1\n",
+                "    [1, 1] count 1\n",
+                "  [2, 3] count 1\n",
+                "    [3, 3] count 1\n",
+            )
+        );
+    }
+}
@@ -0,0 +1,21 @@
+use crate::{BitTrie, ROOT};
+
+impl BitTrie {
+    /// panic if the count of some node differs from the sum over its children
+    pub fn validate(&self) {
+        let mut stack = vec![(ROOT, self.k)];
+        while let Some((x, bits)) = stack.pop() {
+            if bits == 0 {
+                continue;
+            }
+            let mut sum = 0;
+            for &y in &self.data[x].son {
+                if !y.is_null() {
+                    sum += self.data[y].val;
+                    stack.push((y, bits - 1));
+                }
+            }
+            assert_eq!(self.data[x].val, sum, "count of node {} is wrong", x);
+        }
+    }
+}
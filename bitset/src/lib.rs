@@ -0,0 +1,186 @@
+mod word_set;
+
+pub use word_set::WordSet;
+
+use std::ops::{BitAndAssign, BitOrAssign, BitXorAssign, RangeBounds};
+
+use range_query::half_open;
+
+/// half-open [l, r) of range clipped to 0..n
+fn bounds<R: RangeBounds<usize>>(range: R, n: usize) -> (usize, usize) {
+    let (l, r) = half_open(range, 0, n);
+    (l.min(n), r.min(n))
+}
+
+/// subset of 0..n packed into u64 words, bits past n are always 0
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct BitSet {
+    n: usize,
+    w: Vec<u64>,
+}
+
+impl BitSet {
+    pub fn new(n: usize) -> Self {
+        Self {
+            n,
+            w: vec![0; n.div_ceil(64)],
+        }
+    }
+
+    /// size of the universe, not the number of elements
+    pub fn size(&self) -> usize {
+        self.n
+    }
+
+    pub fn words(&self) -> &[u64] {
+        &self.w
+    }
+
+    pub fn contains(&self, i: usize) -> bool {
+        debug_assert!(i < self.n, "index out of range!");
+        self.w[i >> 6] >> (i & 63) & 1 == 1
+    }
+
+    /// return whether i was absent
+    pub fn insert(&mut self, i: usize) -> bool {
+        let res = !self.contains(i);
+        self.w[i >> 6] |= 1 << (i & 63);
+        res
+    }
+
+    /// return whether i was present
+    pub fn remove(&mut self, i: usize) -> bool {
+        let res = self.contains(i);
+        self.w[i >> 6] &= !(1 << (i & 63));
+        res
+    }
+
+    pub fn flip(&mut self, i: usize) {
+        debug_assert!(i < self.n, "index out of range!");
+        self.w[i >> 6] ^= 1 << (i & 63);
+    }
+
+    /// set every bit in range to v
+    pub fn set_range<R: RangeBounds<usize>>(&mut self, range: R, v: bool) {
+        let (l, r) = bounds(range, self.n);
+        let mut i = l;
+        while i < r {
+            let hi = ((i | 63) + 1).min(r);
+            let mask = (u64::MAX >> (64 - (hi - i))) << (i & 63);
+            if v {
+                self.w[i >> 6] |= mask;
+            } else {
+                self.w[i >> 6] &= !mask;
+            }
+            i = hi;
+        }
+    }
+
+    /// number of elements
+    pub fn count(&self) -> usize {
+        self.w.iter().map(|x| x.count_ones() as usize).sum()
+    }
+
+    pub fn clear(&mut self) {
+        self.w.fill(0);
+    }
+
+    /// elements in increasing order
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.w.iter().enumerate().flat_map(|(i, &x)| {
+            let mut x = x;
+            std::iter::from_fn(move || {
+                (x != 0).then(|| {
+                    let b = x.trailing_zeros() as usize;
+                    x &= x - 1;
+                    i << 6 | b
+                })
+            })
+        })
+    }
+}
+
+impl BitOrAssign<&BitSet> for BitSet {
+    fn bitor_assign(&mut self, rhs: &BitSet) {
+        debug_assert_eq!(self.n, rhs.n, "size mismatch!");
+        for (a, b) in self.w.iter_mut().zip(&rhs.w) {
+            *a |= b;
+        }
+    }
+}
+
+impl BitAndAssign<&BitSet> for BitSet {
+    fn bitand_assign(&mut self, rhs: &BitSet) {
+        debug_assert_eq!(self.n, rhs.n, "size mismatch!");
+        for (a, b) in self.w.iter_mut().zip(&rhs.w) {
+            *a &= b;
+        }
+    }
+}
+
+impl BitXorAssign<&BitSet> for BitSet {
+    fn bitxor_assign(&mut self, rhs: &BitSet) {
+        debug_assert_eq!(self.n, rhs.n, "size mismatch!");
+        for (a, b) in self.w.iter_mut().zip(&rhs.w) {
+            *a ^= b;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{BitSet, WordSet};
+    use random::Random;
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn bitset_test() {
+        let (mut a, mut b) = (BitSet::new(130), BitSet::new(130));
+        a.set_range(3..=70, true);
+        b.set_range(60.., true);
+        b.remove(129);
+        let mut c = a.clone();
+        c &= &b;
+        assert!(c.iter().eq(60..=70));
+        c |= &a;
+        c ^= &b;
+        assert!(c.iter().eq((3..60).chain(71..129)));
+        assert_eq!(c.count(), 115);
+        // ends past n are clipped
+        c.set_range(120..usize::MAX, false);
+        c.set_range(125..=1000, true);
+        assert!(c.iter().eq((3..60).chain(71..120).chain(125..130)));
+    }
+
+    #[test]
+    fn word_set_test() {
+        let mut rd = Random::new(11);
+        for n in [0, 1, 64, 65, 4097, 300000] {
+            let mut s = WordSet::new(n);
+            let mut b = BTreeSet::new();
+            for _ in 0..3000.min(n * 10) {
+                let x = rd.next(n as u64) as usize;
+                match rd.next(5) {
+                    0 => assert_eq!(s.insert(x), b.insert(x)),
+                    1 => assert_eq!(s.remove(x), b.remove(&x)),
+                    2 => {
+                        let (y, v) = ((x + rd.next(300) as usize).min(n), rd.next(2) == 0);
+                        s.set_range(x..y, v);
+                        for z in x..y {
+                            if v {
+                                b.insert(z);
+                            } else {
+                                b.remove(&z);
+                            }
+                        }
+                    }
+                    3 => assert_eq!(s.next_at_least(x), b.range(x..).next().copied()),
+                    _ => assert_eq!(s.prev_at_most(x), b.range(..=x).next_back().copied()),
+                }
+                assert_eq!(s.len(), b.len());
+            }
+            assert!(s.iter().eq(b.iter().copied()));
+            assert!(s.as_bitset().iter().eq(b.iter().copied()));
+        }
+    }
+}
@@ -0,0 +1,176 @@
+use std::ops::RangeBounds;
+
+use crate::{bounds, BitSet};
+
+/// ordered set over 0..n, a BitSet with a 64-ary summary of its nonempty words on top \
+/// insert, remove, next_at_least and prev_at_most in O(log_64 n)
+pub struct WordSet {
+    bits: BitSet,
+    up: Vec<Vec<u64>>, // bit i of up[d] tells whether word i one level below is nonzero
+    len: usize,
+}
+
+impl WordSet {
+    pub fn new(n: usize) -> Self {
+        let mut up = vec![];
+        let mut m = n.div_ceil(64);
+        while m > 1 {
+            m = m.div_ceil(64);
+            up.push(vec![0; m]);
+        }
+        Self {
+            bits: BitSet::new(n),
+            up,
+            len: 0,
+        }
+    }
+
+    /// size of the universe
+    pub fn size(&self) -> usize {
+        self.bits.size()
+    }
+
+    /// number of elements
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn as_bitset(&self) -> &BitSet {
+        &self.bits
+    }
+
+    // level 0 is the bitset itself
+    fn level(&self, d: usize) -> &[u64] {
+        if d == 0 {
+            self.bits.words()
+        } else {
+            &self.up[d - 1]
+        }
+    }
+
+    pub fn contains(&self, x: usize) -> bool {
+        self.bits.contains(x)
+    }
+
+    /// return whether x was absent
+    pub fn insert(&mut self, x: usize) -> bool {
+        if !self.bits.insert(x) {
+            return false;
+        }
+        self.len += 1;
+        let mut i = x >> 6;
+        for u in &mut self.up {
+            let was = u[i >> 6];
+            u[i >> 6] |= 1 << (i & 63);
+            if was != 0 {
+                break;
+            }
+            i >>= 6;
+        }
+        true
+    }
+
+    /// return whether x was present
+    pub fn remove(&mut self, x: usize) -> bool {
+        if !self.bits.remove(x) {
+            return false;
+        }
+        self.len -= 1;
+        let mut i = x >> 6;
+        let mut empty = self.bits.words()[i] == 0;
+        for u in &mut self.up {
+            if !empty {
+                break;
+            }
+            u[i >> 6] &= !(1 << (i & 63));
+            empty = u[i >> 6] == 0;
+            i >>= 6;
+        }
+        true
+    }
+
+    /// set every element of range to present or absent, O(|range| / 64 + log_64 n)
+    pub fn set_range<R: RangeBounds<usize>>(&mut self, range: R, v: bool) {
+        let (l, r) = bounds(range, self.size());
+        if l == r {
+            return;
+        }
+        let (mut lo, mut hi) = (l >> 6, (r - 1) >> 6);
+        let before: usize = self.bits.words()[lo..=hi]
+            .iter()
+            .map(|x| x.count_ones() as usize)
+            .sum();
+        self.bits.set_range(l..r, v);
+        let after: usize = self.bits.words()[lo..=hi]
+            .iter()
+            .map(|x| x.count_ones() as usize)
+            .sum();
+        self.len = self.len + after - before;
+        for d in 0..self.up.len() {
+            for i in lo..=hi {
+                let nonzero = self.level(d)[i] != 0;
+                let u = &mut self.up[d][i >> 6];
+                if nonzero {
+                    *u |= 1 << (i & 63);
+                } else {
+                    *u &= !(1 << (i & 63));
+                }
+            }
+            (lo, hi) = (lo >> 6, hi >> 6);
+        }
+    }
+
+    /// smallest element >= x
+    pub fn next_at_least(&self, x: usize) -> Option<usize> {
+        let mut i = x;
+        for d in 0..=self.up.len() {
+            let w = self.level(d);
+            if i >> 6 >= w.len() {
+                return None;
+            }
+            let m = w[i >> 6] >> (i & 63);
+            if m != 0 {
+                i += m.trailing_zeros() as usize;
+                for e in (0..d).rev() {
+                    i = i << 6 | self.level(e)[i].trailing_zeros() as usize;
+                }
+                return Some(i);
+            }
+            i = (i >> 6) + 1;
+        }
+        None
+    }
+
+    /// largest element <= x
+    pub fn prev_at_most(&self, x: usize) -> Option<usize> {
+        if self.size() == 0 {
+            return None;
+        }
+        let mut i = x.min(self.size() - 1);
+        for d in 0..=self.up.len() {
+            let w = self.level(d);
+            let m = w[i >> 6] << (63 - (i & 63));
+            if m != 0 {
+                i -= m.leading_zeros() as usize;
+                for e in (0..d).rev() {
+                    i = i << 6 | (63 - self.level(e)[i].leading_zeros() as usize);
+                }
+                return Some(i);
+            }
+            if i >> 6 == 0 {
+                return None;
+            }
+            i = (i >> 6) - 1;
+        }
+        None
+    }
+
+    /// elements in increasing order
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        std::iter::successors(self.next_at_least(0), |&x| self.next_at_least(x + 1))
+    }
+}
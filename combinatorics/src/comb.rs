@@ -0,0 +1,112 @@
+use number_theory::pow_mod;
+
+/// factorials and inverse factorials modulo a prime p \
+/// the tables grow on demand up to p - 1, the largest factorial nonzero mod p
+pub struct Comb {
+    p: u64,
+    fact: Vec<u64>,
+    inv_fact: Vec<u64>,
+}
+
+impl Comb {
+    /// tables for 0..n up front
+    pub fn new(n: usize, p: u64) -> Self {
+        let mut res = Self {
+            p,
+            fact: vec![1 % p],
+            inv_fact: vec![1 % p],
+        };
+        res.reserve(n);
+        res
+    }
+
+    pub fn modulus(&self) -> u64 {
+        self.p
+    }
+
+    /// grow the tables to cover 0..n
+    pub fn reserve(&mut self, n: usize) {
+        let old = self.fact.len();
+        if n <= old {
+            return;
+        }
+        assert!(n as u64 <= self.p, "factorials vanish mod p from p on!");
+        let n = n.max(old * 2).min(self.p as usize);
+        let p = self.p;
+        for i in old..n {
+            self.fact.push(self.fact[i - 1] * i as u64 % p);
+        }
+        self.inv_fact.resize(n, 0);
+        self.inv_fact[n - 1] = pow_mod(self.fact[n - 1], p - 2, p);
+        for i in (old..n - 1).rev() {
+            self.inv_fact[i] = self.inv_fact[i + 1] * (i + 1) as u64 % p;
+        }
+    }
+
+    pub fn fact(&mut self, n: usize) -> u64 {
+        self.reserve(n + 1);
+        self.fact[n]
+    }
+
+    pub fn inv_fact(&mut self, n: usize) -> u64 {
+        self.reserve(n + 1);
+        self.inv_fact[n]
+    }
+
+    /// 1 / n mod p for 1 <= n < p
+    pub fn inv(&mut self, n: usize) -> u64 {
+        self.reserve(n + 1);
+        self.inv_fact[n] * self.fact[n - 1] % self.p
+    }
+
+    /// C(n, k) mod p, 0 if k > n
+    pub fn binom(&mut self, n: usize, k: usize) -> u64 {
+        if k > n {
+            return 0;
+        }
+        self.reserve(n + 1);
+        self.fact[n] * self.inv_fact[k] % self.p * self.inv_fact[n - k] % self.p
+    }
+
+    /// n! / (n - k)!, 0 if k > n
+    pub fn perm(&mut self, n: usize, k: usize) -> u64 {
+        if k > n {
+            return 0;
+        }
+        self.reserve(n + 1);
+        self.fact[n] * self.inv_fact[n - k] % self.p
+    }
+
+    /// (k\[0\] + k\[1\] + ...)! / (k\[0\]! k\[1\]! ...)
+    pub fn multinomial(&mut self, k: &[usize]) -> u64 {
+        let n: usize = k.iter().sum();
+        self.reserve(n + 1);
+        k.iter()
+            .fold(self.fact[n], |acc, &x| acc * self.inv_fact[x] % self.p)
+    }
+
+    /// C(2n, n) / (n + 1)
+    pub fn catalan(&mut self, n: usize) -> u64 {
+        (self.binom(2 * n, n) + self.p - self.binom(2 * n, n + 1)) % self.p
+    }
+    /// C(n, k) mod p for any n < 2^64 and k < p, O(k) \
+    /// n (n - 1) ... (n - k + 1) / k!
+    pub fn binom_big(&mut self, n: u64, k: usize) -> u64 {
+        if k as u64 > n {
+            return 0;
+        }
+        let p = self.p;
+        (0..k as u64).fold(self.inv_fact(k), |acc, i| acc * ((n - i) % p) % p)
+    }
+}
+
+/// derangements D(i) mod p for i in 0..=n, permutations of i items without fixed points \
+/// D(i) = (i - 1) (D(i - 1) + D(i - 2))
+pub fn derangements(n: usize, p: u64) -> Vec<u64> {
+    let mut d = vec![1 % p, 0];
+    for i in 2..=n {
+        d.push((i as u64 - 1) % p * ((d[i - 1] + d[i - 2]) % p) % p);
+    }
+    d.truncate(n + 1);
+    d
+}
@@ -0,0 +1,68 @@
+use crate::Comb;
+
+// monotone paths taking n steps in total whose y - x changes by t
+fn walks(c: &mut Comb, n: i64, t: i64) -> u64 {
+    if n < 0 || t.abs() > n || (n + t) % 2 != 0 {
+        return 0;
+    }
+    c.binom(n as usize, ((n + t) / 2) as usize)
+}
+
+// the number of steps and the change of y - x between two points, None if unreachable
+fn steps(from: (i64, i64), to: (i64, i64)) -> Option<(i64, i64, i64)> {
+    let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+    (dx >= 0 && dy >= 0).then_some((dx + dy, from.1 - from.0, to.1 - to.0))
+}
+
+/// paths from `from` to `to` with unit steps right (x + 1) or up (y + 1)
+pub fn paths(c: &mut Comb, from: (i64, i64), to: (i64, i64)) -> u64 {
+    match steps(from, to) {
+        Some((n, d0, d1)) => walks(c, n, d1 - d0),
+        None => 0,
+    }
+}
+
+/// right/up paths never going above the line y = x + k, by reflection \
+/// 0 if an endpoint is above the line already
+pub fn paths_below(c: &mut Comb, from: (i64, i64), to: (i64, i64), k: i64) -> u64 {
+    let Some((n, d0, d1)) = steps(from, to) else {
+        return 0;
+    };
+    if d0 > k || d1 > k {
+        return 0;
+    }
+    // paths touching y = x + k + 1 are in bijection with paths from the mirrored start
+    let bad = walks(c, n, d1 - (2 * (k + 1) - d0));
+    (walks(c, n, d1 - d0) + c.modulus() - bad) % c.modulus()
+}
+
+/// right/up paths staying within x + lo <= y <= x + hi, lo <= hi, by repeated reflection \
+/// O(n / (hi - lo + 2)) for paths of n steps
+pub fn paths_between(c: &mut Comb, from: (i64, i64), to: (i64, i64), lo: i64, hi: i64) -> u64 {
+    let Some((n, d0, d1)) = steps(from, to) else {
+        return 0;
+    };
+    if d0 < lo || d0 > hi || d1 < lo || d1 > hi {
+        return 0;
+    }
+    let (a, p) = (hi + 1, c.modulus());
+    // images of the start across y - x = hi + 1 and y - x = lo - 1 repeat with this period
+    let w = 2 * (hi - lo + 2);
+    let mut res = 0;
+    let kmax = n / w + 1;
+    for k in -kmax..=kmax {
+        res = (res + walks(c, n, d1 - d0 + k * w)) % p;
+        res = (res + p - walks(c, n, d1 - (2 * a - d0) + k * w)) % p;
+    }
+    res
+}
+
+/// orderings of a votes for A and b votes for B with A strictly ahead after every vote,
+/// (a - b) / (a + b) * C(a + b, a) for a > b, 1 for a = b = 0
+pub fn ballot(c: &mut Comb, a: usize, b: usize) -> u64 {
+    if a == 0 && b == 0 {
+        return 1;
+    }
+    // votes as steps, A strictly ahead is y <= x - 1, so the first vote is for A
+    paths_below(c, (1, 0), (a as i64, b as i64), -1)
+}
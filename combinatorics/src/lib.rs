@@ -0,0 +1,150 @@
+mod comb;
+mod lattice;
+mod partition;
+mod q_analog;
+mod stirling;
+
+pub use comb::{derangements, Comb};
+pub use lattice::{ballot, paths, paths_below, paths_between};
+pub use partition::partition_numbers;
+pub use q_analog::QComb;
+pub use stirling::{bell_numbers, stirling1_row, stirling2_row};
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        ballot, bell_numbers, derangements, partition_numbers, paths, paths_below, paths_between,
+        stirling1_row, stirling2_row, Comb, QComb,
+    };
+    use convolution::fps_inv;
+
+    // right/up paths through points where ok holds, by dp
+    fn naive(from: (i64, i64), to: (i64, i64), ok: impl Fn(i64, i64) -> bool) -> u64 {
+        if to.0 < from.0 || to.1 < from.1 {
+            return 0;
+        }
+        let (w, h) = ((to.0 - from.0 + 1) as usize, (to.1 - from.1 + 1) as usize);
+        let mut f = vec![vec![0u64; h + 1]; w + 1];
+        f[0][1] = 1;
+        for i in 1..=w {
+            for j in 1..=h {
+                if ok(from.0 + i as i64 - 1, from.1 + j as i64 - 1) {
+                    f[i][j] = f[i - 1][j] + f[i][j - 1];
+                }
+            }
+        }
+        f[w][h]
+    }
+
+    #[test]
+    fn lattice_test() {
+        let mut c = Comb::new(100, 1_000_000_007);
+        for (x0, y0, x1, y1) in [(0, 0, 5, 5), (-2, 1, 4, 3), (1, -3, 2, 5), (0, 0, 7, 2)] {
+            let (f, t) = ((x0, y0), (x1, y1));
+            assert_eq!(paths(&mut c, f, t), naive(f, t, |_, _| true));
+            for k in -3..4 {
+                assert_eq!(paths_below(&mut c, f, t, k), naive(f, t, |x, y| y <= x + k));
+                for hi in k..5 {
+                    let e = naive(f, t, |x, y| x + k <= y && y <= x + hi);
+                    assert_eq!(paths_between(&mut c, f, t, k, hi), e);
+                }
+            }
+        }
+        assert_eq!(paths(&mut c, (0, 0), (-1, 3)), 0);
+        // (a - b) / (a + b) * C(a + b, a)
+        assert_eq!(ballot(&mut c, 5, 3), 14);
+        assert_eq!(ballot(&mut c, 3, 3), 0);
+        assert_eq!((ballot(&mut c, 0, 0), ballot(&mut c, 4, 0)), (1, 1));
+    }
+
+    #[test]
+    fn q_comb_test() {
+        const P: u64 = 1_000_000_007;
+        for q in [2, 3, 7] {
+            let c = QComb::new(30, q, P);
+            // [n choose k]_q = [n-1 choose k-1]_q + q^k [n-1 choose k]_q
+            let mut g = vec![vec![0u64; 30]; 30];
+            for n in 0..30 {
+                g[n][0] = 1;
+                let mut qk = 1;
+                for k in 1..=n {
+                    qk = qk * q % P;
+                    g[n][k] = (g[n - 1][k - 1] + qk * g[n - 1][k]) % P;
+                }
+                for (k, &x) in g[n].iter().enumerate() {
+                    assert_eq!(c.binom(n, k), x);
+                }
+            }
+        }
+        let c = QComb::new(10, 2, P);
+        assert_eq!(
+            (c.binom(4, 2), c.subspaces(3), c.ordered_bases(3)),
+            (35, 16, 168)
+        );
+    }
+
+    #[test]
+    fn comb_test() {
+        let mut c = Comb::new(0, 13);
+        assert_eq!(
+            (c.binom(12, 5), c.perm(6, 3), c.inv(5)),
+            (792 % 13, 120 % 13, 8)
+        );
+        let mut c = Comb::new(4, 1_000_000_007);
+        let catalan: Vec<u64> = (0..10).map(|n| c.catalan(n)).collect();
+        assert_eq!(catalan, [1, 1, 2, 5, 14, 42, 132, 429, 1430, 4862]);
+        assert_eq!(c.multinomial(&[2, 3, 1]), 60);
+        assert_eq!(c.fact(20), 146326063);
+        let n = 1_000_000_000_000_000_000u128;
+        assert_eq!(
+            c.binom_big(n as u64, 2) as u128,
+            n * (n - 1) / 2 % 1_000_000_007
+        );
+        assert_eq!((c.binom_big(5, 6), c.binom_big(7, 7)), (0, 1));
+        assert_eq!(derangements(6, 1_000_000_007), [1, 0, 1, 2, 9, 44, 265]);
+        assert_eq!(derangements(0, 7), [1]);
+    }
+
+    #[test]
+    fn stirling_test() {
+        const P: u64 = 998244353;
+        // both triangles by their recurrences
+        let n = 150;
+        let mut s1 = vec![vec![0u64; n + 1]; n + 1];
+        let mut s2 = s1.clone();
+        (s1[0][0], s2[0][0]) = (1, 1);
+        for i in 1..=n {
+            for k in 1..=i {
+                s1[i][k] = (s1[i - 1][k - 1] + (i as u64 - 1) * s1[i - 1][k]) % P;
+                s2[i][k] = (s2[i - 1][k - 1] + k as u64 * s2[i - 1][k]) % P;
+            }
+        }
+        let bell = bell_numbers(n, P);
+        for i in (0..=n).step_by(7).chain([1, 2, 64, 127, 128]) {
+            assert_eq!(stirling1_row(i, P), s1[i][..=i]);
+            assert_eq!(stirling2_row(i, P), s2[i][..=i]);
+            assert_eq!(bell[i], s2[i].iter().fold(0, |acc, x| (acc + x) % P));
+        }
+        assert_eq!(bell[..6], [1, 1, 2, 5, 15, 52]);
+    }
+
+    #[test]
+    fn partition_test() {
+        const P: u64 = 998244353;
+        let n = 2000;
+        // 1 / prod (1 - x^k) as a series
+        let mut euler = vec![0u64; n + 1];
+        euler[0] = 1;
+        for k in 1..=n {
+            for i in (k..=n).rev() {
+                euler[i] = (euler[i] + P - euler[i - k]) % P;
+            }
+        }
+        assert_eq!(partition_numbers(n, P), fps_inv(&euler, n + 1, P));
+        assert_eq!(
+            partition_numbers(10, P)[..],
+            [1, 1, 2, 3, 5, 7, 11, 15, 22, 30, 42]
+        );
+        assert_eq!(partition_numbers(0, 1), [0]);
+    }
+}
@@ -0,0 +1,26 @@
+/// number of partitions p(i) mod m for i in 0..=n, m <= 2^63, O(n sqrt(n)) \
+/// pentagonal number theorem: p(i) = sum (-1)^(k + 1) p(i - k (3k -+ 1) / 2) over k >= 1
+pub fn partition_numbers(n: usize, m: u64) -> Vec<u64> {
+    let mut p = vec![0u64; n + 1];
+    p[0] = 1 % m;
+    for i in 1..=n {
+        let mut s = 0;
+        for k in 1.. {
+            let g = k * (3 * k - 1) / 2;
+            if g > i {
+                break;
+            }
+            let mut t = p[i - g];
+            if g + k <= i {
+                t = (t + p[i - g - k]) % m;
+            }
+            s = if k % 2 == 1 {
+                (s + t) % m
+            } else {
+                (s + m - t) % m
+            };
+        }
+        p[i] = s;
+    }
+    p
+}
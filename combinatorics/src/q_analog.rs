@@ -0,0 +1,70 @@
+use number_theory::pow_mod;
+
+/// q-factorials and Gaussian binomials modulo a prime p, for arguments below n \
+/// [k]_q = 1 + q + ... + q^(k - 1) must be nonzero mod p for 1 <= k < n
+pub struct QComb {
+    p: u64,
+    q: u64,
+    fact: Vec<u64>,
+    inv_fact: Vec<u64>,
+}
+
+impl QComb {
+    pub fn new(n: usize, q: u64, p: u64) -> Self {
+        let (n, q) = (n.max(1), q % p);
+        // qint[i] = [i]_q
+        let mut qint = vec![0; n];
+        let mut qk = 1 % p;
+        for i in 1..n {
+            qint[i] = (qint[i - 1] + qk) % p;
+            qk = qk * q % p;
+            assert!(qint[i] != 0, "[{i}]_q vanishes mod p!");
+        }
+        let mut fact = vec![1 % p; n];
+        for i in 1..n {
+            fact[i] = fact[i - 1] * qint[i] % p;
+        }
+        let mut inv_fact = vec![1 % p; n];
+        inv_fact[n - 1] = pow_mod(fact[n - 1], p - 2, p);
+        for i in (1..n).rev() {
+            inv_fact[i - 1] = inv_fact[i] * qint[i] % p;
+        }
+        Self {
+            p,
+            q,
+            fact,
+            inv_fact,
+        }
+    }
+
+    /// [n]_q!
+    pub fn fact(&self, n: usize) -> u64 {
+        self.fact[n]
+    }
+
+    /// Gaussian binomial [n choose k]_q mod p, 0 if k > n \
+    /// for a prime power q, the number of k-dimensional subspaces of F_q^n
+    pub fn binom(&self, n: usize, k: usize) -> u64 {
+        if k > n {
+            return 0;
+        }
+        self.fact[n] * self.inv_fact[k] % self.p * self.inv_fact[n - k] % self.p
+    }
+
+    /// number of subspaces of F_q^n of any dimension, the Galois number
+    pub fn subspaces(&self, n: usize) -> u64 {
+        (0..=n).fold(0, |acc, k| (acc + self.binom(n, k)) % self.p)
+    }
+
+    /// number of ordered bases of F_q^n, the order of GL(n, q), (q^n - 1)(q^n - q)...(q^n - q^(n-1))
+    pub fn ordered_bases(&self, n: usize) -> u64 {
+        let qn = pow_mod(self.q, n as u64, self.p);
+        let mut qi = 1 % self.p;
+        let mut res = 1 % self.p;
+        for _ in 0..n {
+            res = res * ((qn + self.p - qi) % self.p) % self.p;
+            qi = qi * self.q % self.p;
+        }
+        res
+    }
+}
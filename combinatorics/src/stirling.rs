@@ -0,0 +1,59 @@
+use convolution::{convolution_mod, fps_exp, taylor_shift};
+use number_theory::pow_mod;
+
+use crate::Comb;
+
+/// unsigned Stirling numbers of the first kind c(n, k) for k in 0..=n, n < p \
+/// coefficients of x (x + 1) ... (x + n - 1), doubled by a Taylor shift in O(n log n)
+pub fn stirling1_row(n: usize, p: u64) -> Vec<u64> {
+    if n == 0 {
+        return vec![1 % p];
+    }
+    // f = x (x + 1) ... (x + m - 1) over the bits of n from the top
+    let mut f = vec![0, 1 % p];
+    let mut m = 1;
+    for bit in (0..usize::BITS - 1 - n.leading_zeros()).rev() {
+        f = convolution_mod(&f, &taylor_shift(&f, m as u64, p), p);
+        m *= 2;
+        if n >> bit & 1 == 1 {
+            f = convolution_mod(&f, &[m as u64 % p, 1], p);
+            m += 1;
+        }
+    }
+    f
+}
+
+/// Stirling numbers of the second kind S(n, k) for k in 0..=n, n < p, O(n log n) \
+/// S(n, k) = sum (-1)^i / i! * (k - i)^n / (k - i)!
+pub fn stirling2_row(n: usize, p: u64) -> Vec<u64> {
+    let mut c = Comb::new(n + 1, p);
+    let a: Vec<u64> = (0..=n)
+        .map(|i| {
+            let x = c.inv_fact(i);
+            if i % 2 == 1 {
+                (p - x) % p
+            } else {
+                x
+            }
+        })
+        .collect();
+    let b: Vec<u64> = (0..=n)
+        .map(|j| pow_mod(j as u64, n as u64, p) * c.inv_fact(j) % p)
+        .collect();
+    let mut s = convolution_mod(&a, &b, p);
+    s.truncate(n + 1);
+    s
+}
+
+/// Bell numbers B(i) for i in 0..=n, n < p \
+/// i! [x^i] exp(e^x - 1) in O(n log n)
+pub fn bell_numbers(n: usize, p: u64) -> Vec<u64> {
+    let mut c = Comb::new(n + 1, p);
+    let mut a: Vec<u64> = (0..=n).map(|i| c.inv_fact(i)).collect();
+    a[0] = 0;
+    fps_exp(&a, n + 1, p)
+        .into_iter()
+        .enumerate()
+        .map(|(i, x)| x * c.fact(i) % p)
+        .collect()
+}
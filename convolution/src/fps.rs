@@ -0,0 +1,185 @@
+use crate::{convolution_mod, pow_mod};
+
+/// a * b mod x^n
+fn mul_trunc(a: &[u64], b: &[u64], n: usize, p: u64) -> Vec<u64> {
+    let a = &a[..a.len().min(n)];
+    let b = &b[..b.len().min(n)];
+    let mut c = convolution_mod(a, b, p);
+    c.resize(n, 0);
+    c
+}
+
+/// 1 / a mod x^n, a\[0\] must be nonzero \
+/// the series here have coefficients mod a prime p that `convolution_mod` accepts
+pub fn fps_inv(a: &[u64], n: usize, p: u64) -> Vec<u64> {
+    assert!(
+        a.first().is_some_and(|&x| !x.is_multiple_of(p)),
+        "a[0] is not invertible!"
+    );
+    let mut b = vec![pow_mod(a[0], p - 2, p)];
+    let mut m = 1;
+    while m < n {
+        m *= 2;
+        // b = b * (2 - a * b)
+        let mut t = mul_trunc(a, &b, m, p);
+        for x in t.iter_mut() {
+            *x = (p - *x) % p;
+        }
+        t[0] = (t[0] + 2) % p;
+        b = mul_trunc(&b, &t, m, p);
+    }
+    b.truncate(n);
+    b
+}
+
+/// log a mod x^n, a\[0\] must be 1 and n <= p
+pub fn fps_log(a: &[u64], n: usize, p: u64) -> Vec<u64> {
+    assert!(a.first().is_some_and(|&x| x % p == 1), "a[0] must be 1!");
+    if n == 0 {
+        return vec![];
+    }
+    // log a = integral of a' / a, inverses of 1..n by inv[i] = -(p / i) * inv[p % i]
+    let da: Vec<u64> = (1..a.len()).map(|i| a[i] % p * i as u64 % p).collect();
+    let q = mul_trunc(&da, &fps_inv(a, n, p), n - 1, p);
+    let mut inv = vec![1u64; n];
+    for i in 2..n {
+        inv[i] = (p - p / i as u64) * inv[(p % i as u64) as usize] % p;
+    }
+    let mut res = vec![0];
+    res.extend(q.iter().zip(&inv[1..]).map(|(x, y)| x * y % p));
+    res
+}
+
+/// exp a mod x^n, a\[0\] must be 0 and n <= p
+pub fn fps_exp(a: &[u64], n: usize, p: u64) -> Vec<u64> {
+    assert!(
+        a.first().is_none_or(|x| x.is_multiple_of(p)),
+        "a[0] must be 0!"
+    );
+    let mut b = vec![1 % p];
+    let mut m = 1;
+    while m < n {
+        m *= 2;
+        // b = b * (1 - log b + a)
+        let mut t = fps_log(&b, m, p);
+        for (i, x) in t.iter_mut().enumerate() {
+            *x = (p - *x + a.get(i).map_or(0, |y| y % p)) % p;
+        }
+        t[0] = (t[0] + 1) % p;
+        b = mul_trunc(&b, &t, m, p);
+    }
+    b.resize(n, 0);
+    b
+}
+
+/// f(x + c), needs f.len() <= p, O(n log n)
+pub fn taylor_shift(f: &[u64], c: u64, p: u64) -> Vec<u64> {
+    let n = f.len();
+    if n == 0 {
+        return vec![];
+    }
+    let mut fact = vec![1u64; n];
+    for i in 1..n {
+        fact[i] = fact[i - 1] * i as u64 % p;
+    }
+    let mut inv_fact = vec![pow_mod(fact[n - 1], p - 2, p); n];
+    for i in (1..n).rev() {
+        inv_fact[i - 1] = inv_fact[i] * i as u64 % p;
+    }
+    // g[i] * i! = sum_k f[i + k] (i + k)! * c^k / k!
+    let a: Vec<u64> = (0..n).rev().map(|j| f[j] % p * fact[j] % p).collect();
+    let mut ck = 1;
+    let b: Vec<u64> = (0..n)
+        .map(|k| {
+            let res = ck * inv_fact[k] % p;
+            ck = ck * (c % p) % p;
+            res
+        })
+        .collect();
+    let s = convolution_mod(&a, &b, p);
+    (0..n).map(|i| s[n - 1 - i] * inv_fact[i] % p).collect()
+}
+
+/// f(g(x)) mod x^n, baby-step giant-step in O(n |f| + sqrt(|f|) n log n)
+pub fn compose(f: &[u64], g: &[u64], n: usize, p: u64) -> Vec<u64> {
+    if n == 0 || f.is_empty() {
+        return vec![0; n];
+    }
+    let k = (f.len() as f64).sqrt().ceil() as usize;
+    // gp[r] = g^r for r <= k
+    let mut gp = vec![vec![0; n]];
+    gp[0][0] = 1;
+    for r in 1..=k {
+        let t = mul_trunc(&gp[r - 1], g, n, p);
+        gp.push(t);
+    }
+    let gk = gp.pop().unwrap();
+    // Horner over the blocks f[ik..ik + k] in g^k
+    let mut res = vec![0; n];
+    for blk in f.chunks(k).rev() {
+        res = mul_trunc(&res, &gk, n, p);
+        for (&c, q) in blk.iter().zip(&gp) {
+            let c = c % p;
+            for (x, &y) in res.iter_mut().zip(q) {
+                *x = (*x + c * y) % p;
+            }
+        }
+    }
+    res
+}
+
+/// h with g(h(x)) = h(g(x)) = x mod x^n, needs g\[0\] = 0 and g\[1\] != 0 \
+/// Newton iteration on compose, O(n^2)
+pub fn compositional_inverse(g: &[u64], n: usize, p: u64) -> Vec<u64> {
+    assert!(
+        g.len() >= 2 && g[0].is_multiple_of(p) && !g[1].is_multiple_of(p),
+        "g has no compositional inverse!"
+    );
+    let dg: Vec<u64> = (1..g.len()).map(|i| g[i] % p * i as u64 % p).collect();
+    let mut h = vec![0, pow_mod(g[1], p - 2, p)];
+    let mut m = 2;
+    while m < n {
+        m *= 2;
+        // h = h - (g(h) - x) / g'(h)
+        let mut e = compose(g, &h, m, p);
+        e[1] = (e[1] + p - 1) % p;
+        let d = compose(&dg, &h, m, p);
+        let t = mul_trunc(&e, &fps_inv(&d, m, p), m, p);
+        h.resize(m, 0);
+        for (x, y) in h.iter_mut().zip(t) {
+            *x = (*x + p - y) % p;
+        }
+    }
+    h.resize(n, 0);
+    h
+}
+
+/// res\[j\] = sum_i w\[i\] * \[x^i\] g^j for j < m, transposed baby-step giant-step \
+/// O(|w| m + sqrt(m) |w| log |w|)
+pub fn power_projection(w: &[u64], g: &[u64], m: usize, p: u64) -> Vec<u64> {
+    let n = w.len();
+    if n == 0 || m == 0 {
+        return vec![0; m];
+    }
+    let k = (m as f64).sqrt().ceil() as usize;
+    let mut gp = vec![vec![0; n]];
+    gp[0][0] = 1;
+    for r in 1..=k {
+        let t = mul_trunc(&gp[r - 1], g, n, p);
+        gp.push(t);
+    }
+    let gk = gp.pop().unwrap();
+    let wr: Vec<u64> = w.iter().rev().map(|x| x % p).collect();
+    let mut res = vec![0; m];
+    let mut gi = gp[0].clone();
+    for i in 0..m.div_ceil(k) {
+        // u[s] = sum_t w[t] * [x^(t - s)] g^(ik)
+        let c = convolution_mod(&wr, &gi, p);
+        let u: Vec<u64> = (0..n).map(|s| c[n - 1 - s]).collect();
+        for (r, q) in gp.iter().enumerate().take(m - i * k) {
+            res[i * k + r] = u.iter().zip(q).fold(0, |acc, (&x, &y)| (acc + x * y) % p);
+        }
+        gi = mul_trunc(&gi, &gk, n, p);
+    }
+    res
+}
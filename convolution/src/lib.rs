@@ -0,0 +1,460 @@
+use std::f64::consts::PI;
+use std::ops::{Add, Mul, Sub};
+
+mod fps;
+mod subset;
+
+pub use fps::{
+    compose, compositional_inverse, fps_exp, fps_inv, fps_log, power_projection, taylor_shift,
+};
+pub use subset::{subset_convolution, subset_mobius, subset_zeta, superset_mobius, superset_zeta};
+
+fn pow_mod(mut a: u64, mut e: u64, p: u64) -> u64 {
+    let mut res = 1;
+    a %= p;
+    while e > 0 {
+        if e & 1 == 1 {
+            res = res * a % p;
+        }
+        a = a * a % p;
+        e >>= 1;
+    }
+    res
+}
+
+fn primitive_root(p: u64) -> u64 {
+    let mut fs = vec![];
+    let mut x = p - 1;
+    let mut d = 2;
+    while d * d <= x {
+        if x.is_multiple_of(d) {
+            fs.push(d);
+            while x.is_multiple_of(d) {
+                x /= d;
+            }
+        }
+        d += 1;
+    }
+    if x > 1 {
+        fs.push(x);
+    }
+    (2..)
+        .find(|&g| fs.iter().all(|&f| pow_mod(g, (p - 1) / f, p) != 1))
+        .unwrap()
+}
+
+fn ntt(a: &mut [u64], invert: bool, p: u64, g: u64) {
+    let n = a.len();
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j ^= bit;
+        if i < j {
+            a.swap(i, j);
+        }
+    }
+    let mut len = 2;
+    while len <= n {
+        let mut w = pow_mod(g, (p - 1) / len as u64, p);
+        if invert {
+            w = pow_mod(w, p - 2, p);
+        }
+        let mut ws = vec![1u64; len / 2];
+        for k in 1..len / 2 {
+            ws[k] = ws[k - 1] * w % p;
+        }
+        for i in (0..n).step_by(len) {
+            for k in 0..len / 2 {
+                let u = a[i + k];
+                let v = a[i + k + len / 2] * ws[k] % p;
+                a[i + k] = if u + v >= p { u + v - p } else { u + v };
+                a[i + k + len / 2] = if u >= v { u - v } else { u + p - v };
+            }
+        }
+        len <<= 1;
+    }
+    if invert {
+        let inv = pow_mod(n as u64, p - 2, p);
+        for x in a.iter_mut() {
+            *x = *x * inv % p;
+        }
+    }
+}
+
+/// c[k] = sum a[i] * b[k - i] mod p \
+/// p must be a prime below 2^32 with 2^t | p - 1 for 2^t >= |a| + |b| - 1,
+/// like 998244353
+pub fn convolution_mod(a: &[u64], b: &[u64], p: u64) -> Vec<u64> {
+    if a.is_empty() || b.is_empty() {
+        return vec![];
+    }
+    let len = a.len() + b.len() - 1;
+    if a.len().min(b.len()) <= 60 {
+        let mut c = vec![0u64; len];
+        for (i, &x) in a.iter().enumerate() {
+            for (j, &y) in b.iter().enumerate() {
+                c[i + j] = (c[i + j] + x % p * (y % p)) % p;
+            }
+        }
+        return c;
+    }
+    let n = len.next_power_of_two();
+    assert!(
+        (p - 1).is_multiple_of(n as u64),
+        "modulus does not support this length!"
+    );
+    let g = primitive_root(p);
+    let mut fa: Vec<u64> = a.iter().map(|x| x % p).collect();
+    let mut fb: Vec<u64> = b.iter().map(|x| x % p).collect();
+    fa.resize(n, 0);
+    fb.resize(n, 0);
+    ntt(&mut fa, false, p, g);
+    ntt(&mut fb, false, p, g);
+    for (x, y) in fa.iter_mut().zip(&fb) {
+        *x = *x * y % p;
+    }
+    ntt(&mut fa, true, p, g);
+    fa.truncate(len);
+    fa
+}
+
+#[derive(Clone, Copy, Default)]
+struct Complex {
+    re: f64,
+    im: f64,
+}
+
+impl Add for Complex {
+    type Output = Self;
+    fn add(self, o: Self) -> Self {
+        Self {
+            re: self.re + o.re,
+            im: self.im + o.im,
+        }
+    }
+}
+
+impl Sub for Complex {
+    type Output = Self;
+    fn sub(self, o: Self) -> Self {
+        Self {
+            re: self.re - o.re,
+            im: self.im - o.im,
+        }
+    }
+}
+
+impl Mul for Complex {
+    type Output = Self;
+    fn mul(self, o: Self) -> Self {
+        Self {
+            re: self.re * o.re - self.im * o.im,
+            im: self.re * o.im + self.im * o.re,
+        }
+    }
+}
+
+fn fft(a: &mut [Complex], invert: bool) {
+    let n = a.len();
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j ^= bit;
+        if i < j {
+            a.swap(i, j);
+        }
+    }
+    let mut len = 2;
+    while len <= n {
+        let ang = 2.0 * PI / len as f64 * if invert { -1.0 } else { 1.0 };
+        // roots computed directly, repeated multiplication loses precision
+        let ws: Vec<Complex> = (0..len / 2)
+            .map(|k| Complex {
+                re: (ang * k as f64).cos(),
+                im: (ang * k as f64).sin(),
+            })
+            .collect();
+        for i in (0..n).step_by(len) {
+            for k in 0..len / 2 {
+                let u = a[i + k];
+                let v = a[i + k + len / 2] * ws[k];
+                a[i + k] = u + v;
+                a[i + k + len / 2] = u - v;
+            }
+        }
+        len <<= 1;
+    }
+    if invert {
+        for x in a.iter_mut() {
+            x.re /= n as f64;
+            x.im /= n as f64;
+        }
+    }
+}
+
+/// c[k] = sum a[i] * b[k - i] by a complex FFT \
+/// the error grows with the magnitude of the results, round when the inputs are integers
+pub fn convolution_f64(a: &[f64], b: &[f64]) -> Vec<f64> {
+    if a.is_empty() || b.is_empty() {
+        return vec![];
+    }
+    let len = a.len() + b.len() - 1;
+    let n = len.next_power_of_two();
+    // a in the real part, b in the imaginary part, then (a + bi)^2 = a^2 - b^2 + 2abi
+    let mut f = vec![Complex::default(); n];
+    for (i, &x) in a.iter().enumerate() {
+        f[i].re = x;
+    }
+    for (i, &y) in b.iter().enumerate() {
+        f[i].im = y;
+    }
+    fft(&mut f, false);
+    for x in f.iter_mut() {
+        *x = *x * *x;
+    }
+    fft(&mut f, true);
+    f[..len].iter().map(|x| x.im / 2.0).collect()
+}
+
+const M1: u64 = 754974721; // 2^24
+const M2: u64 = 167772161; // 2^25
+const M3: u64 = 469762049; // 2^26
+
+/// c[k] = sum a[i] * b[k - i] mod m for any 1 <= m < 2^32, |a| + |b| - 1 <= 2^24 \
+/// NTT over three primes combined by Garner, so every exact c[k] must stay below M1 * M2 * M3 ~ 2^85.6: \
+/// panics unless min(|a|, |b|) * (m - 1)^2 < M1 * M2 * M3, e.g. min(|a|, |b|) <= 3225600 for m = 2^32 - 1
+pub fn convolution_arbitrary_mod(a: &[u64], b: &[u64], m: u64) -> Vec<u64> {
+    assert!((1..1 << 32).contains(&m), "modulus out of range!");
+    if a.is_empty() || b.is_empty() {
+        return vec![];
+    }
+    assert!(
+        a.len().min(b.len()) as u128 * ((m - 1) as u128).pow(2)
+            < M1 as u128 * M2 as u128 * M3 as u128,
+        "results may exceed M1 * M2 * M3!"
+    );
+    let a: Vec<u64> = a.iter().map(|x| x % m).collect();
+    let b: Vec<u64> = b.iter().map(|x| x % m).collect();
+    let c1 = convolution_mod(&a, &b, M1);
+    let c2 = convolution_mod(&a, &b, M2);
+    let c3 = convolution_mod(&a, &b, M3);
+    let i1 = pow_mod(M1, M2 - 2, M2);
+    let i12 = pow_mod(M1 * M2 % M3, M3 - 2, M3);
+    let m12 = (M1 * M2 % m) as u128;
+    (0..c1.len())
+        .map(|i| {
+            // c = t1 + t2 * M1 + t3 * M1 * M2 with t1 < M1, t2 < M2, t3 < M3
+            let t1 = c1[i];
+            let t2 = (c2[i] + M2 - t1 % M2) * i1 % M2;
+            let t3 = (c3[i] + M3 - (t1 + t2 * M1) % M3) * i12 % M3;
+            ((t1 as u128 + t2 as u128 * M1 as u128 + t3 as u128 * m12) % m as u128) as u64
+        })
+        .collect()
+}
+
+/// c[k] = sum a[i] * b[k - i], exact as long as every c[k] fits in i64 \
+/// uses a floating point FFT when the results are small enough,
+/// otherwise NTT over three primes combined by CRT
+pub fn convolution_i64(a: &[i64], b: &[i64]) -> Vec<i64> {
+    if a.is_empty() || b.is_empty() {
+        return vec![];
+    }
+    let ma = a
+        .iter()
+        .map(|x| x.unsigned_abs() as f64)
+        .fold(0.0, f64::max);
+    let mb = b
+        .iter()
+        .map(|x| x.unsigned_abs() as f64)
+        .fold(0.0, f64::max);
+    // the (a + bi)^2 trick also accumulates a^2 and b^2 terms, so bound by the larger side
+    let m = ma.max(mb);
+    if m * m * (a.len().max(b.len()) as f64) < 1e10 {
+        let fa: Vec<f64> = a.iter().map(|&x| x as f64).collect();
+        let fb: Vec<f64> = b.iter().map(|&x| x as f64).collect();
+        return convolution_f64(&fa, &fb)
+            .into_iter()
+            .map(|x| x.round() as i64)
+            .collect();
+    }
+
+    const M2M3: u64 = M2 * M3;
+    const M1M3: u64 = M1 * M3;
+    const M1M2: u64 = M1 * M2;
+    const M1M2M3: u64 = M1M2.wrapping_mul(M3);
+    let i1 = pow_mod(M2M3 % M1, M1 - 2, M1);
+    let i2 = pow_mod(M1M3 % M2, M2 - 2, M2);
+    let i3 = pow_mod(M1M2 % M3, M3 - 2, M3);
+
+    let reduce = |v: &[i64], p: u64| -> Vec<u64> {
+        v.iter().map(|&x| x.rem_euclid(p as i64) as u64).collect()
+    };
+    let c1 = convolution_mod(&reduce(a, M1), &reduce(b, M1), M1);
+    let c2 = convolution_mod(&reduce(a, M2), &reduce(b, M2), M2);
+    let c3 = convolution_mod(&reduce(a, M3), &reduce(b, M3), M3);
+
+    // x below is the answer plus k * M1M2M3 for some k in [0, 3),
+    // the residue mod M1 tells k apart since M1M2M3 mod M1 = 0 and 2^64 mod M1 != 0
+    const OFFSET: [u64; 5] = [0, 0, M1M2M3, M1M2M3.wrapping_mul(2), M1M2M3.wrapping_mul(3)];
+    (0..c1.len())
+        .map(|i| {
+            let mut x = 0u64;
+            x = x.wrapping_add((c1[i] * i1 % M1).wrapping_mul(M2M3));
+            x = x.wrapping_add((c2[i] * i2 % M2).wrapping_mul(M1M3));
+            x = x.wrapping_add((c3[i] * i3 % M3).wrapping_mul(M1M2));
+            let r = (x as i64).rem_euclid(M1 as i64) as u64;
+            let diff = (c1[i] + M1 - r) % M1;
+            x = x.wrapping_sub(OFFSET[(diff % 5) as usize]);
+            x as i64
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        compose, compositional_inverse, convolution_arbitrary_mod, convolution_f64,
+        convolution_i64, convolution_mod, power_projection, subset_convolution, subset_mobius,
+        subset_zeta, superset_mobius, superset_zeta,
+    };
+    use random::Random;
+
+    #[test]
+    fn fps_test() {
+        const P: u64 = 998244353;
+        let f: Vec<u64> = (0..50).map(|i| i * i + 7).collect();
+        let g: Vec<u64> = (0..80).map(|i| (i * 31 + 3) % 17 + 1).collect();
+        let n = 70;
+        let mut exp = vec![0; n];
+        let mut gj = vec![0; n];
+        gj[0] = 1;
+        let mut proj = vec![];
+        for &c in f.iter() {
+            for (x, y) in exp.iter_mut().zip(&gj) {
+                *x = (*x + c * y) % P;
+            }
+            proj.push(f.iter().zip(&gj).fold(0, |acc, (x, y)| (acc + x * y) % P));
+            gj = convolution_mod(&gj, &g, P);
+            gj.truncate(n);
+        }
+        assert_eq!(compose(&f, &g, n, P), exp);
+        assert_eq!(power_projection(&f, &g, f.len(), P), proj);
+        let mut g = g;
+        g[0] = 0;
+        let h = compositional_inverse(&g, n, P);
+        let mut x = vec![0; n];
+        x[1] = 1;
+        assert_eq!(compose(&g, &h, n, P), x);
+    }
+
+    #[test]
+    fn arbitrary_mod_test() {
+        let a: Vec<u64> = (0..300u64).map(|i| i.pow(5) ^ 0xdead_beef).collect();
+        let b: Vec<u64> = (0..200u64).map(|i| u64::MAX - i * i * 12345).collect();
+        for m in [1, 2, 1_000_000_007, (1 << 32) - 1] {
+            let mut c = vec![0u64; 499];
+            for (i, x) in a.iter().enumerate() {
+                for (j, y) in b.iter().enumerate() {
+                    c[i + j] =
+                        ((c[i + j] as u128 + (x % m) as u128 * (y % m) as u128) % m as u128) as u64;
+                }
+            }
+            assert_eq!(convolution_arbitrary_mod(&a, &b, m), c);
+        }
+        let c = convolution_f64(&[0.5, 1.0], &[2.0, -1.0, 4.0]);
+        let e = [1.0, 1.5, 1.0, 4.0];
+        assert!(c.iter().zip(e).all(|(x, y)| (x - y).abs() < 1e-9));
+    }
+
+    #[test]
+    fn arbitrary_mod_bound_test() {
+        // the largest all-(m - 1) input whose exact results still fit below M1 * M2 * M3
+        let m = (1 << 32) - 1;
+        let n = 3225600;
+        let a = vec![m - 1; n];
+        let c = convolution_arbitrary_mod(&a, &a, m);
+        // (m - 1)^2 = 1 mod m, so c[k] is the number of terms
+        assert!(c
+            .iter()
+            .enumerate()
+            .all(|(k, &x)| x == (k + 1).min(2 * n - 1 - k) as u64));
+    }
+
+    #[test]
+    #[should_panic]
+    fn arbitrary_mod_overflow_test() {
+        let m = (1 << 32) - 1;
+        let a = vec![m - 1; 3225601];
+        convolution_arbitrary_mod(&a, &a, m);
+    }
+
+    #[test]
+    fn subset_test() {
+        let n = 6;
+        let a: Vec<i64> = (0..1 << n).map(|i| (i * 37 % 11) - 5).collect();
+        let b: Vec<i64> = (0..1 << n).map(|i| (i * i % 13) - 6).collect();
+        let mut z = a.clone();
+        subset_zeta(&mut z);
+        let mut sz = a.clone();
+        superset_zeta(&mut sz);
+        for s in 0..1 << n {
+            let sub: i64 = (0..1 << n).filter(|t| t & s == *t).map(|t| a[t]).sum();
+            let sup: i64 = (0..1 << n).filter(|t| t & s == s).map(|t| a[t]).sum();
+            assert_eq!((z[s], sz[s]), (sub, sup));
+        }
+        subset_mobius(&mut z);
+        superset_mobius(&mut sz);
+        assert_eq!((&z, &sz), (&a, &a));
+        let c = subset_convolution(&a, &b);
+        for s in 0..1 << n {
+            let e: i64 = (0..1 << n)
+                .filter(|t| t & s == *t)
+                .map(|t| a[t] * b[s ^ t])
+                .sum();
+            assert_eq!(c[s], e);
+        }
+        assert_eq!(subset_convolution(&[3], &[4]), [12]);
+    }
+
+    #[test]
+    fn convolution_i64_test() {
+        let naive = |a: &[i64], b: &[i64]| {
+            let mut c = vec![0i64; a.len() + b.len() - 1];
+            for (i, &x) in a.iter().enumerate() {
+                for (j, &y) in b.iter().enumerate() {
+                    c[i + j] += x * y;
+                }
+            }
+            c
+        };
+        assert_eq!(
+            convolution_i64(&[1_000_000_000; 5], &[1; 5]),
+            naive(&[1_000_000_000; 5], &[1; 5])
+        );
+        let mut rd = Random::new(7);
+        for _ in 0..200 {
+            let (n, m) = (
+                rd.next_bounds(1, 40) as usize,
+                rd.next_bounds(1, 40) as usize,
+            );
+            // skewed magnitudes: one side may be huge while the other is tiny
+            let ea = rd.next(50);
+            let (ba, bb) = (1u64 << ea, 1u64 << rd.next(50 - ea));
+            let a: Vec<i64> = (0..n)
+                .map(|_| rd.next(2 * ba + 1) as i64 - ba as i64)
+                .collect();
+            let b: Vec<i64> = (0..m)
+                .map(|_| rd.next(2 * bb + 1) as i64 - bb as i64)
+                .collect();
+            assert_eq!(convolution_i64(&a, &b), naive(&a, &b));
+        }
+    }
+}
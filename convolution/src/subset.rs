@@ -0,0 +1,114 @@
+use std::ops::{Add, Mul, Sub};
+
+fn check_len(n: usize) {
+    assert!(n.is_power_of_two(), "length must be a power of two!");
+}
+
+/// a[S] becomes the sum of a[T] over T subset of S
+pub fn subset_zeta<T>(a: &mut [T])
+where
+    T: Copy + Add<Output = T>,
+{
+    check_len(a.len());
+    let mut w = 1;
+    while w < a.len() {
+        for s in 0..a.len() {
+            if s & w != 0 {
+                a[s] = a[s] + a[s ^ w];
+            }
+        }
+        w <<= 1;
+    }
+}
+
+/// inverse of subset_zeta
+pub fn subset_mobius<T>(a: &mut [T])
+where
+    T: Copy + Sub<Output = T>,
+{
+    check_len(a.len());
+    let mut w = 1;
+    while w < a.len() {
+        for s in 0..a.len() {
+            if s & w != 0 {
+                a[s] = a[s] - a[s ^ w];
+            }
+        }
+        w <<= 1;
+    }
+}
+
+/// a[S] becomes the sum of a[T] over T superset of S
+pub fn superset_zeta<T>(a: &mut [T])
+where
+    T: Copy + Add<Output = T>,
+{
+    check_len(a.len());
+    let mut w = 1;
+    while w < a.len() {
+        for s in 0..a.len() {
+            if s & w == 0 {
+                a[s] = a[s] + a[s | w];
+            }
+        }
+        w <<= 1;
+    }
+}
+
+/// inverse of superset_zeta
+pub fn superset_mobius<T>(a: &mut [T])
+where
+    T: Copy + Sub<Output = T>,
+{
+    check_len(a.len());
+    let mut w = 1;
+    while w < a.len() {
+        for s in 0..a.len() {
+            if s & w == 0 {
+                a[s] = a[s] - a[s | w];
+            }
+        }
+        w <<= 1;
+    }
+}
+
+/// c[S] = sum a[T] * b[S \ T] over T subset of S, |a| = |b| = 2^n, O(2^n n^2) \
+/// ranked zeta transforms, so the default value of T must be zero
+pub fn subset_convolution<T>(a: &[T], b: &[T]) -> Vec<T>
+where
+    T: Copy + Default + Add<Output = T> + Sub<Output = T> + Mul<Output = T>,
+{
+    assert_eq!(a.len(), b.len(), "lengths differ!");
+    check_len(a.len());
+    let len = a.len();
+    let n = len.trailing_zeros() as usize;
+    // f[k][S] is a[S] if |S| = k, transformed by subset_zeta
+    let ranked = |a: &[T]| {
+        let mut f = vec![vec![T::default(); len]; n + 1];
+        for (s, &x) in a.iter().enumerate() {
+            f[s.count_ones() as usize][s] = x;
+        }
+        for fk in f.iter_mut() {
+            subset_zeta(fk);
+        }
+        f
+    };
+    let (fa, fb) = (ranked(a), ranked(b));
+    let mut c = vec![T::default(); len];
+    let mut h = vec![T::default(); len];
+    for k in 0..=n {
+        h.fill(T::default());
+        for i in 0..=k {
+            for (s, x) in h.iter_mut().enumerate() {
+                *x = *x + fa[i][s] * fb[k - i][s];
+            }
+        }
+        subset_mobius(&mut h);
+        for (s, x) in c.iter_mut().enumerate() {
+            if s.count_ones() as usize == k {
+                *x = h[s];
+            }
+        }
+    }
+    c
+}
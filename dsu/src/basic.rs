@@ -0,0 +1,77 @@
+/// disjoint set union with path compression and union by size
+#[derive(Clone, Debug)]
+pub struct Dsu {
+    fa: Vec<usize>,
+    size: Vec<usize>,
+}
+
+impl Dsu {
+    pub fn new(n: usize) -> Self {
+        Self {
+            fa: (0..n).collect(),
+            size: vec![1; n],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.fa.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fa.is_empty()
+    }
+
+    pub fn leader(&mut self, x: usize) -> usize {
+        let mut r = x;
+        while self.fa[r] != r {
+            r = self.fa[r];
+        }
+        let mut x = x;
+        while self.fa[x] != r {
+            let nxt = self.fa[x];
+            self.fa[x] = r;
+            x = nxt;
+        }
+        r
+    }
+
+    pub fn same(&mut self, a: usize, b: usize) -> bool {
+        self.leader(a) == self.leader(b)
+    }
+
+    pub fn size(&mut self, x: usize) -> usize {
+        let x = self.leader(x);
+        self.size[x]
+    }
+
+    /// return the new leader
+    pub fn merge(&mut self, a: usize, b: usize) -> usize {
+        let (mut a, mut b) = (self.leader(a), self.leader(b));
+        if a == b {
+            return a;
+        }
+        if self.size[a] < self.size[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        self.fa[b] = a;
+        self.size[a] += self.size[b];
+        a
+    }
+
+    /// members of every component in increasing order,
+    /// components ordered by their smallest member
+    pub fn groups(&mut self) -> Vec<Vec<usize>> {
+        let n = self.len();
+        let mut id = vec![usize::MAX; n];
+        let mut res: Vec<Vec<usize>> = vec![];
+        for x in 0..n {
+            let r = self.leader(x);
+            if id[r] == usize::MAX {
+                id[r] = res.len();
+                res.push(Vec::with_capacity(self.size[r]));
+            }
+            res[id[r]].push(x);
+        }
+        res
+    }
+}
@@ -0,0 +1,231 @@
+pub trait Commutative {
+    type S: Clone + Copy;
+    fn operation(a: Self::S, b: Self::S) -> Self::S;
+}
+
+use std::cmp::{max, min};
+use std::convert::Infallible;
+use std::marker::PhantomData;
+use std::ops::{Add, BitXor};
+
+mod basic;
+mod rollback;
+
+pub use basic::Dsu;
+pub use rollback::RollbackDsu;
+
+pub struct Additive<S>(Infallible, PhantomData<fn() -> S>);
+impl<S> Commutative for Additive<S>
+where
+    S: Copy + Add<Output = S>,
+{
+    type S = S;
+    fn operation(a: Self::S, b: Self::S) -> Self::S {
+        a + b
+    }
+}
+
+pub struct Max<S>(Infallible, PhantomData<fn() -> S>);
+impl<S> Commutative for Max<S>
+where
+    S: Copy + Ord,
+{
+    type S = S;
+    fn operation(a: Self::S, b: Self::S) -> Self::S {
+        max(a, b)
+    }
+}
+
+pub struct Min<S>(Infallible, PhantomData<fn() -> S>);
+impl<S> Commutative for Min<S>
+where
+    S: Copy + Ord,
+{
+    type S = S;
+    fn operation(a: Self::S, b: Self::S) -> Self::S {
+        min(a, b)
+    }
+}
+
+pub struct Xor<S>(Infallible, PhantomData<fn() -> S>);
+impl<S> Commutative for Xor<S>
+where
+    S: Copy + BitXor<Output = S>,
+{
+    type S = S;
+    fn operation(a: Self::S, b: Self::S) -> Self::S {
+        a ^ b
+    }
+}
+
+/// dsu keeping the aggregate of node values for every component
+pub struct AggDsu<M>
+where
+    M: Commutative,
+{
+    fa: Vec<usize>,
+    size: Vec<usize>,
+    agg: Vec<M::S>, // only valid on leaders
+}
+
+impl<M> AggDsu<M>
+where
+    M: Commutative,
+{
+    pub fn new(vals: Vec<M::S>) -> Self {
+        let n = vals.len();
+        Self {
+            fa: (0..n).collect(),
+            size: vec![1; n],
+            agg: vals,
+        }
+    }
+
+    pub fn leader(&mut self, x: usize) -> usize {
+        let mut r = x;
+        while self.fa[r] != r {
+            r = self.fa[r];
+        }
+        let mut x = x;
+        while self.fa[x] != r {
+            let nxt = self.fa[x];
+            self.fa[x] = r;
+            x = nxt;
+        }
+        r
+    }
+
+    pub fn same(&mut self, a: usize, b: usize) -> bool {
+        self.leader(a) == self.leader(b)
+    }
+
+    pub fn size(&mut self, x: usize) -> usize {
+        let x = self.leader(x);
+        self.size[x]
+    }
+
+    /// aggregate of the component containing x
+    pub fn agg(&mut self, x: usize) -> M::S {
+        let x = self.leader(x);
+        self.agg[x]
+    }
+
+    /// combine v into the aggregate of the component containing x
+    pub fn apply(&mut self, x: usize, v: M::S) {
+        let x = self.leader(x);
+        self.agg[x] = M::operation(self.agg[x], v);
+    }
+
+    /// return the new leader
+    pub fn merge(&mut self, a: usize, b: usize) -> usize {
+        self.merge_with(a, b, |_, _| {})
+    }
+
+    /// f(big, small) is called with the two old leaders before they are joined,
+    /// big becomes the new leader; use it to move per-component data small-to-large \
+    /// f isn't called when a and b are already in the same component
+    pub fn merge_with<F>(&mut self, a: usize, b: usize, f: F) -> usize
+    where
+        F: FnOnce(usize, usize),
+    {
+        let (mut a, mut b) = (self.leader(a), self.leader(b));
+        if a == b {
+            return a;
+        }
+        if self.size[a] < self.size[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        f(a, b);
+        self.fa[b] = a;
+        self.size[a] += self.size[b];
+        self.agg[a] = M::operation(self.agg[a], self.agg[b]);
+        a
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use random::Random;
+
+    use crate::{Additive, AggDsu, Dsu, Min, RollbackDsu};
+
+    #[test]
+    fn dsu_test() {
+        let mut d = Dsu::new(7);
+        d.merge(5, 2);
+        d.merge(2, 6);
+        d.merge(0, 3);
+        assert!(d.same(6, 5) && !d.same(0, 2));
+        assert_eq!((d.size(2), d.size(4), d.len()), (3, 1, 7));
+        assert_eq!(d.groups(), [vec![0, 3], vec![1], vec![2, 5, 6], vec![4]]);
+        assert_eq!(d.merge(3, 0), d.leader(0));
+        assert!(Dsu::new(0).groups().is_empty());
+    }
+
+    #[test]
+    fn rollback_dsu_test() {
+        let mut d = RollbackDsu::new(6);
+        d.merge(0, 1);
+        let s = d.snapshot();
+        assert!(d.merge(1, 2) && d.merge(3, 4) && !d.merge(0, 2));
+        assert_eq!((d.size(2), d.count()), (3, 3));
+        let t = d.snapshot();
+        d.merge(2, 4);
+        assert!(d.same(0, 3));
+        d.rollback(t);
+        assert!(!d.same(0, 3) && d.same(3, 4));
+        d.rollback(s);
+        assert!(!d.same(1, 2) && d.same(0, 1));
+        assert_eq!((d.size(0), d.size(4), d.count()), (2, 1, 5));
+    }
+
+    #[test]
+    fn agg_dsu_test() {
+        let mut rd = Random::new(1);
+        let n = 20;
+        // fresh structures every round, so components stay small
+        for _ in 0..100 {
+            // applied values are folded into the node they were applied at
+            let mut sv: Vec<i64> = (0..n).map(|_| rd.next(100) as i64 - 50).collect();
+            let mut mv = sv.clone();
+            let mut comp: Vec<usize> = (0..n).collect();
+            let mut sum = AggDsu::<Additive<i64>>::new(sv.clone());
+            let mut min = AggDsu::<Min<i64>>::new(mv.clone());
+            for _ in 0..60 {
+                let (a, b) = (rd.next(n as u64) as usize, rd.next(n as u64) as usize);
+                let v = rd.next(100) as i64 - 50;
+                match rd.next(3) {
+                    0 => {
+                        let (la, lb) = (sum.leader(a), sum.leader(b));
+                        let (sa, sb) = (sum.size(a), sum.size(b));
+                        let mut called = None;
+                        let r = sum.merge_with(a, b, |big, small| called = Some((big, small)));
+                        min.merge(a, b);
+                        if la == lb {
+                            assert_eq!((called, r), (None, la));
+                        } else {
+                            let (big, small) = called.unwrap();
+                            assert_eq!(r, big);
+                            assert!((big, small) == (la, lb) || (big, small) == (lb, la));
+                            let (bs, ss) = if big == la { (sa, sb) } else { (sb, sa) };
+                            assert!(bs >= ss && sum.size(a) == sa + sb);
+                            let (ca, cb) = (comp[a], comp[b]);
+                            comp.iter_mut().filter(|c| **c == cb).for_each(|c| *c = ca);
+                        }
+                    }
+                    1 => {
+                        sum.apply(a, v);
+                        min.apply(a, v);
+                        sv[a] += v;
+                        mv[a] = mv[a].min(v);
+                    }
+                    _ => {
+                        let c = (0..n).filter(|&i| comp[i] == comp[a]);
+                        assert_eq!(sum.agg(a), c.clone().map(|i| sv[i]).sum::<i64>());
+                        assert_eq!(min.agg(a), c.map(|i| mv[i]).min().unwrap());
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,73 @@
+/// dsu with union by size only, so merges can be undone in reverse order \
+/// O(log n) per operation
+#[derive(Clone, Debug)]
+pub struct RollbackDsu {
+    fa: Vec<usize>,
+    size: Vec<usize>,
+    // (small, big) leaders of every successful merge
+    history: Vec<(usize, usize)>,
+    comps: usize,
+}
+
+impl RollbackDsu {
+    pub fn new(n: usize) -> Self {
+        Self {
+            fa: (0..n).collect(),
+            size: vec![1; n],
+            history: vec![],
+            comps: n,
+        }
+    }
+
+    pub fn leader(&self, mut x: usize) -> usize {
+        while self.fa[x] != x {
+            x = self.fa[x];
+        }
+        x
+    }
+
+    pub fn same(&self, a: usize, b: usize) -> bool {
+        self.leader(a) == self.leader(b)
+    }
+
+    pub fn size(&self, x: usize) -> usize {
+        self.size[self.leader(x)]
+    }
+
+    /// number of components
+    pub fn count(&self) -> usize {
+        self.comps
+    }
+
+    /// false if a and b were already joined, nothing is recorded then
+    pub fn merge(&mut self, a: usize, b: usize) -> bool {
+        let (mut a, mut b) = (self.leader(a), self.leader(b));
+        if a == b {
+            return false;
+        }
+        if self.size[a] < self.size[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        self.fa[b] = a;
+        self.size[a] += self.size[b];
+        self.history.push((b, a));
+        self.comps -= 1;
+        true
+    }
+
+    /// a point to come back to with rollback
+    pub fn snapshot(&self) -> usize {
+        self.history.len()
+    }
+
+    /// undo every merge made after the snapshot
+    pub fn rollback(&mut self, snapshot: usize) {
+        assert!(snapshot <= self.history.len(), "snapshot from the future!");
+        while self.history.len() > snapshot {
+            let (b, a) = self.history.pop().unwrap();
+            self.fa[b] = b;
+            self.size[a] -= self.size[b];
+            self.comps += 1;
+        }
+    }
+}
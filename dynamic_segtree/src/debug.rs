@@ -0,0 +1,41 @@
+use std::fmt::{self, Debug, Formatter};
+
+use arena::NodeId;
+
+use crate::{DynSegtree, Monoid};
+
+impl<M> DynSegtree<M>
+where
+    M: Monoid,
+    M::S: Debug,
+{
+    fn dump(&self, f: &mut Formatter<'_>, x: NodeId, l: i64, r: i64, depth: usize) -> fmt::Result {
+        writeln!(
+            f,
+            "{:w$}[{}, {}] {:?}",
+            "",
+            l,
+            r,
+            self.tr[x].s,
+            w = 2 * depth
+        )?;
+        if !self.tr[x].lson.is_null() {
+            let mid = (l + r - 1) / 2;
+            self.dump(f, self.ls(x), l, mid, depth + 1)?;
+            self.dump(f, self.rs(x), mid + 1, r, depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
+/// every created node with its range and aggregate, children indented
+impl<M> Debug for DynSegtree<M>
+where
+    M: Monoid,
+    M::S: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "DynSegtree ({} nodes)", self.tr.len())?;
+        self.dump(f, NodeId::NULL, self.ml, self.mr, 0)
+    }
+}
@@ -1,4 +1,15 @@
-use std::ops::{Add, Sub};
+use std::ops::{Add, RangeBounds, Sub};
+
+use arena::{Arena, NodeId};
+use range_query::closed;
+pub use range_query::Bisect;
+
+#[cfg(feature = "debug")]
+mod debug;
+mod persistent;
+#[cfg(feature = "validate")]
+mod validate;
+pub use persistent::{DistinctCount, PersistentCount};
 
 use self::dyn_monoid::Monoid;
 use dyn_monoid::dyn_internal_type_traits::Zero;
@@ -28,6 +39,7 @@ mod dyn_monoid {
         //
         // Maybe we should rename this to `PrimitiveInteger` or something, as it probably won't be used in the
         // same way as the original ACL.
+        #[allow(dead_code)]
         pub trait Integral:
             'static
             + Send
@@ -108,14 +120,14 @@ mod dyn_monoid {
                     impl BoundedBelow for $ty {
                         #[inline]
                         fn min_value() -> Self {
-                            Self::min_value()
+                            Self::MIN
                         }
                     }
 
                     impl BoundedAbove for $ty {
                         #[inline]
                         fn max_value() -> Self {
-                            Self::max_value()
+                            Self::MAX
                         }
                     }
 
@@ -243,16 +255,16 @@ where
     M: Monoid,
 {
     s: M::S,
-    lson: u32,
-    rson: u32,
+    lson: NodeId,
+    rson: NodeId,
 }
 
 impl<M: Monoid> Node<M> {
     pub fn new() -> Self {
         Self {
             s: M::identity(),
-            lson: 0,
-            rson: 0,
+            lson: NodeId::NULL,
+            rson: NodeId::NULL,
         }
     }
 }
@@ -261,43 +273,44 @@ pub struct DynSegtree<M>
 where
     M: Monoid,
 {
-    n: usize,
-    tr: Vec<Node<M>>,
+    tr: Arena<Node<M>>,
     ml: i64,
     mr: i64,
 }
 
 impl<M: Monoid> DynSegtree<M> {
-    fn ls(&self, x: usize) -> usize {
-        self.tr[x].lson as usize
+    fn ls(&self, x: NodeId) -> NodeId {
+        self.tr[x].lson
     }
-    fn rs(&self, x: usize) -> usize {
-        self.tr[x].rson as usize
+    fn rs(&self, x: NodeId) -> NodeId {
+        self.tr[x].rson
     }
     pub fn new(ml: i64, mr: i64, cap: usize) -> Self {
-        let mut tr = Vec::with_capacity(cap);
-        tr.push(Node::new());
-        Self { n: 1, tr, ml, mr }
+        let mut tr = Arena::with_capacity(cap);
+        tr.alloc(Node::new());
+        Self { tr, ml, mr }
+    }
+
+    /// reset every position to the identity, keeping the allocated memory
+    pub fn clear(&mut self) {
+        self.tr.clear();
+        self.tr.alloc(Node::new());
     }
 
-    fn pushup(&mut self, x: usize) {
+    fn pushup(&mut self, x: NodeId) {
         self.tr[x].s = M::binary_operation(&self.tr[self.ls(x)].s, &self.tr[self.rs(x)].s);
     }
 
-    fn pushdown(&mut self, x: usize) {
-        if self.tr[x].lson == 0 {
-            self.tr.push(Node::new());
-            self.tr[x].lson = self.n as u32;
-            self.n += 1;
+    fn pushdown(&mut self, x: NodeId) {
+        if self.tr[x].lson.is_null() {
+            self.tr[x].lson = self.tr.alloc(Node::new());
         }
-        if self.tr[x].rson == 0 {
-            self.tr.push(Node::new());
-            self.tr[x].rson = self.n as u32;
-            self.n += 1;
+        if self.tr[x].rson.is_null() {
+            self.tr[x].rson = self.tr.alloc(Node::new());
         }
     }
 
-    fn st(&mut self, x: usize, l: i64, r: i64, pos: i64, k: M::S) {
+    fn st(&mut self, x: NodeId, l: i64, r: i64, pos: i64, k: M::S) {
         if l == r {
             self.tr[x].s = k;
             return;
@@ -313,10 +326,10 @@ impl<M: Monoid> DynSegtree<M> {
     }
 
     pub fn set(&mut self, pos: i64, k: M::S) {
-        self.st(0, self.ml, self.mr, pos, k);
+        self.st(NodeId::NULL, self.ml, self.mr, pos, k);
     }
 
-    fn upd(&mut self, x: usize, l: i64, r: i64, pos: i64, k: M::S) {
+    fn upd(&mut self, x: NodeId, l: i64, r: i64, pos: i64, k: M::S) {
         if l == r {
             self.tr[x].s = M::binary_operation(&self.tr[x].s, &k);
             return;
@@ -332,10 +345,10 @@ impl<M: Monoid> DynSegtree<M> {
     }
 
     pub fn update(&mut self, pos: i64, k: M::S) {
-        self.upd(0, self.ml, self.mr, pos, k);
+        self.upd(NodeId::NULL, self.ml, self.mr, pos, k);
     }
 
-    fn que(&mut self, x: usize, l: i64, r: i64, ql: i64, qr: i64) -> M::S {
+    fn que(&mut self, x: NodeId, l: i64, r: i64, ql: i64, qr: i64) -> M::S {
         if ql <= l && r <= qr {
             return self.tr[x].s;
         }
@@ -350,8 +363,170 @@ impl<M: Monoid> DynSegtree<M> {
         )
     }
 
-    pub fn query(&mut self, ql: i64, qr: i64) -> M::S {
-        self.que(0, self.ml, self.mr, ql, qr)
+    /// product over range, an unbounded end is ml or mr, `seg.query(-5..=5)` \
+    /// identity if the range is empty
+    pub fn query<R: RangeBounds<i64>>(&mut self, range: R) -> M::S {
+        match closed(range, self.ml, self.mr) {
+            Some((ql, qr)) => self.query_inclusive(ql, qr),
+            None => M::identity(),
+        }
+    }
+
+    /// both included
+    pub fn query_inclusive(&mut self, ql: i64, qr: i64) -> M::S {
+        self.que(NodeId::NULL, self.ml, self.mr, ql, qr)
+    }
+}
+
+impl<M: Monoid> DynSegtree<M> {
+    // first pos >= l where f fails, acc is the product before node x
+    fn max_right_from<F>(
+        &self,
+        x: NodeId,
+        nl: i64,
+        nr: i64,
+        l: i64,
+        f: &F,
+        acc: &mut M::S,
+    ) -> Option<i64>
+    where
+        F: Fn(&M::S) -> bool,
+    {
+        if nr < l {
+            return None;
+        }
+        if l <= nl {
+            let t = M::binary_operation(acc, &self.tr[x].s);
+            if f(&t) {
+                *acc = t;
+                return None;
+            }
+            if nl == nr {
+                return Some(nl);
+            }
+        }
+        if self.tr[x].lson.is_null() {
+            // an unexpanded subtree only holds identities
+            return None;
+        }
+        let mid = (nl + nr - 1) / 2;
+        self.max_right_from(self.ls(x), nl, mid, l, f, acc)
+            .or_else(|| self.max_right_from(self.rs(x), mid + 1, nr, l, f, acc))
+    }
+
+    // last pos < r where f fails, plus one; acc is the product after node x
+    fn min_left_from<F>(
+        &self,
+        x: NodeId,
+        nl: i64,
+        nr: i64,
+        r: i64,
+        f: &F,
+        acc: &mut M::S,
+    ) -> Option<i64>
+    where
+        F: Fn(&M::S) -> bool,
+    {
+        if nl >= r {
+            return None;
+        }
+        if nr < r {
+            let t = M::binary_operation(&self.tr[x].s, acc);
+            if f(&t) {
+                *acc = t;
+                return None;
+            }
+            if nl == nr {
+                return Some(nl + 1);
+            }
+        }
+        if self.tr[x].lson.is_null() {
+            return None;
+        }
+        let mid = (nl + nr - 1) / 2;
+        self.min_left_from(self.rs(x), mid + 1, nr, r, f, acc)
+            .or_else(|| self.min_left_from(self.ls(x), nl, mid, r, f, acc))
+    }
+}
+
+impl<M: Monoid> Bisect for DynSegtree<M> {
+    type Index = i64;
+    type S = M::S;
+
+    /// return mr + 1 if f holds up to the end
+    fn max_right<F>(&self, l: i64, f: F) -> i64
+    where
+        F: Fn(&M::S) -> bool,
+    {
+        let mut acc = M::identity();
+        self.max_right_from(NodeId::NULL, self.ml, self.mr, l, &f, &mut acc)
+            .unwrap_or(self.mr + 1)
+    }
+
+    /// return ml if f holds down to the beginning
+    fn min_left<F>(&self, r: i64, f: F) -> i64
+    where
+        F: Fn(&M::S) -> bool,
+    {
+        let mut acc = M::identity();
+        self.min_left_from(NodeId::NULL, self.ml, self.mr, r, &f, &mut acc)
+            .unwrap_or(self.ml)
+    }
+}
+
+impl<M> DynSegtree<M>
+where
+    M: Monoid,
+    M::S: PartialEq,
+{
+    /// iterate (pos, value) of every leaf whose value is not the identity, in increasing pos
+    pub fn iter_set(&self) -> IterSet<'_, M> {
+        IterSet {
+            tree: self,
+            stack: vec![(NodeId::NULL, self.ml, self.mr)],
+        }
+    }
+
+    pub fn for_each_leaf<F>(&self, mut f: F)
+    where
+        F: FnMut(i64, M::S),
+    {
+        for (pos, s) in self.iter_set() {
+            f(pos, s);
+        }
+    }
+}
+
+pub struct IterSet<'a, M>
+where
+    M: Monoid,
+{
+    tree: &'a DynSegtree<M>,
+    stack: Vec<(NodeId, i64, i64)>,
+}
+
+impl<M> Iterator for IterSet<'_, M>
+where
+    M: Monoid,
+    M::S: PartialEq,
+{
+    type Item = (i64, M::S);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((x, l, r)) = self.stack.pop() {
+            let node = &self.tree.tr[x];
+            if node.lson.is_null() {
+                // unexpanded inner nodes always hold the identity
+                if l == r && node.s != M::identity() {
+                    return Some((l, node.s));
+                }
+                continue;
+            }
+            let mid = (l + r - 1) / 2;
+            self.stack.push((node.rson, mid + 1, r));
+            self.stack.push((node.lson, l, mid));
+        }
+        None
     }
 }
 
@@ -361,7 +536,7 @@ impl<M> KthTree<M>
 where
     M: Copy + Add<Output = M> + Zero + PartialOrd + Sub<Output = M>,
 {
-    fn inner_kth(&mut self, x: usize, l: i64, r: i64, k: M) -> i64 {
+    fn inner_kth(&mut self, x: NodeId, l: i64, r: i64, k: M) -> i64 {
         if l == r {
             l
         } else {
@@ -376,6 +551,159 @@ where
     }
 
     pub fn kth(&mut self, k: M) -> i64 {
-        self.inner_kth(0, self.ml, self.mr, k)
+        self.inner_kth(NodeId::NULL, self.ml, self.mr, k)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use random::Random;
+    use test_support::NaiveArray;
+
+    use crate::{Additive, Bisect, DistinctCount, DynSegtree, Max, PersistentCount};
+
+    #[test]
+    fn differential_test() {
+        let (ml, mr) = (-50i64, 49i64);
+        let mut rd = Random::new(1);
+        let mut sum = DynSegtree::<Additive<i64>>::new(ml, mr, 0);
+        let mut max = DynSegtree::<Max<i64>>::new(ml, mr, 0);
+        let mut a = NaiveArray::new(100, 0i64, |a: &i64, b: &i64| a + b);
+        let mut b = NaiveArray::new(100, i64::MIN, |a: &i64, b: &i64| *a.max(b));
+        for _ in 0..20000 {
+            let p = rd.next_bounds(0, 99) as i64 + ml;
+            let v = rd.next(1000) as i64;
+            match rd.next(4) {
+                0 => {
+                    sum.set(p, v);
+                    max.set(p, v);
+                    a.set((p - ml) as usize, v);
+                    b.set((p - ml) as usize, v);
+                }
+                1 => {
+                    sum.update(p, v);
+                    a.update((p - ml) as usize, &v);
+                }
+                2 => {
+                    let q = rd.next_bounds(0, 99) as i64 + ml;
+                    let (l, r) = ((p.min(q) - ml) as usize, (p.max(q) - ml) as usize);
+                    assert_eq!(sum.query(p.min(q)..=p.max(q)), a.query(l, r));
+                    assert_eq!(max.query_inclusive(p.min(q), p.max(q)), b.query(l, r));
+                    assert_eq!(sum.query(..p + 1), a.query(0, (p - ml) as usize));
+                    assert_eq!(sum.query(p..), a.query((p - ml) as usize, 99));
+                    assert_eq!(sum.query(..), a.query(0, 99));
+                    assert_eq!((sum.query(p..p), sum.query(..ml)), (0, 0));
+                    assert_eq!((sum.query(q + 1..=q), max.query(..i64::MIN)), (0, i64::MIN));
+                }
+                _ => {
+                    // longest prefix from p with sum <= v * 5
+                    let l = (p - ml) as usize;
+                    let mut r = l;
+                    while r < 100 && a.query(l, r) <= v * 5 {
+                        r += 1;
+                    }
+                    assert_eq!(sum.max_right(p, |&s| s <= v * 5), r as i64 + ml);
+                }
+            }
+            #[cfg(feature = "validate")]
+            {
+                sum.validate();
+                max.validate();
+            }
+        }
+    }
+
+    #[test]
+    fn iter_set_test() {
+        let (ml, mr) = (-1_000_000_000i64, 1_000_000_000i64);
+        let mut rd = Random::new(3);
+        let mut tr = DynSegtree::<Additive<i64>>::new(ml, mr, 0);
+        let mut map = BTreeMap::new();
+        for _ in 0..2000 {
+            let p = if rd.next(2) == 0 {
+                rd.next_bounds(0, 40) as i64 - 20
+            } else {
+                rd.next_bounds(0, (mr - ml) as u64) as i64 + ml
+            };
+            // small values so points regularly fall back to the identity
+            let v = rd.next(5) as i64 - 2;
+            if rd.next(2) == 0 {
+                tr.set(p, v);
+                map.insert(p, v);
+            } else {
+                tr.update(p, v);
+                *map.entry(p).or_insert(0) += v;
+            }
+            #[cfg(feature = "validate")]
+            tr.validate();
+            map.retain(|_, v| *v != 0);
+            let expect: Vec<_> = map.iter().map(|(&p, &v)| (p, v)).collect();
+            assert_eq!(tr.iter_set().collect::<Vec<_>>(), expect);
+            let mut got = vec![];
+            tr.for_each_leaf(|p, v| got.push((p, v)));
+            assert_eq!(got, expect);
+        }
+        tr.clear();
+        assert_eq!(tr.iter_set().next(), None);
+    }
+
+    #[test]
+    fn distinct_count_test() {
+        let mut rd = Random::new(2);
+        for _ in 0..100 {
+            let n = rd.next(40) as usize;
+            let k = rd.next_bounds(1, 10);
+            let a: Vec<u64> = (0..n).map(|_| rd.next(k)).collect();
+            let dc = DistinctCount::new(&a);
+            for l in 0..n {
+                for r in l..n {
+                    let mut s = a[l..=r].to_vec();
+                    s.sort_unstable();
+                    s.dedup();
+                    assert_eq!(dc.query(l, r), s.len());
+                }
+                assert_eq!(dc.query(l + 1, l), 0);
+            }
+        }
+
+        // every version against its own snapshot
+        let n = 30;
+        let mut pc = PersistentCount::new(n);
+        let mut snapshots = vec![vec![0i64; n]];
+        for _ in 0..300 {
+            let ver = rd.next(snapshots.len() as u64) as usize;
+            let (pos, v) = (rd.next(n as u64) as usize, rd.next(11) as i64 - 5);
+            let mut a = snapshots[ver].clone();
+            a[pos] += v;
+            snapshots.push(a);
+            assert_eq!(pc.add(ver, pos, v), snapshots.len() - 1);
+            let ver = rd.next(snapshots.len() as u64) as usize;
+            let l = rd.next(n as u64) as usize;
+            let r = rd.next_bounds(l as u64, n as u64 - 1) as usize;
+            assert_eq!(
+                pc.query(ver, l, r),
+                snapshots[ver][l..=r].iter().sum::<i64>()
+            );
+        }
+    }
+
+    #[cfg(feature = "debug")]
+    #[test]
+    fn debug_test() {
+        let mut tr = DynSegtree::<Additive<i64>>::new(0, 3, 0);
+        tr.set(2, 5);
+        assert_eq!(
+            format!("{:?}", tr),
+            concat!(
+                "DynSegtree (5 nodes)\n",
+                "[0, 3] 5\n",
+                "  [0, 1] 0\n",
+                "  [2, 3] 5\n",
+                "    [2, 2] 5\n",
+                "    [3, 3] 0\n",
+            )
+        );
     }
 }
@@ -0,0 +1,114 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use arena::{Arena, NodeId};
+
+#[derive(Clone, Copy)]
+struct Node {
+    l: NodeId,
+    r: NodeId,
+    s: i64,
+}
+
+/// persistent segment tree of counts over positions [0, n) \
+/// version 0 is all zero, every add creates a new version in O(log n)
+pub struct PersistentCount {
+    t: Arena<Node>, // t[NULL] is the all zero tree, its children are itself
+    roots: Vec<NodeId>,
+    n: usize,
+}
+
+impl PersistentCount {
+    pub fn new(n: usize) -> Self {
+        let mut t = Arena::new();
+        t.alloc(Node {
+            l: NodeId::NULL,
+            r: NodeId::NULL,
+            s: 0,
+        });
+        Self {
+            t,
+            roots: vec![NodeId::NULL],
+            n,
+        }
+    }
+
+    /// the latest version
+    pub fn version(&self) -> usize {
+        self.roots.len() - 1
+    }
+
+    fn _add(&mut self, x: NodeId, l: usize, r: usize, pos: usize, v: i64) -> NodeId {
+        let mut node = self.t[x];
+        node.s += v;
+        if l < r {
+            let mid = (l + r) / 2;
+            if pos <= mid {
+                node.l = self._add(node.l, l, mid, pos, v);
+            } else {
+                node.r = self._add(node.r, mid + 1, r, pos, v);
+            }
+        }
+        self.t.alloc(node)
+    }
+
+    /// add v at pos on top of version ver, return the new version
+    pub fn add(&mut self, ver: usize, pos: usize, v: i64) -> usize {
+        assert!(pos < self.n, "index out of range!");
+        let root = self._add(self.roots[ver], 0, self.n - 1, pos, v);
+        self.roots.push(root);
+        self.version()
+    }
+
+    fn _query(&self, x: NodeId, l: usize, r: usize, ql: usize, qr: usize) -> i64 {
+        if x.is_null() || qr < l || r < ql {
+            return 0;
+        }
+        if ql <= l && r <= qr {
+            return self.t[x].s;
+        }
+        let mid = (l + r) / 2;
+        self._query(self.t[x].l, l, mid, ql, qr) + self._query(self.t[x].r, mid + 1, r, ql, qr)
+    }
+
+    /// sum over [l, r] in version ver
+    pub fn query(&self, ver: usize, l: usize, r: usize) -> i64 {
+        if l > r || self.n == 0 {
+            return 0;
+        }
+        self._query(self.roots[ver], 0, self.n - 1, l, r)
+    }
+}
+
+/// number of distinct values in a\[l..=r\], answered online in O(log n) \
+/// version i + 1 marks the last occurrence of every value in a\[..=i\]
+pub struct DistinctCount {
+    tree: PersistentCount,
+    ver: Vec<usize>, // ver[i] is the version after a[..i]
+}
+
+impl DistinctCount {
+    pub fn new<T>(a: &[T]) -> Self
+    where
+        T: Hash + Eq,
+    {
+        let mut tree = PersistentCount::new(a.len());
+        let mut ver = vec![0];
+        let mut last = HashMap::new();
+        for (i, x) in a.iter().enumerate() {
+            let mut v = tree.version();
+            if let Some(j) = last.insert(x, i) {
+                v = tree.add(v, j, -1);
+            }
+            ver.push(tree.add(v, i, 1));
+        }
+        Self { tree, ver }
+    }
+
+    pub fn query(&self, l: usize, r: usize) -> usize {
+        if l > r {
+            return 0;
+        }
+        self.tree.query(self.ver[r + 1], l, r) as usize
+    }
+}
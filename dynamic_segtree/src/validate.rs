@@ -0,0 +1,32 @@
+use arena::NodeId;
+
+use crate::{DynSegtree, Monoid};
+
+impl<M> DynSegtree<M>
+where
+    M: Monoid,
+    M::S: PartialEq,
+{
+    /// panic if some created node is not the product of its children
+    pub fn validate(&self) {
+        let mut stack = vec![NodeId::NULL];
+        while let Some(x) = stack.pop() {
+            if self.tr[x].lson.is_null() {
+                continue;
+            }
+            let (l, r) = (self.ls(x), self.rs(x));
+            assert!(
+                l.index() < self.tr.len() && r.index() < self.tr.len(),
+                "node {} has a bad child",
+                x
+            );
+            assert!(
+                self.tr[x].s == M::binary_operation(&self.tr[l].s, &self.tr[r].s),
+                "node {} is not the product of its children",
+                x
+            );
+            stack.push(l);
+            stack.push(r);
+        }
+    }
+}
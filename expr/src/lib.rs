@@ -0,0 +1,239 @@
+use std::ops::{Add, Div, Mul, Sub};
+use std::str::FromStr;
+
+/// byte position in the input where parsing failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseError {
+    pub pos: usize,
+}
+
+struct BinOp<T> {
+    sym: String,
+    prec: u32,
+    right_assoc: bool,
+    f: Box<dyn Fn(T, T) -> T>,
+}
+
+struct PrefixOp<T> {
+    sym: String,
+    f: Box<dyn Fn(T) -> T>,
+}
+
+type Operand<T> = Box<dyn Fn(&str) -> Option<(T, usize)>>;
+
+/// precedence climbing parser, whitespace is skipped between tokens
+pub struct ExprParser<T> {
+    operand: Operand<T>,
+    ops: Vec<BinOp<T>>,
+    prefix: Vec<PrefixOp<T>>,
+}
+
+impl<T> ExprParser<T> {
+    /// operand(rest) parses an operand at the start of rest,
+    /// returning its value and the number of bytes consumed
+    pub fn new<P>(operand: P) -> Self
+    where
+        P: Fn(&str) -> Option<(T, usize)> + 'static,
+    {
+        Self {
+            operand: Box::new(operand),
+            ops: vec![],
+            prefix: vec![],
+        }
+    }
+
+    /// add a binary operator, larger prec binds tighter
+    pub fn op<F>(mut self, sym: &str, prec: u32, right_assoc: bool, f: F) -> Self
+    where
+        F: Fn(T, T) -> T + 'static,
+    {
+        self.ops.push(BinOp {
+            sym: sym.to_string(),
+            prec,
+            right_assoc,
+            f: Box::new(f),
+        });
+        self
+    }
+
+    /// add a prefix operator, it applies to the following operand only
+    pub fn prefix<F>(mut self, sym: &str, f: F) -> Self
+    where
+        F: Fn(T) -> T + 'static,
+    {
+        self.prefix.push(PrefixOp {
+            sym: sym.to_string(),
+            f: Box::new(f),
+        });
+        self
+    }
+
+    /// value of the whole of s, Err only for syntax errors \
+    /// operator closures run as they are reduced, so their panics propagate,
+    /// e.g. `arithmetic(number::<i64>)` panics on "1 / 0" and on overflow
+    pub fn eval(&self, s: &str) -> Result<T, ParseError> {
+        let mut pos = 0;
+        let res = self.expr(s, &mut pos, 0)?;
+        skip_ws(s, &mut pos);
+        if pos != s.len() {
+            return Err(ParseError { pos });
+        }
+        Ok(res)
+    }
+
+    fn expr(&self, s: &str, pos: &mut usize, min_prec: u32) -> Result<T, ParseError> {
+        let mut lhs = self.unary(s, pos)?;
+        loop {
+            skip_ws(s, pos);
+            // longest matching symbol wins, e.g. "**" over "*"
+            let op = self
+                .ops
+                .iter()
+                .filter(|o| s[*pos..].starts_with(&o.sym))
+                .max_by_key(|o| o.sym.len());
+            let op = match op {
+                Some(op) if op.prec >= min_prec => op,
+                _ => break,
+            };
+            *pos += op.sym.len();
+            let next = if op.right_assoc { op.prec } else { op.prec + 1 };
+            let rhs = self.expr(s, pos, next)?;
+            lhs = (op.f)(lhs, rhs);
+        }
+        Ok(lhs)
+    }
+
+    fn unary(&self, s: &str, pos: &mut usize) -> Result<T, ParseError> {
+        skip_ws(s, pos);
+        let op = self
+            .prefix
+            .iter()
+            .filter(|o| s[*pos..].starts_with(&o.sym))
+            .max_by_key(|o| o.sym.len());
+        if let Some(op) = op {
+            *pos += op.sym.len();
+            let v = self.unary(s, pos)?;
+            return Ok((op.f)(v));
+        }
+        if s[*pos..].starts_with('(') {
+            *pos += 1;
+            let v = self.expr(s, pos, 0)?;
+            skip_ws(s, pos);
+            if !s[*pos..].starts_with(')') {
+                return Err(ParseError { pos: *pos });
+            }
+            *pos += 1;
+            return Ok(v);
+        }
+        match (self.operand)(&s[*pos..]) {
+            Some((v, len)) if len > 0 => {
+                *pos += len;
+                Ok(v)
+            }
+            _ => Err(ParseError { pos: *pos }),
+        }
+    }
+}
+
+fn skip_ws(s: &str, pos: &mut usize) {
+    while s[*pos..].starts_with(|c: char| c.is_ascii_whitespace()) {
+        *pos += 1;
+    }
+}
+
+/// operand parser for unsigned decimal literals, e.g. i64 or u64
+pub fn number<T: FromStr>(s: &str) -> Option<(T, usize)> {
+    let len = s.bytes().take_while(|c| c.is_ascii_digit()).count();
+    s[..len].parse().ok().map(|v| (v, len))
+}
+
+/// parser for + - * / with the usual precedence, add `.prefix("-", |x: T| -x)` for negation \
+/// the operators are T's own, so integer division by zero or overflow panics inside `eval`
+pub fn arithmetic<T, P>(operand: P) -> ExprParser<T>
+where
+    T: Add<Output = T> + Sub<Output = T> + Mul<Output = T> + Div<Output = T> + 'static,
+    P: Fn(&str) -> Option<(T, usize)> + 'static,
+{
+    ExprParser::new(operand)
+        .op("+", 1, false, |a, b| a + b)
+        .op("-", 1, false, |a, b| a - b)
+        .op("*", 2, false, |a, b| a * b)
+        .op("/", 2, false, |a, b| a / b)
+}
+
+#[cfg(test)]
+mod test {
+    use random::Random;
+
+    use crate::{arithmetic, number, ParseError};
+
+    // random expression over 0..10 with + - * / and negation, as (text, value, precedence),
+    // precedence 3 for operands, negations and parenthesized expressions
+    fn gen(rd: &mut Random, depth: u32) -> (String, i64, u32) {
+        let sp = |rd: &mut Random| if rd.next(2) == 0 { "" } else { " " };
+        let wrap = |(t, v, p): (String, i64, u32), tight: bool| {
+            if tight {
+                (format!("({})", t), v, 3)
+            } else {
+                (t, v, p)
+            }
+        };
+        if depth == 0 || rd.next(4) == 0 {
+            let v = rd.next(10) as i64;
+            return (v.to_string(), v, 3);
+        }
+        if rd.next(5) == 0 {
+            let c = gen(rd, depth - 1);
+            let tight = c.2 < 3;
+            let (t, v, _) = wrap(c, tight);
+            return (format!("-{}{}", sp(rd), t), -v, 3);
+        }
+        let (l, r) = (gen(rd, depth - 1), gen(rd, depth - 1));
+        let mut op = b"+-*/"[rd.next(4) as usize];
+        if op == b'/' && r.1 == 0 {
+            op = b'*';
+        }
+        let prec = if op == b'+' || op == b'-' { 1 } else { 2 };
+        // every operator is left associative
+        let (tl, tr) = (l.2 < prec, r.2 <= prec);
+        let ((lt, lv, _), (rt, rv, _)) = (wrap(l, tl), wrap(r, tr));
+        let v = match op {
+            b'+' => lv + rv,
+            b'-' => lv - rv,
+            b'*' => lv * rv,
+            _ => lv / rv,
+        };
+        let t = format!("{}{}{}{}{}", lt, sp(rd), op as char, sp(rd), rt);
+        wrap((t, v, prec), rd.next(6) == 0)
+    }
+
+    #[test]
+    fn arithmetic_test() {
+        let p = arithmetic(number::<i64>).prefix("-", |x| -x);
+        assert_eq!(p.eval("1 + 2 * 3"), Ok(7));
+        assert_eq!(p.eval("(1 + 2) * 3 - 4 / 2"), Ok(7));
+        assert_eq!(p.eval("10 - 3 - 2"), Ok(5));
+        assert_eq!(p.eval("-(2 + 3) * -2"), Ok(10));
+        assert_eq!(p.eval("2 * (3"), Err(ParseError { pos: 6 }));
+        assert_eq!(p.eval("2 3"), Err(ParseError { pos: 2 }));
+        let p = p.op("^", 3, true, |a, b| a.pow(b as u32));
+        assert_eq!(p.eval("2 ^ 3 ^ 2"), Ok(512));
+    }
+
+    #[test]
+    fn random_test() {
+        let mut rd = Random::new(1);
+        let p = arithmetic(number::<i64>).prefix("-", |x| -x);
+        for _ in 0..3000 {
+            // at most 16 operands below 10, so no overflow
+            let (t, v, _) = gen(&mut rd, 4);
+            assert_eq!(p.eval(&t), Ok(v), "{}", t);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn division_by_zero_test() {
+        let _ = arithmetic(number::<i64>).eval("1 / (2 - 2)");
+    }
+}
@@ -0,0 +1,61 @@
+use std::fmt::{self, Debug, Formatter};
+
+use crate::{Fenwick, PersistentFenwick, WindowKth};
+
+/// every cell with the range it sums
+impl<T> Debug for Fenwick<T>
+where
+    T: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Fenwick (n {})", self.n)?;
+        for i in 1..=self.n {
+            writeln!(f, "[{}, {}] {:?}", i - (i & (!i + 1)) + 1, i, self.tr[i])?;
+        }
+        Ok(())
+    }
+}
+
+/// every cell with the range it sums, its latest value and history length
+impl<T> Debug for PersistentFenwick<T>
+where
+    T: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "PersistentFenwick (n {}, version {})", self.n, self.ver)?;
+        for i in 1..=self.n {
+            let (ver, v) = self.tr[i].last().unwrap();
+            writeln!(
+                f,
+                "[{}, {}] {:?} at version {} ({} versions)",
+                i - (i & (!i + 1)) + 1,
+                i,
+                v,
+                ver,
+                self.tr[i].len()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// the window and the count cells over the compressed values
+impl<T> Debug for WindowKth<T>
+where
+    T: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "WindowKth [{}, {})", self.l, self.l + self.w)?;
+        for i in 1..self.tr.len() {
+            let lo = i - (i & (!i + 1));
+            writeln!(
+                f,
+                "[{:?}, {:?}] count {}",
+                self.vals[lo],
+                self.vals[i - 1],
+                self.tr[i]
+            )?;
+        }
+        Ok(())
+    }
+}
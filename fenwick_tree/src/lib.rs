@@ -0,0 +1,407 @@
+use std::ops::{Add, Sub};
+
+use range_query::Bisect;
+
+#[cfg(feature = "debug")]
+mod debug;
+
+/// fenwick tree over positions 1..=n, point add and prefix sums in O(log n)
+pub struct Fenwick<T> {
+    tr: Vec<T>,
+    n: usize,
+}
+
+impl<T> Fenwick<T>
+where
+    T: Copy + Default + Add<Output = T> + Sub<Output = T>,
+{
+    pub fn new(n: usize) -> Self {
+        Self {
+            tr: vec![T::default(); n + 1],
+            n,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.n
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    /// add v at position i
+    pub fn add(&mut self, i: usize, v: T) {
+        assert!(0 < i && i <= self.n, "index out of range!");
+        let mut i = i;
+        while i <= self.n {
+            self.tr[i] = self.tr[i] + v;
+            i += i & (!i + 1);
+        }
+    }
+
+    /// sum of [1, i], i may exceed n
+    pub fn sum(&self, i: usize) -> T {
+        let mut res = T::default();
+        let mut i = i.min(self.n);
+        while i > 0 {
+            res = res + self.tr[i];
+            i -= i & (!i + 1);
+        }
+        res
+    }
+
+    /// sum of [l, r]
+    pub fn query(&self, l: usize, r: usize) -> T {
+        self.sum(r) - self.sum(l - 1)
+    }
+
+    // max p with pred(p, sum of [1, p]) true, pred must hold at 0 and then turn false once
+    fn descend<P>(&self, pred: P) -> usize
+    where
+        P: Fn(usize, &T) -> bool,
+    {
+        let (mut pos, mut acc) = (0, T::default());
+        let mut step = if self.n == 0 { 0 } else { 1 << self.n.ilog2() };
+        while step > 0 {
+            if pos + step <= self.n {
+                let t = acc + self.tr[pos + step];
+                if pred(pos + step, &t) {
+                    pos += step;
+                    acc = t;
+                }
+            }
+            step >>= 1;
+        }
+        pos
+    }
+}
+
+/// l and r are 1-indexed in [1, n + 1], O(log n) by walking down the tree
+impl<T> Bisect for Fenwick<T>
+where
+    T: Copy + Default + Add<Output = T> + Sub<Output = T>,
+{
+    type Index = usize;
+    type S = T;
+
+    fn max_right<F>(&self, l: usize, f: F) -> usize
+    where
+        F: Fn(&T) -> bool,
+    {
+        assert!(0 < l && l <= self.n + 1, "index out of range!");
+        let base = self.sum(l - 1);
+        // prefixes ending before l stay true, so the predicate is monotone
+        self.descend(|p, s| p < l || f(&(*s - base))) + 1
+    }
+
+    fn min_left<F>(&self, r: usize, f: F) -> usize
+    where
+        F: Fn(&T) -> bool,
+    {
+        assert!(0 < r && r <= self.n + 1, "index out of range!");
+        let top = self.sum(r - 1);
+        if f(&top) {
+            return 1;
+        }
+        // the last p with f failing on [p + 1, r)
+        self.descend(|p, s| p < r - 1 && !f(&(top - *s))) + 2
+    }
+}
+
+/// fenwick tree keeping every version, all indices are 1-indexed \
+/// version 0 is all zeros, each add creates the next version \
+/// every cell keeps its history, so add is O(log n) and sum O(log^2 n)
+pub struct PersistentFenwick<T> {
+    // (version, value) pairs with increasing version
+    tr: Vec<Vec<(u32, T)>>,
+    n: usize,
+    ver: u32,
+}
+
+impl<T> PersistentFenwick<T>
+where
+    T: Copy + Default + Add<Output = T> + Sub<Output = T>,
+{
+    fn lowbit(x: usize) -> usize {
+        x & (!x + 1)
+    }
+
+    pub fn new(n: usize) -> Self {
+        Self {
+            tr: vec![vec![(0, T::default())]; n + 1],
+            n,
+            ver: 0,
+        }
+    }
+
+    /// the latest version
+    pub fn version(&self) -> u32 {
+        self.ver
+    }
+
+    /// add v at position i on top of the latest version, return the new version
+    pub fn add(&mut self, i: usize, v: T) -> u32 {
+        assert!(0 < i && i <= self.n, "index out of range!");
+        self.ver += 1;
+        let mut i = i;
+        while i <= self.n {
+            let last = self.tr[i].last().unwrap().1;
+            self.tr[i].push((self.ver, last + v));
+            i += Self::lowbit(i);
+        }
+        self.ver
+    }
+
+    fn cell(&self, i: usize, ver: u32) -> T {
+        let h = &self.tr[i];
+        h[h.partition_point(|&(t, _)| t <= ver) - 1].1
+    }
+
+    /// sum of [1, i] in version ver
+    pub fn sum(&self, ver: u32, i: usize) -> T {
+        assert!(ver <= self.ver, "version out of range!");
+        let mut res = T::default();
+        let mut i = i.min(self.n);
+        while i > 0 {
+            res = res + self.cell(i, ver);
+            i -= Self::lowbit(i);
+        }
+        res
+    }
+
+    /// sum of [l, r] in version ver
+    pub fn query(&self, ver: u32, l: usize, r: usize) -> T {
+        self.sum(ver, r) - self.sum(ver, l - 1)
+    }
+}
+
+/// k-th smallest in a window of fixed size sliding over a, O(log n) per step \
+/// counts of the compressed values in the window are kept in a fenwick tree
+pub struct WindowKth<T> {
+    vals: Vec<T>,   // sorted distinct values
+    a: Vec<usize>,  // a[i] as an index into vals
+    tr: Vec<usize>, // counts, 1-indexed
+    w: usize,
+    l: usize,
+}
+
+impl<T> WindowKth<T>
+where
+    T: Ord + Clone,
+{
+    /// window starts at [0, w)
+    pub fn new(a: &[T], w: usize) -> Self {
+        assert!(0 < w && w <= a.len(), "window size out of range!");
+        let mut vals = a.to_vec();
+        vals.sort();
+        vals.dedup();
+        let a: Vec<usize> = a.iter().map(|x| vals.binary_search(x).unwrap()).collect();
+        let mut res = Self {
+            tr: vec![0; vals.len() + 1],
+            vals,
+            a,
+            w,
+            l: 0,
+        };
+        for i in 0..w {
+            res.add(res.a[i], 1);
+        }
+        res
+    }
+
+    fn add(&mut self, i: usize, v: isize) {
+        let mut i = i + 1;
+        while i < self.tr.len() {
+            self.tr[i] = self.tr[i].wrapping_add_signed(v);
+            i += i & (!i + 1);
+        }
+    }
+
+    /// left end of the current window
+    pub fn start(&self) -> usize {
+        self.l
+    }
+
+    /// slide the window right by one, false if it is already at the end
+    pub fn advance(&mut self) -> bool {
+        if self.l + self.w == self.a.len() {
+            return false;
+        }
+        self.add(self.a[self.l], -1);
+        self.add(self.a[self.l + self.w], 1);
+        self.l += 1;
+        true
+    }
+
+    /// k-th smallest in the window, 0-indexed
+    pub fn kth(&self, k: usize) -> &T {
+        assert!(k < self.w, "k out of range!");
+        let n = self.tr.len() - 1;
+        let (mut pos, mut rest) = (0, k);
+        let mut step = if n == 0 { 0 } else { 1 << n.ilog2() };
+        while step > 0 {
+            if pos + step <= n && self.tr[pos + step] <= rest {
+                pos += step;
+                rest -= self.tr[pos];
+            }
+            step >>= 1;
+        }
+        &self.vals[pos]
+    }
+}
+
+/// k-th smallest (0-indexed) of every window of size w
+pub fn sliding_kth<T>(a: &[T], w: usize, k: usize) -> Vec<T>
+where
+    T: Ord + Clone,
+{
+    let mut wk = WindowKth::new(a, w);
+    let mut res = vec![wk.kth(k).clone()];
+    while wk.advance() {
+        res.push(wk.kth(k).clone());
+    }
+    res
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{sliding_kth, Fenwick, PersistentFenwick, WindowKth};
+    use random::Random;
+    use range_query::Bisect;
+
+    #[test]
+    fn bisect_test() {
+        let mut rd = Random::new(2);
+        for n in [0, 1, 2, 7, 8, 9, 33] {
+            let mut fw = Fenwick::<u64>::new(n);
+            let mut a = vec![0u64; n + 1];
+            for _ in 0..300 {
+                if n > 0 && rd.next(2) == 0 {
+                    let (i, v) = (rd.next_bounds(1, n as u64) as usize, rd.next(4));
+                    fw.add(i, v);
+                    a[i] += v;
+                    continue;
+                }
+                let x = rd.next(3 * n as u64 + 2);
+                let (l, r) = (
+                    rd.next_bounds(1, n as u64 + 1) as usize,
+                    rd.next_bounds(1, n as u64 + 1) as usize,
+                );
+                let mut e = l;
+                while e <= n && a[l..=e].iter().sum::<u64>() <= x {
+                    e += 1;
+                }
+                assert_eq!(fw.max_right(l, |&s| s <= x), e);
+                let mut e = r;
+                while e > 1 && a[e - 1..r].iter().sum::<u64>() <= x {
+                    e -= 1;
+                }
+                assert_eq!(fw.min_left(r, |&s| s <= x), e);
+            }
+            assert_eq!(fw.max_right(1, |_| true), n + 1);
+            assert_eq!(fw.min_left(n + 1, |_| true), 1);
+        }
+    }
+
+    #[test]
+    fn persistent_test() {
+        let mut rd = Random::new(4);
+        let n = 20;
+        let mut fw = PersistentFenwick::<i64>::new(n);
+        // snapshots[v] is the array after version v, 1-indexed
+        let mut snapshots = vec![vec![0i64; n + 1]];
+        for _ in 0..500 {
+            let (i, v) = (
+                rd.next_bounds(1, n as u64) as usize,
+                rd.next(21) as i64 - 10,
+            );
+            let mut a = snapshots.last().unwrap().clone();
+            a[i] += v;
+            snapshots.push(a);
+            assert_eq!(fw.add(i, v) as usize, snapshots.len() - 1);
+            for _ in 0..5 {
+                let ver = rd.next(snapshots.len() as u64) as usize;
+                let l = rd.next_bounds(1, n as u64) as usize;
+                let r = rd.next_bounds(l as u64, n as u64) as usize;
+                let a = &snapshots[ver];
+                assert_eq!(fw.query(ver as u32, l, r), a[l..=r].iter().sum::<i64>());
+                assert_eq!(fw.sum(ver as u32, r), a[..=r].iter().sum::<i64>());
+            }
+        }
+        assert_eq!(fw.version(), 500);
+        assert_eq!(fw.sum(0, n), 0);
+        assert_eq!(fw.sum(500, n + 5), snapshots[500].iter().sum::<i64>());
+    }
+
+    #[test]
+    fn sliding_kth_test() {
+        let mut rd = Random::new(6);
+        for _ in 0..200 {
+            let n = rd.next_bounds(1, 30) as usize;
+            // few distinct values, so windows have duplicates
+            let a: Vec<u64> = (0..n).map(|_| rd.next(5)).collect();
+            let w = if rd.next(4) == 0 {
+                n
+            } else {
+                rd.next_bounds(1, n as u64) as usize
+            };
+            let sorted: Vec<Vec<u64>> = a
+                .windows(w)
+                .map(|s| {
+                    let mut s = s.to_vec();
+                    s.sort_unstable();
+                    s
+                })
+                .collect();
+            for k in 0..w {
+                let e: Vec<u64> = sorted.iter().map(|s| s[k]).collect();
+                assert_eq!(sliding_kth(&a, w, k), e);
+            }
+            let mut wk = WindowKth::new(&a, w);
+            for (i, s) in sorted.iter().enumerate() {
+                assert_eq!(wk.start(), i);
+                assert!((0..w).all(|k| *wk.kth(k) == s[k]));
+                assert_eq!(wk.advance(), i + 1 < sorted.len());
+            }
+        }
+    }
+
+    #[cfg(feature = "debug")]
+    #[test]
+    fn debug_test() {
+        let mut fw = Fenwick::new(4);
+        fw.add(1, 1);
+        fw.add(3, 2);
+        assert_eq!(
+            format!("{:?}", fw),
+            concat!(
+                "Fenwick (n 4)\n",
+                "[1, 1] 1\n",
+                "[1, 2] 1\n",
+                "[3, 3] 2\n",
+                "[1, 4] 3\n",
+            )
+        );
+        let mut pf = PersistentFenwick::new(2);
+        pf.add(2, 5);
+        assert_eq!(
+            format!("{:?}", pf),
+            concat!(
+                "PersistentFenwick (n 2, version 1)\n",
+                "[1, 1] 0 at version 0 (1 versions)\n",
+                "[1, 2] 5 at version 1 (2 versions)\n",
+            )
+        );
+        let wk = WindowKth::new(&[3, 1, 2], 2);
+        assert_eq!(
+            format!("{:?}", wk),
+            concat!(
+                "WindowKth [0, 2)\n",
+                "[1, 1] count 1\n",
+                "[1, 2] count 1\n",
+                "[3, 3] count 1\n",
+            )
+        );
+    }
+}
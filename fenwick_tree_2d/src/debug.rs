@@ -0,0 +1,26 @@
+use std::fmt::{self, Debug, Formatter};
+
+use crate::FenwickTree2d;
+
+/// every nonzero cell with the rectangle it sums
+impl Debug for FenwickTree2d {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "FenwickTree2d ({} x {})", self.n, self.m)?;
+        for i in 1..=self.n {
+            for j in 1..=self.m {
+                if self.tr[i][j] != 0 {
+                    writeln!(
+                        f,
+                        "[{}, {}] x [{}, {}] {}",
+                        i - Self::lowbit(i) + 1,
+                        i,
+                        j - Self::lowbit(j) + 1,
+                        j,
+                        self.tr[i][j]
+                    )?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -1,3 +1,10 @@
+#[cfg(feature = "debug")]
+mod debug;
+
+use std::ops::RangeBounds;
+
+use range_query::closed;
+
 pub struct FenwickTree2d {
     // point add, matrix query
     tr: Vec<Vec<u64>>,
@@ -39,8 +46,74 @@ impl FenwickTree2d {
         }
         res
     }
-    pub fn query(&self, x1: usize, y1: usize, x2: usize, y2: usize) -> u64 {
-        // - - +
-        self.sum(x2, y2) - self.sum(x2, y1 - 1) - self.sum(x1 - 1, y2) + self.sum(x1 - 1, y1 - 1)
+    /// sum over the rectangle xs x ys, 1-indexed, `fw.query(2..=3, ..)`, 0 if either is empty
+    pub fn query<X, Y>(&self, xs: X, ys: Y) -> u64
+    where
+        X: RangeBounds<usize>,
+        Y: RangeBounds<usize>,
+    {
+        match (closed(xs, 1, self.n), closed(ys, 1, self.m)) {
+            (Some((x1, x2)), Some((y1, y2))) => self.query_rect(x1, y1, x2, y2),
+            _ => 0,
+        }
+    }
+    /// corners (x1, y1) and (x2, y2) both included
+    pub fn query_rect(&self, x1: usize, y1: usize, x2: usize, y2: usize) -> u64 {
+        // + + - -, adding first so the u64 never goes below zero
+        self.sum(x2, y2) + self.sum(x1 - 1, y1 - 1) - self.sum(x2, y1 - 1) - self.sum(x1 - 1, y2)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::FenwickTree2d;
+
+    #[test]
+    fn query_test() {
+        let (n, m) = (5, 7);
+        let mut fw = FenwickTree2d::new(n, m);
+        let mut a = vec![vec![0u64; m + 1]; n + 1];
+        for (x, row) in a.iter_mut().enumerate().skip(1) {
+            for (y, c) in row.iter_mut().enumerate().skip(1) {
+                *c = (x * 7 + y * 3) as u64 % 10;
+                fw.add(x, y, *c);
+            }
+        }
+        let sum = |x1: usize, x2: usize, y1: usize, y2: usize| -> u64 {
+            (x1..=x2).flat_map(|x| a[x][y1..=y2].iter()).sum()
+        };
+        for x1 in 1..=n {
+            for x2 in x1..=n {
+                for y1 in 1..=m {
+                    for y2 in y1..=m {
+                        assert_eq!(fw.query(x1..=x2, y1..y2 + 1), sum(x1, x2, y1, y2));
+                    }
+                }
+                assert_eq!(fw.query(x1..=x2, ..), sum(x1, x2, 1, m));
+            }
+        }
+        assert_eq!(fw.query(.., ..), sum(1, n, 1, m));
+        assert_eq!(fw.query(2.., ..=3), sum(2, n, 1, 3));
+        assert_eq!(
+            (fw.query(..0, ..), fw.query(..1, ..), fw.query(.., 3..3)),
+            (0, 0, 0)
+        );
+        let (l, r) = (4, 3);
+        assert_eq!(fw.query(l..=r, 1..=m), 0);
+    }
+
+    #[cfg(feature = "debug")]
+    #[test]
+    fn debug_test() {
+        let mut fw = FenwickTree2d::new(2, 2);
+        fw.add(1, 2, 4);
+        assert_eq!(
+            format!("{:?}", fw),
+            concat!(
+                "FenwickTree2d (2 x 2)\n",
+                "[1, 1] x [1, 2] 4\n",
+                "[1, 2] x [1, 2] 4\n",
+            )
+        );
     }
 }
@@ -0,0 +1,40 @@
+use modint::{ModInt, Modulus};
+
+/// f(x) for the polynomial f of degree <= d with f(i) = values\[i\] for i in 0..=d \
+/// O(d) with one inversion, d must be below the prime modulus
+pub fn lagrange_consecutive<M: Modulus>(values: &[ModInt<M>], x: ModInt<M>) -> ModInt<M> {
+    let n = values.len();
+    if n == 0 {
+        return ModInt::default();
+    }
+    // pre[i] = prod (x - j) for j < i, suf[i] = prod (x - j) for j >= i
+    let mut pre = vec![ModInt::new(1); n + 1];
+    let mut suf = vec![ModInt::new(1); n + 1];
+    for i in 0..n {
+        pre[i + 1] = pre[i] * (x - ModInt::new(i));
+    }
+    for i in (0..n).rev() {
+        suf[i] = suf[i + 1] * (x - ModInt::new(i));
+    }
+    let mut fact = vec![ModInt::<M>::new(1); n];
+    for i in 1..n {
+        fact[i] = fact[i - 1] * ModInt::new(i);
+    }
+    let mut inv_fact = vec![fact[n - 1].inv(); n];
+    for i in (1..n).rev() {
+        inv_fact[i - 1] = inv_fact[i] * ModInt::new(i);
+    }
+    // the denominator prod (i - j) for j != i is i! (d - i)! (-1)^(d - i)
+    let d = n - 1;
+    values
+        .iter()
+        .enumerate()
+        .fold(ModInt::default(), |acc, (i, &y)| {
+            let t = y * pre[i] * suf[i + 1] * inv_fact[i] * inv_fact[d - i];
+            if (d - i) % 2 == 1 {
+                acc - t
+            } else {
+                acc + t
+            }
+        })
+}
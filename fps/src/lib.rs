@@ -0,0 +1,138 @@
+mod lagrange;
+mod multipoint;
+mod recurrence;
+mod series;
+
+pub use lagrange::lagrange_consecutive;
+pub use multipoint::{interpolate, multipoint_eval};
+pub use recurrence::{berlekamp_massey, kth_term};
+pub use series::{div, exp, inv, log, multiply, pow, sqrt};
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        berlekamp_massey, div, exp, interpolate, inv, kth_term, lagrange_consecutive, log,
+        multiply, multipoint_eval, pow, sqrt,
+    };
+    use modint::ModInt998244353;
+
+    type Mint = ModInt998244353;
+
+    fn trunc(mut a: Vec<Mint>, n: usize) -> Vec<Mint> {
+        a.resize(n, Mint::default());
+        a
+    }
+
+    #[test]
+    fn series_test() {
+        let n = 200;
+        let a: Vec<Mint> = (0..150u64).map(|i| Mint::new(i * i + 1)).collect();
+        let one = trunc(vec![Mint::new(1)], n);
+        assert_eq!(trunc(multiply(&a, &inv(&a, n)), n), one);
+        let b: Vec<Mint> = (0..100u64).map(|i| Mint::new(i * 7 + 3)).collect();
+        assert_eq!(trunc(multiply(&div(&a, &b, n), &b), n), trunc(a.clone(), n));
+        let mut c = a.clone();
+        c[0] = Mint::new(1);
+        assert_eq!(exp(&log(&c, n), n), trunc(c.clone(), n));
+        // a^5 by repeated multiplication, with a leading zero
+        let mut d = a.clone();
+        d[0] = Mint::default();
+        let mut e = one.clone();
+        for _ in 0..5 {
+            e = trunc(multiply(&e, &d), n);
+        }
+        assert_eq!(pow(&d, 5, n), e);
+        assert_eq!(pow(&d, 1 << 40, n), trunc(vec![], n));
+        // odd order has no square root
+        assert!(sqrt(&e, n).is_none());
+        let sq = trunc(multiply(&d, &d), n);
+        let s = sqrt(&sq, n).unwrap();
+        assert_eq!(trunc(multiply(&s, &s), n), sq);
+        assert!(sqrt(&[Mint::new(3)], 4).is_none());
+        assert!(sqrt(&[Mint::default(), Mint::new(1)], 4).is_none());
+        assert_eq!(pow(&a, 0, 2), [Mint::new(1), Mint::default()]);
+    }
+
+    #[test]
+    fn multipoint_test() {
+        let f: Vec<Mint> = (0..300u64).map(|i| Mint::new(i * i * 31 + 7)).collect();
+        let xs: Vec<Mint> = (0..500u64).map(|i| Mint::new(i * 1234567 + 89)).collect();
+        let ys = multipoint_eval(&f, &xs);
+        for (&x, &y) in xs.iter().zip(&ys) {
+            let e = f.iter().rev().fold(Mint::default(), |acc, &c| acc * x + c);
+            assert_eq!(y, e);
+        }
+        assert_eq!(interpolate(&xs[..300], &ys[..300]), f);
+        let g = interpolate(&xs, &ys);
+        assert_eq!(g[..300], f);
+        assert!(g[300..].iter().all(|x| x.val() == 0));
+        assert!(multipoint_eval(&f, &[]).is_empty());
+        assert_eq!(
+            interpolate(&[Mint::new(5)], &[Mint::new(3)]),
+            [Mint::new(3)]
+        );
+    }
+
+    #[test]
+    fn recurrence_test() {
+        // a[i] = 2 a[i - 1] + 3 a[i - 3] + i^2, which has order 6
+        let mut a = vec![Mint::new(1), Mint::new(5), Mint::new(2)];
+        for i in 3..100u64 {
+            let x = Mint::new(2) * a[i as usize - 1] + Mint::new(3) * a[i as usize - 3];
+            a.push(x + Mint::new(i * i));
+        }
+        let rec = berlekamp_massey(&a[..40]);
+        assert_eq!(rec.len(), 6);
+        for k in 0..100 {
+            assert_eq!(kth_term(&rec, &a[..12], k as u64), a[k]);
+        }
+        let fib = berlekamp_massey(&[0, 1, 1, 2, 3, 5, 8].map(Mint::new));
+        assert_eq!(fib, [Mint::new(1), Mint::new(1)]);
+        // F(10^18) mod 998244353
+        let f = kth_term(
+            &fib,
+            &[Mint::new(0), Mint::new(1)],
+            1_000_000_000_000_000_000,
+        );
+        let (mut x, mut y) = (Mint::new(1), Mint::new(0));
+        let mut m = [[Mint::new(1), Mint::new(1)], [Mint::new(1), Mint::new(0)]];
+        let mut e = 1_000_000_000_000_000_000u64;
+        while e > 0 {
+            if e & 1 == 1 {
+                (x, y) = (m[0][0] * x + m[0][1] * y, m[1][0] * x + m[1][1] * y);
+            }
+            let t = m;
+            for (i, row) in m.iter_mut().enumerate() {
+                for (j, c) in row.iter_mut().enumerate() {
+                    *c = t[i][0] * t[0][j] + t[i][1] * t[1][j];
+                }
+            }
+            e >>= 1;
+        }
+        assert_eq!(f, y);
+        assert!(berlekamp_massey(&[Mint::default(); 5]).is_empty());
+        assert_eq!(kth_term(&[], &[Mint::new(4)], 7), Mint::default());
+    }
+
+    #[test]
+    fn lagrange_test() {
+        // sum of i^5 for i < n, a polynomial of degree 6 in n
+        let sums: Vec<Mint> = (0..7u64)
+            .scan(0, |s, n| {
+                let r = *s;
+                *s += n.pow(5);
+                Some(Mint::new(r))
+            })
+            .collect();
+        for n in [0u64, 3, 7, 100, 12345] {
+            let e: Mint = (0..n).map(|i| Mint::new(i).pow(5)).sum();
+            assert_eq!(lagrange_consecutive(&sums, Mint::new(n)), e);
+        }
+        let f: Vec<Mint> = (0..4u64).map(|x| Mint::new(x * x) - Mint::new(3)).collect();
+        assert_eq!(lagrange_consecutive(&f, Mint::new(-2)), Mint::new(1));
+        assert_eq!(
+            lagrange_consecutive(&[Mint::new(9)], Mint::new(5)),
+            Mint::new(9)
+        );
+    }
+}
@@ -0,0 +1,111 @@
+use modint::{ModInt, Modulus};
+
+use crate::series::derivative;
+use crate::{inv, multiply};
+
+/// remainder of a divided by b, b's leading coefficient must be nonzero
+fn rem<M: Modulus>(a: &[ModInt<M>], b: &[ModInt<M>]) -> Vec<ModInt<M>> {
+    if a.len() < b.len() {
+        return a.to_vec();
+    }
+    // reversed quotient = rev(a) / rev(b) mod x^k
+    let k = a.len() - b.len() + 1;
+    let ra: Vec<ModInt<M>> = a.iter().rev().take(k).copied().collect();
+    let rb: Vec<ModInt<M>> = b.iter().rev().copied().collect();
+    let mut q = multiply(&ra, &inv(&rb, k));
+    q.truncate(k);
+    q.reverse();
+    let qb = multiply(&q, b);
+    (0..b.len() - 1).map(|i| a[i] - qb[i]).collect()
+}
+
+// tree[k] = prod (x - xs[i]) for i in [l, r)
+fn build<M: Modulus>(tree: &mut [Vec<ModInt<M>>], k: usize, l: usize, r: usize, xs: &[ModInt<M>]) {
+    if r - l == 1 {
+        tree[k] = vec![-xs[l], ModInt::new(1)];
+        return;
+    }
+    let mid = (l + r) / 2;
+    build(tree, 2 * k, l, mid, xs);
+    build(tree, 2 * k + 1, mid, r, xs);
+    tree[k] = multiply(&tree[2 * k], &tree[2 * k + 1]);
+}
+
+fn subproduct_tree<M: Modulus>(xs: &[ModInt<M>]) -> Vec<Vec<ModInt<M>>> {
+    let mut tree = vec![vec![]; 4 * xs.len()];
+    build(&mut tree, 1, 0, xs.len(), xs);
+    tree
+}
+
+fn eval_down<M: Modulus>(
+    tree: &[Vec<ModInt<M>>],
+    k: usize,
+    l: usize,
+    r: usize,
+    f: Vec<ModInt<M>>,
+    xs: &[ModInt<M>],
+    out: &mut [ModInt<M>],
+) {
+    // Horner is faster on small blocks
+    if r - l <= 32 {
+        for i in l..r {
+            out[i] = f
+                .iter()
+                .rev()
+                .fold(ModInt::default(), |acc, &c| acc * xs[i] + c);
+        }
+        return;
+    }
+    let mid = (l + r) / 2;
+    eval_down(tree, 2 * k, l, mid, rem(&f, &tree[2 * k]), xs, out);
+    eval_down(tree, 2 * k + 1, mid, r, rem(&f, &tree[2 * k + 1]), xs, out);
+}
+
+/// poly(x) for every x in points, O(n log^2 n) by a subproduct tree \
+/// the modulus must be a prime that `convolution_mod` accepts
+pub fn multipoint_eval<M: Modulus>(poly: &[ModInt<M>], points: &[ModInt<M>]) -> Vec<ModInt<M>> {
+    let mut out = vec![ModInt::default(); points.len()];
+    if points.is_empty() {
+        return out;
+    }
+    let tree = subproduct_tree(points);
+    let f = rem(poly, &tree[1]);
+    eval_down(&tree, 1, 0, points.len(), f, points, &mut out);
+    out
+}
+
+fn interpolate_up<M: Modulus>(
+    tree: &[Vec<ModInt<M>>],
+    k: usize,
+    l: usize,
+    r: usize,
+    w: &[ModInt<M>],
+) -> Vec<ModInt<M>> {
+    if r - l == 1 {
+        return vec![w[l]];
+    }
+    let mid = (l + r) / 2;
+    let a = multiply(&interpolate_up(tree, 2 * k, l, mid, w), &tree[2 * k + 1]);
+    let b = multiply(&interpolate_up(tree, 2 * k + 1, mid, r, w), &tree[2 * k]);
+    let mut res = vec![ModInt::default(); r - l];
+    for (i, x) in res.iter_mut().enumerate() {
+        *x = a.get(i).copied().unwrap_or_default() + b.get(i).copied().unwrap_or_default();
+    }
+    res
+}
+
+/// the polynomial of degree < n through (points\[i\], values\[i\]), points must be distinct \
+/// O(n log^2 n) by a subproduct tree
+pub fn interpolate<M: Modulus>(points: &[ModInt<M>], values: &[ModInt<M>]) -> Vec<ModInt<M>> {
+    assert_eq!(points.len(), values.len(), "lengths differ!");
+    let n = points.len();
+    if n == 0 {
+        return vec![];
+    }
+    let tree = subproduct_tree(points);
+    // Lagrange weights y_i / g'(x_i) with g = prod (x - x_i)
+    let mut dg = vec![ModInt::default(); n];
+    eval_down(&tree, 1, 0, n, derivative(&tree[1]), points, &mut dg);
+    let w: Vec<ModInt<M>> = values.iter().zip(&dg).map(|(&y, &d)| y / d).collect();
+    interpolate_up(&tree, 1, 0, n, &w)
+}
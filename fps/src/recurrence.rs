@@ -0,0 +1,70 @@
+use modint::{ModInt, Modulus};
+
+use crate::multiply;
+
+/// shortest c with a\[i\] = sum c\[j - 1\] * a\[i - j\] for j in 1..=|c| and every i >= |c| \
+/// O(n^2), the modulus must be prime
+pub fn berlekamp_massey<M: Modulus>(a: &[ModInt<M>]) -> Vec<ModInt<M>> {
+    // cur and prev as connection polynomials 1 - c1 x - c2 x^2 ...
+    let mut cur = vec![ModInt::new(1)];
+    let mut prev = vec![ModInt::new(1)];
+    let (mut len, mut shift, mut last) = (0, 1, ModInt::new(1));
+    for i in 0..a.len() {
+        let d = (0..=len).fold(ModInt::default(), |acc, j| acc + cur[j] * a[i - j]);
+        if d.val() == 0 {
+            shift += 1;
+            continue;
+        }
+        let coef = d / last;
+        let old = cur.clone();
+        if cur.len() < prev.len() + shift {
+            cur.resize(prev.len() + shift, ModInt::default());
+        }
+        for (j, &p) in prev.iter().enumerate() {
+            cur[j + shift] -= coef * p;
+        }
+        if 2 * len <= i {
+            (len, prev, last, shift) = (i + 1 - len, old, d, 1);
+        } else {
+            shift += 1;
+        }
+    }
+    cur.resize(len + 1, ModInt::default());
+    cur[1..].iter().map(|&x| -x).collect()
+}
+
+/// a\[k\] of a\[i\] = sum rec\[j - 1\] * a\[i - j\], with a\[i\] = init\[i\] for i < |init|
+/// and |init| >= |rec| \
+/// Bostan-Mori in O(d log d log k), the modulus must be a prime that `convolution_mod` accepts
+pub fn kth_term<M: Modulus>(rec: &[ModInt<M>], init: &[ModInt<M>], k: u64) -> ModInt<M> {
+    assert!(init.len() >= rec.len(), "too few initial terms!");
+    if k < init.len() as u64 {
+        return init[k as usize];
+    }
+    let d = rec.len();
+    if d == 0 {
+        return ModInt::default();
+    }
+    // a = p / q with q = 1 - sum rec[j - 1] x^j and p = init * q mod x^d,
+    // terms past d are produced by the recurrence on the last d initial ones
+    let start = init.len() - d;
+    let mut q = vec![ModInt::new(1)];
+    q.extend(rec.iter().map(|&x| -x));
+    let mut p = multiply(&init[start..], &q);
+    p.truncate(d);
+    let mut k = k - start as u64;
+    while k > 0 {
+        // p / q = p(x) q(-x) / (q(x) q(-x)), the denominator is even
+        let qm: Vec<ModInt<M>> = q
+            .iter()
+            .enumerate()
+            .map(|(i, &x)| if i % 2 == 1 { -x } else { x })
+            .collect();
+        let u = multiply(&p, &qm);
+        let v = multiply(&q, &qm);
+        p = u.into_iter().skip((k % 2) as usize).step_by(2).collect();
+        q = v.into_iter().step_by(2).collect();
+        k /= 2;
+    }
+    p.first().copied().unwrap_or_default() / q[0]
+}
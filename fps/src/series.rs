@@ -0,0 +1,132 @@
+use convolution::{convolution_mod, fps_exp, fps_inv, fps_log};
+use modint::{ModInt, Modulus};
+
+fn to_u64<M: Modulus>(a: &[ModInt<M>]) -> Vec<u64> {
+    a.iter().map(|x| x.val() as u64).collect()
+}
+
+// values already reduced mod the modulus
+fn from_u64<M: Modulus>(a: Vec<u64>) -> Vec<ModInt<M>> {
+    a.into_iter().map(|x| ModInt::raw(x as u32)).collect()
+}
+
+/// a * b, the modulus must be a prime that `convolution_mod` accepts
+pub fn multiply<M: Modulus>(a: &[ModInt<M>], b: &[ModInt<M>]) -> Vec<ModInt<M>> {
+    from_u64(convolution_mod(&to_u64(a), &to_u64(b), M::modulus() as u64))
+}
+
+/// a * b mod x^n
+fn mul_trunc<M: Modulus>(a: &[ModInt<M>], b: &[ModInt<M>], n: usize) -> Vec<ModInt<M>> {
+    let mut c = multiply(&a[..a.len().min(n)], &b[..b.len().min(n)]);
+    c.resize(n, ModInt::default());
+    c
+}
+
+pub(crate) fn derivative<M: Modulus>(a: &[ModInt<M>]) -> Vec<ModInt<M>> {
+    (1..a.len()).map(|i| a[i] * ModInt::new(i)).collect()
+}
+
+/// 1 / a mod x^n, a\[0\] must be nonzero, see `convolution::fps_inv`
+pub fn inv<M: Modulus>(a: &[ModInt<M>], n: usize) -> Vec<ModInt<M>> {
+    from_u64(fps_inv(&to_u64(a), n, M::modulus() as u64))
+}
+
+/// a / b mod x^n, b\[0\] must be nonzero
+pub fn div<M: Modulus>(a: &[ModInt<M>], b: &[ModInt<M>], n: usize) -> Vec<ModInt<M>> {
+    mul_trunc(a, &inv(b, n), n)
+}
+
+/// log a mod x^n, a\[0\] must be 1, see `convolution::fps_log`
+pub fn log<M: Modulus>(a: &[ModInt<M>], n: usize) -> Vec<ModInt<M>> {
+    from_u64(fps_log(&to_u64(a), n, M::modulus() as u64))
+}
+
+/// exp a mod x^n, a\[0\] must be 0, see `convolution::fps_exp`
+pub fn exp<M: Modulus>(a: &[ModInt<M>], n: usize) -> Vec<ModInt<M>> {
+    from_u64(fps_exp(&to_u64(a), n, M::modulus() as u64))
+}
+
+/// a^k mod x^n, n must not exceed the modulus
+pub fn pow<M: Modulus>(a: &[ModInt<M>], k: u64, n: usize) -> Vec<ModInt<M>> {
+    let mut res = vec![ModInt::default(); n];
+    if k == 0 {
+        if n > 0 {
+            res[0] = ModInt::new(1);
+        }
+        return res;
+    }
+    let Some(z) = a.iter().take(n).position(|x| x.val() != 0) else {
+        return res;
+    };
+    if z as u128 * k as u128 >= n as u128 {
+        return res;
+    }
+    let shift = z * k as usize;
+    // a = c x^z b with b[0] = 1, a^k = c^k x^(zk) exp(k log b)
+    let c = a[z];
+    let ci = c.inv();
+    let b: Vec<ModInt<M>> = a[z..].iter().map(|&x| x * ci).collect();
+    let l: Vec<ModInt<M>> = log(&b, n - shift)
+        .into_iter()
+        .map(|x| x * ModInt::new(k))
+        .collect();
+    let ck = c.pow(k);
+    for (x, y) in res[shift..].iter_mut().zip(exp(&l, n - shift)) {
+        *x = y * ck;
+    }
+    res
+}
+
+/// some r with r^2 = a, None if a is not a square mod the prime modulus \
+/// Tonelli-Shanks
+fn sqrt_mod<M: Modulus>(a: ModInt<M>) -> Option<ModInt<M>> {
+    let p = M::modulus() as u64;
+    if a.val() == 0 || p == 2 {
+        return Some(a);
+    }
+    if a.pow((p - 1) / 2).val() != 1 {
+        return None;
+    }
+    let s = (p - 1).trailing_zeros();
+    let q = (p - 1) >> s;
+    let z = (2..)
+        .map(ModInt::<M>::new)
+        .find(|z| z.pow((p - 1) / 2).val() == p as u32 - 1)
+        .unwrap();
+    let (mut m, mut c, mut t, mut r) = (s, z.pow(q), a.pow(q), a.pow(q.div_ceil(2)));
+    while t.val() != 1 {
+        // least i with t^(2^i) = 1
+        let mut i = 0;
+        let mut tt = t;
+        while tt.val() != 1 {
+            tt *= tt;
+            i += 1;
+        }
+        let b = c.pow(1 << (m - i - 1));
+        (m, c, t, r) = (i, b * b, t * b * b, r * b);
+    }
+    Some(r)
+}
+
+/// some b with b^2 = a mod x^n, None if there is none
+pub fn sqrt<M: Modulus>(a: &[ModInt<M>], n: usize) -> Option<Vec<ModInt<M>>> {
+    let mut res = vec![ModInt::default(); n];
+    let Some(z) = a.iter().take(n).position(|x| x.val() != 0) else {
+        return Some(res);
+    };
+    if z % 2 == 1 {
+        return None;
+    }
+    let shift = z / 2;
+    let c = a[z];
+    let r = sqrt_mod(c)?;
+    let ci = c.inv();
+    let b: Vec<ModInt<M>> = a[z..].iter().map(|&x| x * ci).collect();
+    // sqrt b = exp(log b / 2)
+    let half = ModInt::new(2).inv();
+    let l: Vec<ModInt<M>> = log(&b, n - shift).into_iter().map(|x| x * half).collect();
+    for (x, y) in res[shift..].iter_mut().zip(exp(&l, n - shift)) {
+        *x = y * r;
+    }
+    Some(res)
+}
@@ -0,0 +1,88 @@
+/// shortest distances from s over directed edges (u, v, w), O(V E) \
+/// return (dist, neg): dist\[u\] is None if u is unreachable,
+/// neg\[u\] means u is reachable through a negative cycle and dist\[u\] is meaningless
+pub fn bellman_ford(
+    n: usize,
+    edges: &[(usize, usize, i64)],
+    s: usize,
+) -> (Vec<Option<i64>>, Vec<bool>) {
+    let mut dist = vec![None; n];
+    dist[s] = Some(0);
+    for _ in 1..n {
+        let mut changed = false;
+        for &(u, v, w) in edges {
+            if let Some(d) = dist[u] {
+                if dist[v].is_none_or(|x| d + w < x) {
+                    dist[v] = Some(d + w);
+                    changed = true;
+                }
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+    // still relaxable after n - 1 rounds: on or behind a negative cycle,
+    // and so is everything reachable from there
+    let mut neg = vec![false; n];
+    let mut stack = vec![];
+    for &(u, v, w) in edges {
+        if let (Some(du), Some(dv)) = (dist[u], dist[v]) {
+            if du + w < dv && !neg[v] {
+                neg[v] = true;
+                stack.push(v);
+            }
+        }
+    }
+    if !stack.is_empty() {
+        let mut adj = vec![vec![]; n];
+        for &(u, v, _) in edges {
+            adj[u].push(v);
+        }
+        while let Some(u) = stack.pop() {
+            for &v in &adj[u] {
+                if !neg[v] {
+                    neg[v] = true;
+                    stack.push(v);
+                }
+            }
+        }
+    }
+    (dist, neg)
+}
+
+/// indices of the edges of some negative cycle in order along it, None if there is none \
+/// the cycle may be anywhere in the graph, O(V E)
+pub fn negative_cycle(n: usize, edges: &[(usize, usize, i64)]) -> Option<Vec<usize>> {
+    // as if a virtual source had 0-weight edges to every vertex
+    let mut dist = vec![0i64; n];
+    let mut pred = vec![usize::MAX; n];
+    let mut last = None;
+    for _ in 0..n {
+        last = None;
+        for (i, &(u, v, w)) in edges.iter().enumerate() {
+            if dist[u] + w < dist[v] {
+                dist[v] = dist[u] + w;
+                pred[v] = i;
+                last = Some(v);
+            }
+        }
+        last?;
+    }
+    // relaxed in round n, so n steps back along pred lands on the cycle
+    let mut x = last?;
+    for _ in 0..n {
+        x = edges[pred[x]].0;
+    }
+    let mut cyc = vec![];
+    let mut y = x;
+    loop {
+        cyc.push(pred[y]);
+        y = edges[pred[y]].0;
+        if y == x {
+            break;
+        }
+    }
+    cyc.reverse();
+    Some(cyc)
+}
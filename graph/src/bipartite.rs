@@ -0,0 +1,54 @@
+use std::collections::VecDeque;
+
+/// 2-color an undirected graph \
+/// Ok(color) on success, otherwise Err(cycle) with an odd cycle as vertices in order,
+/// the last vertex is adjacent to the first one
+pub fn bipartition(adj: &[Vec<usize>]) -> Result<Vec<bool>, Vec<usize>> {
+    let n = adj.len();
+    let mut color = vec![false; n];
+    let mut dep = vec![usize::MAX; n];
+    let mut fa = vec![n; n];
+    let mut q = VecDeque::new();
+    for s in 0..n {
+        if dep[s] != usize::MAX {
+            continue;
+        }
+        dep[s] = 0;
+        q.push_back(s);
+        while let Some(u) = q.pop_front() {
+            for &v in &adj[u] {
+                if dep[v] == usize::MAX {
+                    dep[v] = dep[u] + 1;
+                    color[v] = !color[u];
+                    fa[v] = u;
+                    q.push_back(v);
+                } else if color[v] == color[u] {
+                    return Err(odd_cycle(&fa, &dep, u, v));
+                }
+            }
+        }
+    }
+    Ok(color)
+}
+
+// both tree paths up to the lca, joined by the edge (u, v)
+fn odd_cycle(fa: &[usize], dep: &[usize], mut u: usize, mut v: usize) -> Vec<usize> {
+    let (mut left, mut right) = (vec![], vec![]);
+    while dep[u] > dep[v] {
+        left.push(u);
+        u = fa[u];
+    }
+    while dep[v] > dep[u] {
+        right.push(v);
+        v = fa[v];
+    }
+    while u != v {
+        left.push(u);
+        right.push(v);
+        u = fa[u];
+        v = fa[v];
+    }
+    left.push(u);
+    left.extend(right.into_iter().rev());
+    left
+}
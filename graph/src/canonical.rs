@@ -0,0 +1,88 @@
+/// canonical form of an undirected graph with n <= 10 vertices,
+/// adj\[u\] is the bitmask of neighbours of u (no self loops) \
+/// return (code, perm): isomorphic graphs get the same code, perm\[i\] is the vertex
+/// placed at position i in the canonical labeling
+pub fn canonical_form(adj: &[u32]) -> (u64, Vec<usize>) {
+    let n = adj.len();
+    assert!(n <= 10, "too many vertices!");
+    let total = n * n.saturating_sub(1) / 2;
+    // only vertices of the i-th smallest degree may go to position i
+    let mut deg: Vec<u32> = adj.iter().map(|a| a.count_ones()).collect();
+    deg.sort();
+    let mut s = Search {
+        adj,
+        deg,
+        total,
+        best: u64::MAX,
+        best_perm: (0..n).collect(),
+        perm: Vec::with_capacity(n),
+    };
+    s.dfs(0, 0, false);
+    (((n as u64) << 45) | s.best, s.best_perm)
+}
+
+/// relabel adj by perm, perm\[i\] is the old vertex of the new vertex i
+pub fn relabel(adj: &[u32], perm: &[usize]) -> Vec<u32> {
+    let n = adj.len();
+    let mut pos = vec![0; n];
+    for (i, &p) in perm.iter().enumerate() {
+        pos[p] = i;
+    }
+    perm.iter()
+        .map(|&u| {
+            (0..n)
+                .filter(|&v| adj[u] >> v & 1 == 1)
+                .fold(0, |m, v| m | 1 << pos[v])
+        })
+        .collect()
+}
+
+struct Search<'a> {
+    adj: &'a [u32],
+    deg: Vec<u32>,
+    total: usize,
+    best: u64,
+    best_perm: Vec<usize>,
+    perm: Vec<usize>,
+}
+
+impl Search<'_> {
+    // code: bits of the placed prefix, less: already smaller than best
+    fn dfs(&mut self, used: u32, code: u64, less: bool) {
+        let i = self.perm.len();
+        if i == self.adj.len() {
+            if code < self.best {
+                self.best = code;
+                self.best_perm.clone_from(&self.perm);
+            }
+            return;
+        }
+        let bits = i * (i + 1) / 2;
+        for u in 0..self.adj.len() {
+            if used >> u & 1 == 1 || self.adj[u].count_ones() != self.deg[i] {
+                continue;
+            }
+            // swapping twins is an automorphism fixing the prefix, trying one of them is enough
+            let twin = (0..u)
+                .any(|v| used >> v & 1 == 0 && self.adj[u] & !(1 << v) == self.adj[v] & !(1 << u));
+            if twin {
+                continue;
+            }
+            let mut c = code;
+            for &v in &self.perm {
+                c = c << 1 | (self.adj[u] >> v & 1) as u64;
+            }
+            let mut nless = less;
+            if !less && self.best != u64::MAX {
+                let prefix = self.best >> (self.total - bits);
+                if c > prefix {
+                    continue;
+                }
+                nless = c < prefix;
+            }
+            self.perm.push(u);
+            self.dfs(used | 1 << u, c, nless);
+            self.perm.pop();
+        }
+    }
+}
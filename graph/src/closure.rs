@@ -0,0 +1,50 @@
+use bitset::BitSet;
+use tarjan::scc;
+
+/// reachability between all pairs of vertices of a directed graph \
+/// one bitset per strongly connected component, O(c (n + m) / 64) time and c^2 / 64 words
+/// for c components, fine up to about 5e4 of them
+pub struct TransitiveClosure {
+    comp: Vec<usize>,
+    reach: Vec<BitSet>, // components reachable from each component
+    count: Vec<usize>,  // vertices reachable from each component
+}
+
+impl TransitiveClosure {
+    pub fn new(adj: &[Vec<usize>]) -> Self {
+        let (comp, c) = scc(adj);
+        let mut members = vec![vec![]; c];
+        for (u, &x) in comp.iter().enumerate() {
+            members[x].push(u);
+        }
+        let mut reach: Vec<BitSet> = vec![];
+        // successors of a component come before it in the numbering
+        for (x, vs) in members.iter().enumerate() {
+            let mut r = BitSet::new(c);
+            r.insert(x);
+            for &u in vs {
+                for &v in &adj[u] {
+                    if comp[v] != x && !r.contains(comp[v]) {
+                        r |= &reach[comp[v]];
+                    }
+                }
+            }
+            reach.push(r);
+        }
+        let count = reach
+            .iter()
+            .map(|r| r.iter().map(|y| members[y].len()).sum())
+            .collect();
+        Self { comp, reach, count }
+    }
+
+    /// whether there is a path from u to v, every vertex reaches itself
+    pub fn reachable(&self, u: usize, v: usize) -> bool {
+        self.reach[self.comp[u]].contains(self.comp[v])
+    }
+
+    /// number of vertices reachable from u, u included
+    pub fn reach_count(&self, u: usize) -> usize {
+        self.count[self.comp[u]]
+    }
+}
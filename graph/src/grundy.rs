@@ -0,0 +1,34 @@
+/// grundy value of every position of a game on a DAG, adj\[u\] are the moves from u \
+/// return None if the graph has a cycle, O(V + E)
+pub fn grundy_on_dag(adj: &[Vec<usize>]) -> Option<Vec<usize>> {
+    let n = adj.len();
+    // process a vertex after all its successors
+    let mut out: Vec<usize> = adj.iter().map(|e| e.len()).collect();
+    let mut radj = vec![vec![]; n];
+    for (u, e) in adj.iter().enumerate() {
+        for &v in e {
+            radj[v].push(u);
+        }
+    }
+    let mut stack: Vec<usize> = (0..n).filter(|&u| out[u] == 0).collect();
+    let mut sg = vec![0; n];
+    // mark[g] == u + 1 iff some successor of u has grundy value g, values > deg don't matter
+    let mut mark = vec![0; n + 1];
+    let mut done = 0;
+    while let Some(u) = stack.pop() {
+        done += 1;
+        for &v in &adj[u] {
+            if sg[v] <= adj[u].len() {
+                mark[sg[v]] = u + 1;
+            }
+        }
+        sg[u] = (0..).find(|&g| mark[g] != u + 1).unwrap();
+        for &p in &radj[u] {
+            out[p] -= 1;
+            if out[p] == 0 {
+                stack.push(p);
+            }
+        }
+    }
+    (done == n).then_some(sg)
+}
@@ -0,0 +1,302 @@
+mod bellman_ford;
+mod bipartite;
+mod canonical;
+mod closure;
+mod grundy;
+mod mst;
+mod radix_heap;
+
+pub use bellman_ford::{bellman_ford, negative_cycle};
+pub use bipartite::bipartition;
+pub use canonical::{canonical_form, relabel};
+pub use closure::TransitiveClosure;
+pub use grundy::grundy_on_dag;
+pub use mst::{kruskal, prim, prim_with, second_best_mst, MstPathMax};
+pub use radix_heap::{dijkstra, RadixHeap, RadixKey};
+
+#[cfg(test)]
+mod test {
+    use crate::bipartition;
+    fn g(n: usize, e: &[(usize, usize)]) -> Vec<Vec<usize>> {
+        let mut a = vec![vec![]; n];
+        for &(u, v) in e {
+            a[u].push(v);
+            if u != v {
+                a[v].push(u);
+            }
+        }
+        a
+    }
+    fn check(a: &[Vec<usize>], c: &[usize]) {
+        assert!(c.len() % 2 == 1);
+        for i in 0..c.len() {
+            assert!(a[c[i]].contains(&c[(i + 1) % c.len()]));
+        }
+        let mut s = c.to_vec();
+        s.sort();
+        s.dedup();
+        assert_eq!(s.len(), c.len());
+    }
+    #[test]
+    fn bipartition_test() {
+        let a = g(6, &[(0, 1), (1, 2), (2, 3), (3, 0), (4, 5)]);
+        let c = bipartition(&a).unwrap();
+        assert!(c[0] != c[1] && c[4] != c[5]);
+        let a = g(7, &[(0, 1), (1, 2), (2, 3), (3, 4), (4, 5), (5, 6), (6, 2)]);
+        check(&a, &bipartition(&a).unwrap_err());
+        let a = g(5, &[(0, 1), (0, 2), (1, 3), (2, 4), (3, 4)]);
+        check(&a, &bipartition(&a).unwrap_err());
+        let a = g(2, &[(1, 1)]);
+        assert_eq!(bipartition(&a).unwrap_err(), vec![1]);
+    }
+
+    #[test]
+    fn dijkstra_test() {
+        use crate::dijkstra;
+        let mut rd = random::Random::new(7);
+        let n = 200;
+        let mut adj = vec![vec![]; n];
+        for _ in 0..1000 {
+            let (u, v) = (rd.next(n as u64) as usize, rd.next(n as u64) as usize);
+            adj[u].push((v, rd.next(1 << 40)));
+        }
+        // Bellman-Ford style relaxation as the reference
+        let mut dist = vec![u64::MAX; n];
+        dist[0] = 0;
+        for _ in 0..n {
+            for u in 0..n {
+                for &(v, w) in &adj[u] {
+                    if dist[u] != u64::MAX {
+                        dist[v] = dist[v].min(dist[u] + w);
+                    }
+                }
+            }
+        }
+        assert_eq!(dijkstra(&adj, 0), dist);
+    }
+
+    #[test]
+    fn mst_test() {
+        use crate::{second_best_mst, MstPathMax};
+        let mut rd = random::Random::new(3);
+        for _ in 0..200 {
+            let (n, m) = (rd.next_bounds(1, 5) as usize, rd.next(8) as usize);
+            let e: Vec<(usize, usize, i64)> = (0..m)
+                .map(|_| {
+                    let (u, v) = (rd.next(n as u64) as usize, rd.next(n as u64) as usize);
+                    (u, v, rd.next(5) as i64)
+                })
+                .collect();
+            // weights of all spanning trees by brute force
+            let mut ws = vec![];
+            for mask in 0u32..1 << m {
+                if mask.count_ones() as usize + 1 != n {
+                    continue;
+                }
+                let mut c: Vec<usize> = (0..n).collect();
+                let mut w = 0;
+                for (i, &(u, v, x)) in e.iter().enumerate() {
+                    if mask >> i & 1 == 1 {
+                        let (a, b) = (c[u], c[v]);
+                        c.iter_mut().filter(|y| **y == b).for_each(|y| *y = a);
+                        w += x;
+                    }
+                }
+                if c.iter().all(|&y| y == c[0]) {
+                    ws.push(w);
+                }
+            }
+            ws.sort();
+            let t = MstPathMax::new(n, &e);
+            assert_eq!(t.as_ref().map(|t| t.total()), ws.first().copied());
+            assert_eq!(second_best_mst(n, &e), ws.get(1).copied());
+            if let Some(t) = t {
+                for (i, &(u, v, w)) in e.iter().enumerate() {
+                    let mx = t.max_edge_on_tree_path(u, v);
+                    assert!(mx.is_none_or(|mx| mx <= w) || t.in_tree(i));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn transitive_closure_test() {
+        use crate::TransitiveClosure;
+        let mut rd = random::Random::new(13);
+        for (n, m) in [(1, 0), (30, 20), (60, 100), (100, 400)] {
+            let mut adj = vec![vec![]; n];
+            for _ in 0..m {
+                let (u, v) = (rd.next(n as u64) as usize, rd.next(n as u64) as usize);
+                adj[u].push(v);
+            }
+            let tc = TransitiveClosure::new(&adj);
+            for s in 0..n {
+                let mut seen = vec![false; n];
+                seen[s] = true;
+                let mut stack = vec![s];
+                while let Some(u) = stack.pop() {
+                    for &v in &adj[u] {
+                        if !seen[v] {
+                            seen[v] = true;
+                            stack.push(v);
+                        }
+                    }
+                }
+                assert!((0..n).all(|v| tc.reachable(s, v) == seen[v]));
+                assert_eq!(tc.reach_count(s), seen.iter().filter(|&&x| x).count());
+            }
+        }
+    }
+
+    #[test]
+    fn bellman_ford_test() {
+        use crate::{bellman_ford, negative_cycle};
+        // 0 -> 1 -> 2 -> 3 with a cheap detour 0 -> 2, cycle 4 -> 5 -> 6 -> 4 of weight -1
+        // hanging off 3, 7 behind it, 8 unreachable
+        let mut e = vec![(0, 1, 4), (1, 2, -2), (0, 2, 3), (2, 3, 5), (3, 4, 1)];
+        e.extend([(4, 5, 2), (5, 6, -4), (6, 4, 1), (6, 7, 0)]);
+        let (dist, neg) = bellman_ford(9, &e, 0);
+        assert_eq!(dist[..4], [Some(0), Some(4), Some(2), Some(7)]);
+        assert_eq!(dist[8], None);
+        assert_eq!(
+            neg,
+            [false, false, false, false, true, true, true, true, false]
+        );
+        let cyc = negative_cycle(9, &e).unwrap();
+        assert_eq!(cyc.iter().map(|&i| e[i].2).sum::<i64>(), -1);
+        for k in 0..cyc.len() {
+            assert_eq!(e[cyc[k]].1, e[cyc[(k + 1) % cyc.len()]].0);
+        }
+        e[7].2 = 2;
+        assert!(negative_cycle(9, &e).is_none());
+        assert!(bellman_ford(9, &e, 0).1.iter().all(|&x| !x));
+    }
+
+    #[test]
+    fn prim_test() {
+        use crate::{kruskal, prim, prim_with};
+        let pts: Vec<(i64, i64)> = (0..60)
+            .map(|i| ((i * 37) % 23, (i * i * 11) % 29))
+            .collect();
+        let d = |u: usize, v: usize| (pts[u].0 - pts[v].0).pow(2) + (pts[u].1 - pts[v].1).pow(2);
+        let n = pts.len();
+        let fa = prim_with(n, d);
+        assert_eq!(fa[0], n);
+        let total: i64 = (1..n).map(|u| d(u, fa[u])).sum();
+        let mut e = vec![];
+        for u in 0..n {
+            for v in u + 1..n {
+                e.push((u, v, d(u, v)));
+            }
+        }
+        let k = kruskal(n, &e);
+        assert_eq!(total, k.iter().map(|&i| e[i].2).sum::<i64>());
+        let w = vec![vec![0, 5, 1], vec![5, 0, 2], vec![1, 2, 0]];
+        assert_eq!(prim(&w), [3, 2, 0]);
+        assert_eq!(prim::<i32>(&[]), []);
+    }
+
+    #[test]
+    fn canonical_form_test() {
+        use crate::{canonical_form, relabel};
+        let mut rd = random::Random::new(10);
+        let random_graph = |rd: &mut random::Random, n: usize| {
+            let mut adj = vec![0u32; n];
+            for u in 0..n {
+                for v in 0..u {
+                    if rd.next(2) == 0 {
+                        adj[u] |= 1 << v;
+                        adj[v] |= 1 << u;
+                    }
+                }
+            }
+            adj
+        };
+        let shuffled = |rd: &mut random::Random, n: usize| {
+            let mut p: Vec<usize> = (0..n).collect();
+            for i in 1..n {
+                p.swap(i, rd.next(i as u64 + 1) as usize);
+            }
+            p
+        };
+        for _ in 0..200 {
+            let n = rd.next(9) as usize;
+            let adj = random_graph(&mut rd, n);
+            let (code, perm) = canonical_form(&adj);
+            let other = relabel(&adj, &shuffled(&mut rd, n));
+            let (code2, perm2) = canonical_form(&other);
+            assert_eq!(code, code2);
+            // both canonical labelings give the very same graph
+            assert_eq!(relabel(&adj, &perm), relabel(&other, &perm2));
+        }
+        // on 4 vertices, same code iff isomorphic, checked over all 24 permutations
+        let perms: Vec<Vec<usize>> = (0..4usize.pow(4))
+            .map(|x| {
+                (0..4)
+                    .map(|i| x / 4usize.pow(i) % 4)
+                    .collect::<Vec<usize>>()
+            })
+            .filter(|p| (0..4).all(|v| p.contains(&v)))
+            .collect();
+        assert_eq!(perms.len(), 24);
+        let graphs: Vec<Vec<u32>> = (0..64).map(|_| random_graph(&mut rd, 4)).collect();
+        for a in &graphs {
+            for b in &graphs {
+                let iso = perms.iter().any(|p| relabel(a, p) == *b);
+                assert_eq!(canonical_form(a).0 == canonical_form(b).0, iso);
+            }
+        }
+    }
+
+    #[test]
+    fn grundy_test() {
+        use crate::grundy_on_dag;
+        // mex of the successors by memoized recursion, moves only go to smaller vertices
+        fn mex(adj: &[Vec<usize>], u: usize, memo: &mut [Option<usize>]) -> usize {
+            if let Some(g) = memo[u] {
+                return g;
+            }
+            let mut seen: Vec<usize> = adj[u].iter().map(|&v| mex(adj, v, memo)).collect();
+            seen.sort_unstable();
+            let g = (0..).find(|g| seen.binary_search(g).is_err()).unwrap();
+            memo[u] = Some(g);
+            g
+        }
+        let mut rd = random::Random::new(11);
+        for _ in 0..300 {
+            let n = rd.next_bounds(1, 30) as usize;
+            let mut adj = vec![vec![]; n];
+            for _ in 0..rd.next(4 * n as u64) {
+                let (u, v) = (rd.next(n as u64) as usize, rd.next(n as u64) as usize);
+                if v < u {
+                    adj[u].push(v);
+                }
+            }
+            // relabel, so the topological order is not the vertex order
+            let p: Vec<usize> = {
+                let mut p: Vec<usize> = (0..n).collect();
+                for i in 1..n {
+                    p.swap(i, rd.next(i as u64 + 1) as usize);
+                }
+                p
+            };
+            let mut shuffled = vec![vec![]; n];
+            for (u, e) in adj.iter().enumerate() {
+                shuffled[p[u]] = e.iter().map(|&v| p[v]).collect();
+            }
+            let mut memo = vec![None; n];
+            let sg = grundy_on_dag(&shuffled).unwrap();
+            for u in 0..n {
+                assert_eq!(sg[p[u]], mex(&adj, u, &mut memo));
+            }
+            // reversing any one move closes a cycle
+            if let Some(u) = (0..n).find(|&u| !adj[u].is_empty()) {
+                let v = adj[u][rd.next(adj[u].len() as u64) as usize];
+                shuffled[p[v]].push(p[u]);
+                assert_eq!(grundy_on_dag(&shuffled), None);
+            }
+        }
+        assert_eq!(grundy_on_dag(&[vec![1], vec![2], vec![0]]), None);
+        assert_eq!(grundy_on_dag(&[]), Some(vec![]));
+    }
+}
@@ -0,0 +1,133 @@
+use std::ops::{Add, Sub};
+
+use dsu::Dsu;
+use tree::{Lifting, Max};
+
+/// Kruskal on edges (u, v, w), indices of the chosen edges in order of weight \
+/// a spanning forest if the graph isn't connected
+pub fn kruskal<T>(n: usize, edges: &[(usize, usize, T)]) -> Vec<usize>
+where
+    T: Copy + Ord,
+{
+    let mut ord: Vec<usize> = (0..edges.len()).collect();
+    ord.sort_by_key(|&i| edges[i].2);
+    let mut d = Dsu::new(n);
+    let mut res = vec![];
+    for i in ord {
+        let (u, v, _) = edges[i];
+        if !d.same(u, v) {
+            d.merge(u, v);
+            res.push(i);
+        }
+    }
+    res
+}
+
+/// Prim on the complete graph with weight w(u, v), O(n^2) without storing the edges \
+/// return fa, the MST is the edges (u, fa\[u\]) for u != 0, fa\[0\] = n
+pub fn prim_with<T, F>(n: usize, mut w: F) -> Vec<usize>
+where
+    T: Copy + Ord,
+    F: FnMut(usize, usize) -> T,
+{
+    let mut fa = vec![n; n];
+    // best[v] = lightest edge from the tree to v so far
+    let mut best: Vec<Option<T>> = vec![None; n];
+    let mut done = vec![false; n];
+    let mut u = 0;
+    for _ in 1..n {
+        done[u] = true;
+        let mut next = n;
+        for v in 0..n {
+            if done[v] {
+                continue;
+            }
+            let x = w(u, v);
+            if best[v].is_none_or(|b| x < b) {
+                (best[v], fa[v]) = (Some(x), u);
+            }
+            if next == n || best[v] < best[next] {
+                next = v;
+            }
+        }
+        u = next;
+    }
+    fa
+}
+
+/// `prim_with` on an adjacency matrix
+pub fn prim<T: Copy + Ord>(w: &[Vec<T>]) -> Vec<usize> {
+    prim_with(w.len(), |u, v| w[u][v])
+}
+
+/// a minimum spanning tree with max-edge queries on its paths \
+/// by the cycle property, a non-tree edge (u, v, w) is in some MST iff
+/// w equals the max edge on the tree path between u and v
+pub struct MstPathMax<T>
+where
+    T: Copy + Ord,
+{
+    total: T,
+    in_tree: Vec<bool>,
+    lift: Lifting<T, Max<T>>,
+}
+
+impl<T> MstPathMax<T>
+where
+    T: Copy + Ord + Default + Add<Output = T>,
+{
+    /// None if the graph isn't connected
+    pub fn new(n: usize, edges: &[(usize, usize, T)]) -> Option<Self> {
+        let chosen = kruskal(n, edges);
+        if chosen.len() + 1 < n {
+            return None;
+        }
+        let mut adj = vec![vec![]; n];
+        let mut in_tree = vec![false; edges.len()];
+        let mut total = T::default();
+        for &i in &chosen {
+            let (u, v, w) = edges[i];
+            adj[u].push((v, w));
+            adj[v].push((u, w));
+            in_tree[i] = true;
+            total = total + w;
+        }
+        let lift = Lifting::with_weights(&adj, 0);
+        Some(Self {
+            total,
+            in_tree,
+            lift,
+        })
+    }
+
+    /// weight of the tree
+    pub fn total(&self) -> T {
+        self.total
+    }
+
+    /// whether edges\[i\] was chosen
+    pub fn in_tree(&self, i: usize) -> bool {
+        self.in_tree[i]
+    }
+
+    /// heaviest edge on the tree path between u and v, None if u == v
+    pub fn max_edge_on_tree_path(&self, u: usize, v: usize) -> Option<T> {
+        self.lift.query_path(u, v)
+    }
+}
+
+/// minimum weight of a spanning tree other than the one Kruskal picks,
+/// equal to the MST weight when the MST isn't unique \
+/// None if the graph isn't connected or has no other spanning tree
+pub fn second_best_mst<T>(n: usize, edges: &[(usize, usize, T)]) -> Option<T>
+where
+    T: Copy + Ord + Default + Add<Output = T> + Sub<Output = T>,
+{
+    let t = MstPathMax::new(n, edges)?;
+    edges
+        .iter()
+        .enumerate()
+        .filter(|&(i, _)| !t.in_tree(i))
+        .filter_map(|(_, &(u, v, w))| Some(t.total() + w - t.max_edge_on_tree_path(u, v)?))
+        .min()
+}
@@ -0,0 +1,95 @@
+/// unsigned keys a `RadixHeap` can bucket by their highest differing bit
+pub trait RadixKey: Copy + Ord + Default {
+    const BITS: usize;
+    /// index of the highest bit where a and b differ plus one, 0 if equal
+    fn bucket(a: Self, b: Self) -> usize;
+}
+
+macro_rules! impl_radix_key {
+    ($($t:ty),*) => {$(
+        impl RadixKey for $t {
+            const BITS: usize = <$t>::BITS as usize;
+            fn bucket(a: Self, b: Self) -> usize {
+                <Self as RadixKey>::BITS - (a ^ b).leading_zeros() as usize
+            }
+        }
+    )*};
+}
+
+impl_radix_key!(u32, u64);
+
+/// monotone min priority queue, a pushed key must not be less than the last popped one \
+/// each element is moved at most BITS times, amortized O(BITS) per push
+pub struct RadixHeap<K, V> {
+    buckets: Vec<Vec<(K, V)>>,
+    last: K,
+    len: usize,
+}
+
+impl<K: RadixKey, V> Default for RadixHeap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: RadixKey, V> RadixHeap<K, V> {
+    pub fn new() -> Self {
+        Self {
+            buckets: (0..=K::BITS).map(|_| vec![]).collect(),
+            last: K::default(),
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn push(&mut self, k: K, v: V) {
+        assert!(k >= self.last, "key less than the last popped one!");
+        self.buckets[K::bucket(k, self.last)].push((k, v));
+        self.len += 1;
+    }
+
+    /// remove an element with the smallest key
+    pub fn pop(&mut self) -> Option<(K, V)> {
+        if self.buckets[0].is_empty() {
+            let i = (1..=K::BITS).find(|&i| !self.buckets[i].is_empty())?;
+            // every key in bucket i shares the bits above i with the new minimum,
+            // so they all fall into lower buckets
+            let b = std::mem::take(&mut self.buckets[i]);
+            self.last = b.iter().map(|e| e.0).min().unwrap();
+            for (k, v) in b {
+                self.buckets[K::bucket(k, self.last)].push((k, v));
+            }
+        }
+        self.len -= 1;
+        self.buckets[0].pop()
+    }
+}
+
+/// distances from s on a graph with nonnegative integer weights, adj\[u\] holds (v, w) \
+/// u64::MAX for unreachable vertices, O((V + E) log W) with a `RadixHeap`
+pub fn dijkstra(adj: &[Vec<(usize, u64)>], s: usize) -> Vec<u64> {
+    let mut dist = vec![u64::MAX; adj.len()];
+    let mut heap = RadixHeap::new();
+    dist[s] = 0;
+    heap.push(0, s);
+    while let Some((d, u)) = heap.pop() {
+        if d > dist[u] {
+            continue;
+        }
+        for &(v, w) in &adj[u] {
+            let nd = d + w;
+            if nd < dist[v] {
+                dist[v] = nd;
+                heap.push(nd, v);
+            }
+        }
+    }
+    dist
+}
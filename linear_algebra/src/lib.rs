@@ -0,0 +1,250 @@
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use modint::{ModInt, Modulus};
+
+mod xor_basis;
+
+pub use xor_basis::XorBasis;
+
+/// scalars for elimination, ModInt over a prime modulus or f64
+pub trait Field:
+    Copy
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+{
+    fn zero() -> Self;
+    fn one() -> Self;
+    fn is_zero(&self) -> bool;
+    /// the row with the largest weight in a column becomes the pivot
+    fn weight(&self) -> f64;
+}
+
+impl<M: Modulus> Field for ModInt<M> {
+    fn zero() -> Self {
+        ModInt::default()
+    }
+    fn one() -> Self {
+        ModInt::new(1)
+    }
+    fn is_zero(&self) -> bool {
+        self.val() == 0
+    }
+    fn weight(&self) -> f64 {
+        if self.is_zero() {
+            0.0
+        } else {
+            1.0
+        }
+    }
+}
+
+const EPS: f64 = 1e-9;
+
+/// partial pivoting, values below 1e-9 in absolute value count as zero
+impl Field for f64 {
+    fn zero() -> Self {
+        0.0
+    }
+    fn one() -> Self {
+        1.0
+    }
+    fn is_zero(&self) -> bool {
+        self.abs() < EPS
+    }
+    fn weight(&self) -> f64 {
+        self.abs()
+    }
+}
+
+/// bring the first `cols` columns of a to reduced row echelon form in place,
+/// other columns follow the row operations \
+/// return the pivot columns and the determinant factor of the operations
+fn eliminate<T: Field>(a: &mut [Vec<T>], cols: usize) -> (Vec<usize>, T) {
+    let mut piv = vec![];
+    let mut det = T::one();
+    for c in 0..cols {
+        let r = piv.len();
+        let Some(best) =
+            (r..a.len()).max_by(|&i, &j| a[i][c].weight().total_cmp(&a[j][c].weight()))
+        else {
+            break;
+        };
+        if a[best][c].is_zero() {
+            det = T::zero();
+            continue;
+        }
+        if best != r {
+            a.swap(best, r);
+            det = -det;
+        }
+        let p = a[r][c];
+        det = det * p;
+        for x in a[r].iter_mut() {
+            *x = *x / p;
+        }
+        for i in 0..a.len() {
+            let f = a[i][c];
+            if i != r && !f.is_zero() {
+                for j in 0..a[i].len() {
+                    let v = a[r][j];
+                    a[i][j] = a[i][j] - f * v;
+                }
+            }
+        }
+        piv.push(c);
+    }
+    (piv, det)
+}
+
+pub fn rank<T: Field>(mut a: Vec<Vec<T>>) -> usize {
+    let cols = a.first().map_or(0, |r| r.len());
+    eliminate(&mut a, cols).0.len()
+}
+
+/// a must be square
+pub fn det<T: Field>(mut a: Vec<Vec<T>>) -> T {
+    let n = a.len();
+    assert!(a.iter().all(|r| r.len() == n), "matrix is not square!");
+    let (piv, det) = eliminate(&mut a, n);
+    if piv.len() < n {
+        T::zero()
+    } else {
+        det
+    }
+}
+
+/// None if a is singular, a must be square
+pub fn inverse<T: Field>(a: Vec<Vec<T>>) -> Option<Vec<Vec<T>>> {
+    let n = a.len();
+    assert!(a.iter().all(|r| r.len() == n), "matrix is not square!");
+    let mut aug: Vec<Vec<T>> = a
+        .into_iter()
+        .enumerate()
+        .map(|(i, mut r)| {
+            r.extend((0..n).map(|j| if i == j { T::one() } else { T::zero() }));
+            r
+        })
+        .collect();
+    if eliminate(&mut aug, n).0.len() < n {
+        return None;
+    }
+    Some(aug.into_iter().map(|r| r[n..].to_vec()).collect())
+}
+
+/// all x with a x = b as (x0, basis), every solution is x0 plus a combination of the basis \
+/// None if there is none; a is n x m and |b| = n
+pub fn solve<T: Field>(a: Vec<Vec<T>>, b: Vec<T>) -> Option<(Vec<T>, Vec<Vec<T>>)> {
+    assert_eq!(a.len(), b.len(), "lengths differ!");
+    let m = a.first().map_or(0, |r| r.len());
+    let mut aug: Vec<Vec<T>> = a
+        .into_iter()
+        .zip(b)
+        .map(|(mut r, y)| {
+            r.push(y);
+            r
+        })
+        .collect();
+    let (piv, _) = eliminate(&mut aug, m);
+    if aug[piv.len()..].iter().any(|r| !r[m].is_zero()) {
+        return None;
+    }
+    let mut x0 = vec![T::zero(); m];
+    for (r, &c) in piv.iter().enumerate() {
+        x0[c] = aug[r][m];
+    }
+    // one basis vector per free column f, with x[f] = 1 and pivots solved for
+    let mut is_piv = vec![false; m];
+    for &c in &piv {
+        is_piv[c] = true;
+    }
+    let basis = (0..m)
+        .filter(|&f| !is_piv[f])
+        .map(|f| {
+            let mut v = vec![T::zero(); m];
+            v[f] = T::one();
+            for (r, &c) in piv.iter().enumerate() {
+                v[c] = -aug[r][f];
+            }
+            v
+        })
+        .collect();
+    Some((x0, basis))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{det, inverse, rank, solve, XorBasis};
+    use modint::ModInt998244353;
+
+    type Mint = ModInt998244353;
+
+    fn mat(a: &[&[i64]]) -> Vec<Vec<Mint>> {
+        a.iter()
+            .map(|r| r.iter().map(|&x| Mint::new(x)).collect())
+            .collect()
+    }
+
+    fn mul(a: &[Vec<Mint>], x: &[Mint]) -> Vec<Mint> {
+        a.iter()
+            .map(|r| r.iter().zip(x).map(|(&p, &q)| p * q).sum())
+            .collect()
+    }
+
+    #[test]
+    fn gauss_test() {
+        let a = mat(&[&[2, 0, 1], &[1, 3, 2], &[1, 1, 2]]);
+        assert_eq!(det(a.clone()), Mint::new(6));
+        let inv = inverse(a.clone()).unwrap();
+        for i in 0..3 {
+            let col: Vec<Mint> = a.iter().map(|r| r[i]).collect();
+            let e: Vec<Mint> = (0..3).map(|j| Mint::new((i == j) as u8)).collect();
+            assert_eq!(mul(&inv, &col), e);
+        }
+        // rank 2: row 3 = row 1 + row 2
+        let s = mat(&[&[1, 2, 3, 4], &[0, 1, 1, 1], &[1, 3, 4, 5]]);
+        assert_eq!(rank(s.clone()), 2);
+        assert!(inverse(mat(&[&[1, 2], &[2, 4]])).is_none());
+        assert_eq!(det(mat(&[&[0, 1], &[1, 0]])), Mint::new(-1));
+        let b = mat(&[&[10, 3, 13]]).remove(0);
+        let (x0, basis) = solve(s.clone(), b.clone()).unwrap();
+        assert_eq!((mul(&s, &x0), basis.len()), (b, 2));
+        for v in &basis {
+            assert!(mul(&s, v).iter().all(|x| x.val() == 0));
+        }
+        assert!(solve(s, mat(&[&[10, 3, 14]]).remove(0)).is_none());
+        let f = vec![vec![1e-3, 2.0], vec![3.0, 4.0]];
+        assert!((det(f.clone()) - (4e-3 - 6.0)).abs() < 1e-9);
+        let (x, basis) = solve(f, vec![2.001, 7.0]).unwrap();
+        assert!(basis.is_empty() && (x[0] - 1.0).abs() < 1e-9 && (x[1] - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn xor_basis_test() {
+        let mut a = XorBasis::new();
+        assert!(a.insert(0b1100) && a.insert(0b1010) && !a.insert(0b0110));
+        let mut b = XorBasis::new();
+        b.insert(0b0001);
+        b.insert(0b1101);
+        a.merge(&b);
+        assert_eq!(a.len(), 3);
+        let span: Vec<u64> = (0..16).filter(|&x| a.contains(x)).collect();
+        assert_eq!(span.len(), 8);
+        assert_eq!(
+            (0..8).map(|k| a.kth_xor(k).unwrap()).collect::<Vec<_>>(),
+            span
+        );
+        assert_eq!((a.kth_xor(8), a.max_xor()), (None, 0b1101));
+        assert_eq!(a.max_xor_with(0b10000), 0b11101);
+        let mut c = XorBasis::new();
+        for i in 0..64 {
+            c.insert(u64::MAX >> i);
+        }
+        assert_eq!(
+            (c.len(), c.kth_xor(u64::MAX), c.max_xor()),
+            (64, Some(u64::MAX), u64::MAX)
+        );
+    }
+}
@@ -0,0 +1,96 @@
+/// basis of the span of u64 values under xor, b\[i\] has highest bit i or is 0
+#[derive(Clone, Debug)]
+pub struct XorBasis {
+    b: [u64; 64],
+    len: usize,
+}
+
+impl Default for XorBasis {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl XorBasis {
+    pub fn new() -> Self {
+        Self { b: [0; 64], len: 0 }
+    }
+
+    /// dimension of the span
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    // x with every basis bit cleared from the top
+    fn reduce(&self, mut x: u64) -> u64 {
+        for i in (0..64).rev() {
+            if x >> i & 1 == 1 {
+                x ^= self.b[i];
+            }
+        }
+        x
+    }
+
+    /// false if x was already in the span
+    pub fn insert(&mut self, x: u64) -> bool {
+        let x = self.reduce(x);
+        if x == 0 {
+            return false;
+        }
+        self.b[63 - x.leading_zeros() as usize] = x;
+        self.len += 1;
+        true
+    }
+
+    pub fn contains(&self, x: u64) -> bool {
+        self.reduce(x) == 0
+    }
+
+    /// max of x ^ s over s in the span
+    pub fn max_xor_with(&self, mut x: u64) -> u64 {
+        for i in (0..64).rev() {
+            x = x.max(x ^ self.b[i]);
+        }
+        x
+    }
+
+    /// max value in the span
+    pub fn max_xor(&self) -> u64 {
+        self.max_xor_with(0)
+    }
+
+    /// k-th smallest value in the span counting from 0, which is 0 itself \
+    /// None if k >= 2^len
+    pub fn kth_xor(&self, k: u64) -> Option<u64> {
+        if self.len < 64 && k >> self.len != 0 {
+            return None;
+        }
+        // reduced basis: no basis vector has another one's top bit set
+        let mut r = self.b;
+        for i in 0..64 {
+            for j in i + 1..64 {
+                if r[j] >> i & 1 == 1 {
+                    r[j] ^= r[i];
+                }
+            }
+        }
+        let mut res = 0;
+        for (bit, &v) in r.iter().filter(|&&v| v != 0).enumerate() {
+            if k >> bit & 1 == 1 {
+                res ^= v;
+            }
+        }
+        Some(res)
+    }
+
+    /// add all of other's span into self
+    pub fn merge(&mut self, other: &XorBasis) {
+        for &v in other.b.iter().filter(|&&v| v != 0) {
+            self.insert(v);
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use std::cmp::Ordering;
+
+/// return indices of a longest increasing subsequence of a \
+/// strict: a\[i\] < a\[j\], otherwise a\[i\] <= a\[j\]
+pub fn lis<T: Ord>(a: &[T], strict: bool) -> Vec<usize> {
+    let n = a.len();
+    // tail[k]: index of the smallest last element of a subsequence of length k + 1
+    let mut tail: Vec<usize> = Vec::with_capacity(n);
+    let mut pre = vec![usize::MAX; n];
+    for i in 0..n {
+        let k = tail.partition_point(|&j| if strict { a[j] < a[i] } else { a[j] <= a[i] });
+        if k > 0 {
+            pre[i] = tail[k - 1];
+        }
+        if k == tail.len() {
+            tail.push(i);
+        } else {
+            tail[k] = i;
+        }
+    }
+    let mut res = Vec::with_capacity(tail.len());
+    let mut now = tail.last().copied().unwrap_or(usize::MAX);
+    while now != usize::MAX {
+        res.push(now);
+        now = pre[now];
+    }
+    res.reverse();
+    res
+}
+
+// sort by x, ties broken on y so that equal x can never be chained when strict
+fn chain_order<X: Ord, Y: Ord>(p: &[(X, Y)], strict: bool) -> Vec<usize> {
+    let mut ord: Vec<usize> = (0..p.len()).collect();
+    ord.sort_by(|&i, &j| match p[i].0.cmp(&p[j].0) {
+        Ordering::Equal if strict => p[j].1.cmp(&p[i].1),
+        Ordering::Equal => p[i].1.cmp(&p[j].1),
+        o => o,
+    });
+    ord
+}
+
+/// return indices of a longest chain, sorted along the chain \
+/// strict: x and y both increase strictly, otherwise both don't decrease
+pub fn longest_chain<X: Ord, Y: Ord>(p: &[(X, Y)], strict: bool) -> Vec<usize> {
+    let ord = chain_order(p, strict);
+    let ys: Vec<&Y> = ord.iter().map(|&i| &p[i].1).collect();
+    lis(&ys, strict).into_iter().map(|k| ord[k]).collect()
+}
+
+/// minimum number of chains (same order as `longest_chain`) covering all points \
+/// by Dilworth, its size equals the largest antichain
+pub fn min_chain_cover<X: Ord, Y: Ord>(p: &[(X, Y)], strict: bool) -> Vec<Vec<usize>> {
+    let ord = chain_order(p, strict);
+    // tails are kept in decreasing y order, put each point after the largest fitting tail
+    let mut tail: Vec<usize> = Vec::new();
+    let mut chains: Vec<Vec<usize>> = Vec::new();
+    for i in ord {
+        let k = tail.partition_point(|&j| {
+            if strict {
+                p[j].1 >= p[i].1
+            } else {
+                p[j].1 > p[i].1
+            }
+        });
+        if k == tail.len() {
+            tail.push(i);
+            chains.push(vec![i]);
+        } else {
+            tail[k] = i;
+            chains[k].push(i);
+        }
+    }
+    chains
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{lis, longest_chain, min_chain_cover};
+
+    #[test]
+    fn lis_test() {
+        let a = [3, 1, 2, 2, 5, 4, 6];
+        assert_eq!(lis(&a, true).len(), 4);
+        assert_eq!(lis(&a, false), vec![1, 2, 3, 5, 6]);
+    }
+
+    #[test]
+    fn chain_test() {
+        let p = [(1, 1), (1, 2), (2, 2), (3, 1), (3, 3)];
+        assert_eq!(longest_chain(&p, true).len(), 3);
+        assert_eq!(longest_chain(&p, false).len(), 4);
+        for strict in [true, false] {
+            let chains = min_chain_cover(&p, strict);
+            for c in &chains {
+                for w in c.windows(2) {
+                    let (a, b) = (p[w[0]], p[w[1]]);
+                    if strict {
+                        assert!(a.0 < b.0 && a.1 < b.1);
+                    } else {
+                        assert!(a.0 <= b.0 && a.1 <= b.1);
+                    }
+                }
+            }
+            assert_eq!(chains.iter().map(|c| c.len()).sum::<usize>(), p.len());
+            assert_eq!(chains.len(), if strict { 3 } else { 2 });
+        }
+    }
+}
@@ -9,20 +9,18 @@ pub struct MoQuery<const K: usize> {
 
 impl<const K: usize> PartialOrd for MoQuery<K> {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        if self.l / K != other.l / K {
-            Some(self.l.cmp(&other.l))
-        } else {
-            if (self.l / K) % 2 == 0 {
-                Some(self.r.cmp(&other.r))
-            } else {
-                Some(self.r.cmp(&other.r).reverse())
-            }
-        }
+        Some(self.cmp(other))
     }
 }
 
 impl<const K: usize> Ord for MoQuery<K> {
     fn cmp(&self, other: &Self) -> Ordering {
-        self.partial_cmp(other).unwrap()
+        if self.l / K != other.l / K {
+            self.l.cmp(&other.l)
+        } else if (self.l / K).is_multiple_of(2) {
+            self.r.cmp(&other.r)
+        } else {
+            self.r.cmp(&other.r).reverse()
+        }
     }
 }
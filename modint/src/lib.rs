@@ -0,0 +1,304 @@
+use std::cell::Cell;
+use std::fmt;
+use std::iter::{Product, Sum};
+use std::marker::PhantomData;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+mod mont64;
+
+pub use mont64::DynamicModInt64;
+
+/// where a ModInt takes its modulus from, 1 <= modulus < 2^31
+pub trait Modulus {
+    fn modulus() -> u32;
+}
+
+/// modulus fixed at compile time
+pub struct ConstMod<const M: u32>;
+impl<const M: u32> Modulus for ConstMod<M> {
+    fn modulus() -> u32 {
+        M
+    }
+}
+
+thread_local! {
+    static DYNAMIC_MODULUS: Cell<u32> = const { Cell::new(998244353) };
+}
+
+/// modulus set at runtime by `DynamicModInt::set_modulus`, per thread like `DynamicModInt64`
+pub struct DynamicMod;
+impl Modulus for DynamicMod {
+    fn modulus() -> u32 {
+        DYNAMIC_MODULUS.with(|m| m.get())
+    }
+}
+
+/// integer modulo M::modulus(), always kept in [0, modulus)
+pub struct ModInt<M: Modulus> {
+    v: u32,
+    _m: PhantomData<fn() -> M>,
+}
+
+pub type StaticModInt<const M: u32> = ModInt<ConstMod<M>>;
+pub type DynamicModInt = ModInt<DynamicMod>;
+pub type ModInt998244353 = StaticModInt<998244353>;
+pub type ModInt1000000007 = StaticModInt<1000000007>;
+
+impl DynamicModInt {
+    /// values created before keep their representative but mean nothing under the new modulus \
+    /// only affects the calling thread, every other thread keeps its own (998244353 by default)
+    pub fn set_modulus(m: u32) {
+        assert!((1..1 << 31).contains(&m), "modulus out of range!");
+        DYNAMIC_MODULUS.with(|c| c.set(m));
+    }
+}
+
+impl<M: Modulus> ModInt<M> {
+    pub fn modulus() -> u32 {
+        M::modulus()
+    }
+
+    /// v must already be below the modulus
+    pub fn raw(v: u32) -> Self {
+        debug_assert!(v < M::modulus(), "value out of range!");
+        Self { v, _m: PhantomData }
+    }
+
+    pub fn new<T: Into<Self>>(v: T) -> Self {
+        v.into()
+    }
+
+    pub fn val(self) -> u32 {
+        self.v
+    }
+
+    pub fn pow(self, mut e: u64) -> Self {
+        let (mut a, mut res) = (self, Self::new(1));
+        while e > 0 {
+            if e & 1 == 1 {
+                res *= a;
+            }
+            a *= a;
+            e >>= 1;
+        }
+        res
+    }
+
+    /// None unless gcd(self, modulus) = 1, the modulus need not be prime
+    pub fn checked_inv(self) -> Option<Self> {
+        // extended Euclid on (v, m) keeping only the coefficient of v
+        let (mut a, mut b) = (self.v as i64, M::modulus() as i64);
+        let (mut x, mut y) = (1i64, 0i64);
+        while b != 0 {
+            let q = a / b;
+            (a, b) = (b, a - q * b);
+            (x, y) = (y, x - q * y);
+        }
+        (a == 1).then(|| Self::new(x))
+    }
+
+    /// panics if self is not invertible
+    pub fn inv(self) -> Self {
+        self.checked_inv().expect("not invertible!")
+    }
+}
+
+impl<M: Modulus> Clone for ModInt<M> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<M: Modulus> Copy for ModInt<M> {}
+
+impl<M: Modulus> PartialEq for ModInt<M> {
+    fn eq(&self, other: &Self) -> bool {
+        self.v == other.v
+    }
+}
+
+impl<M: Modulus> Eq for ModInt<M> {}
+
+impl<M: Modulus> std::hash::Hash for ModInt<M> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.v.hash(state);
+    }
+}
+
+impl<M: Modulus> Default for ModInt<M> {
+    fn default() -> Self {
+        Self::raw(0)
+    }
+}
+
+impl<M: Modulus> fmt::Display for ModInt<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.v, f)
+    }
+}
+
+impl<M: Modulus> fmt::Debug for ModInt<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (mod {})", self.v, M::modulus())
+    }
+}
+
+macro_rules! impl_from_unsigned {
+    ($($t:ty),*) => {
+        $(
+            impl<M: Modulus> From<$t> for ModInt<M> {
+                fn from(v: $t) -> Self {
+                    Self::raw((v as u128 % M::modulus() as u128) as u32)
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_from_signed {
+    ($($t:ty),*) => {
+        $(
+            impl<M: Modulus> From<$t> for ModInt<M> {
+                fn from(v: $t) -> Self {
+                    Self::raw((v as i128).rem_euclid(M::modulus() as i128) as u32)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_unsigned!(u8, u16, u32, u64, u128, usize);
+impl_from_signed!(i8, i16, i32, i64, i128, isize);
+
+impl<M: Modulus> Add for ModInt<M> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        let s = self.v + rhs.v;
+        let m = M::modulus();
+        Self::raw(if s >= m { s - m } else { s })
+    }
+}
+
+impl<M: Modulus> Sub for ModInt<M> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        let m = M::modulus();
+        Self::raw(if self.v >= rhs.v {
+            self.v - rhs.v
+        } else {
+            self.v + m - rhs.v
+        })
+    }
+}
+
+impl<M: Modulus> Mul for ModInt<M> {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        Self::raw((self.v as u64 * rhs.v as u64 % M::modulus() as u64) as u32)
+    }
+}
+
+impl<M: Modulus> Div for ModInt<M> {
+    type Output = Self;
+    /// panics if rhs is not invertible
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self {
+        self * rhs.inv()
+    }
+}
+
+impl<M: Modulus> Neg for ModInt<M> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self::default() - self
+    }
+}
+
+macro_rules! impl_assign {
+    ($($tr:ident $f:ident $op:ident),*) => {
+        $(
+            impl<M: Modulus> $tr for ModInt<M> {
+                fn $f(&mut self, rhs: Self) {
+                    *self = (*self).$op(rhs);
+                }
+            }
+        )*
+    };
+}
+
+impl_assign!(
+    AddAssign add_assign add,
+    SubAssign sub_assign sub,
+    MulAssign mul_assign mul,
+    DivAssign div_assign div
+);
+
+impl<M: Modulus> Sum for ModInt<M> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), |a, b| a + b)
+    }
+}
+
+impl<M: Modulus> Product for ModInt<M> {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::new(1), |a, b| a * b)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{DynamicModInt, DynamicModInt64, ModInt1000000007, ModInt998244353, StaticModInt};
+
+    #[test]
+    fn modint_test() {
+        type Mint = ModInt998244353;
+        let a = Mint::new(-1);
+        assert_eq!(a.val(), 998244352);
+        assert_eq!(a * a, Mint::new(1));
+        assert_eq!(Mint::new(3) / Mint::new(2) * Mint::new(2), Mint::new(3));
+        assert_eq!(Mint::new(3).pow(998244352), Mint::new(1));
+        let s: ModInt1000000007 = (1..=20u64).map(ModInt1000000007::new).product();
+        assert_eq!(s.to_string(), "146326063");
+        // inverses exist for units of a composite modulus
+        type M12 = StaticModInt<12>;
+        assert_eq!(M12::new(5).inv(), M12::new(5));
+        assert_eq!(M12::new(4).checked_inv(), None);
+        DynamicModInt::set_modulus(7);
+        let mut x = DynamicModInt::new(10);
+        x += DynamicModInt::new(5);
+        assert_eq!((x.val(), (-x).val(), x.inv().val()), (1, 6, 1));
+        assert_eq!(
+            (1..7).map(DynamicModInt::new).sum::<DynamicModInt>().val(),
+            0
+        );
+        // other threads keep their own modulus
+        let v = std::thread::spawn(|| DynamicModInt::new(-1).val())
+            .join()
+            .unwrap();
+        assert_eq!(v, 998244352);
+        assert_eq!(DynamicModInt::new(-1).val(), 6);
+    }
+
+    #[test]
+    fn dynamic_modint64_test() {
+        type Mint = DynamicModInt64;
+        let m = (1u64 << 61) - 1;
+        Mint::set_modulus(m);
+        let a = Mint::new(-1);
+        assert_eq!(a.val(), m - 1);
+        let b = Mint::new(123456789012345u64);
+        assert_eq!(
+            (b * b).val(),
+            (123456789012345u128.pow(2) % m as u128) as u64
+        );
+        assert_eq!(b.pow(m - 1), Mint::new(1));
+        assert_eq!((b / Mint::new(7) * Mint::new(7)).val(), 123456789012345);
+        // odd composite modulus close to 2^64
+        let n = u64::MAX - 58;
+        Mint::set_modulus(n);
+        let c = Mint::new(u64::MAX);
+        assert_eq!((c * c).val(), (58u128 * 58 % n as u128) as u64);
+        assert_eq!((c + c - c).val(), 58);
+        assert_eq!(Mint::new(0).checked_inv(), None);
+    }
+}
@@ -0,0 +1,181 @@
+use std::cell::Cell;
+use std::fmt;
+use std::iter::{Product, Sum};
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use number_theory::Montgomery;
+
+thread_local! {
+    static MONT: Cell<Montgomery> = Cell::new(Montgomery::new((1 << 61) - 1));
+}
+
+fn mont() -> Montgomery {
+    MONT.with(|m| m.get())
+}
+
+/// integer modulo an odd runtime modulus below 2^64, kept in Montgomery form \
+/// so multiplication needs no u128 division; the modulus is per thread
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct DynamicModInt64 {
+    v: u64,
+}
+
+impl DynamicModInt64 {
+    /// values created before keep their representative but mean nothing under the new modulus \
+    /// only affects the calling thread, every other thread keeps its own (2^61 - 1 by default)
+    pub fn set_modulus(m: u64) {
+        MONT.with(|c| c.set(Montgomery::new(m)));
+    }
+
+    pub fn modulus() -> u64 {
+        mont().modulus()
+    }
+
+    pub fn new<T: Into<Self>>(v: T) -> Self {
+        v.into()
+    }
+
+    pub fn val(self) -> u64 {
+        mont().from_mont(self.v)
+    }
+
+    pub fn pow(self, e: u64) -> Self {
+        Self {
+            v: mont().pow(self.v, e),
+        }
+    }
+
+    /// None unless gcd(self, modulus) = 1
+    pub fn checked_inv(self) -> Option<Self> {
+        let (mut a, mut b) = (self.val() as i128, Self::modulus() as i128);
+        let (mut x, mut y) = (1i128, 0i128);
+        while b != 0 {
+            let q = a / b;
+            (a, b) = (b, a - q * b);
+            (x, y) = (y, x - q * y);
+        }
+        (a == 1).then(|| Self::new(x))
+    }
+
+    /// panics if self is not invertible
+    pub fn inv(self) -> Self {
+        self.checked_inv().expect("not invertible!")
+    }
+}
+
+impl fmt::Display for DynamicModInt64 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.val(), f)
+    }
+}
+
+impl fmt::Debug for DynamicModInt64 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (mod {})", self.val(), Self::modulus())
+    }
+}
+
+macro_rules! impl_from_unsigned {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for DynamicModInt64 {
+                fn from(v: $t) -> Self {
+                    let mt = mont();
+                    let r = (v as u128 % mt.modulus() as u128) as u64;
+                    Self { v: mt.to_mont(r) }
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_from_signed {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for DynamicModInt64 {
+                fn from(v: $t) -> Self {
+                    let mt = mont();
+                    let r = (v as i128).rem_euclid(mt.modulus() as i128) as u64;
+                    Self { v: mt.to_mont(r) }
+                }
+            }
+        )*
+    };
+}
+
+impl_from_unsigned!(u8, u16, u32, u64, u128, usize);
+impl_from_signed!(i8, i16, i32, i64, i128, isize);
+
+impl Add for DynamicModInt64 {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        Self {
+            v: mont().add(self.v, rhs.v),
+        }
+    }
+}
+
+impl Sub for DynamicModInt64 {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        Self {
+            v: mont().sub(self.v, rhs.v),
+        }
+    }
+}
+
+impl Mul for DynamicModInt64 {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        Self {
+            v: mont().mul(self.v, rhs.v),
+        }
+    }
+}
+
+impl Div for DynamicModInt64 {
+    type Output = Self;
+    /// panics if rhs is not invertible
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self {
+        self * rhs.inv()
+    }
+}
+
+impl Neg for DynamicModInt64 {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self::default() - self
+    }
+}
+
+macro_rules! impl_assign {
+    ($($tr:ident $f:ident $op:ident),*) => {
+        $(
+            impl $tr for DynamicModInt64 {
+                fn $f(&mut self, rhs: Self) {
+                    *self = (*self).$op(rhs);
+                }
+            }
+        )*
+    };
+}
+
+impl_assign!(
+    AddAssign add_assign add,
+    SubAssign sub_assign sub,
+    MulAssign mul_assign mul,
+    DivAssign div_assign div
+);
+
+impl Sum for DynamicModInt64 {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), |a, b| a + b)
+    }
+}
+
+impl Product for DynamicModInt64 {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::new(1), |a, b| a * b)
+    }
+}
@@ -0,0 +1,95 @@
+/// gcd and lcm of primitive integers, the gcd by Stein's binary algorithm \
+/// signed types work on absolute values, the results are never negative,
+/// except that gcd wraps to MIN when the true gcd is -MIN, i.e. gcd(MIN, 0) and gcd(MIN, MIN)
+pub trait BinaryGcd: Copy {
+    fn gcd(self, b: Self) -> Self;
+    /// None on overflow, lcm(0, x) = 0
+    fn checked_lcm(self, b: Self) -> Option<Self>;
+}
+
+macro_rules! impl_binary_gcd {
+    ($($t:ty),*) => {
+        $(
+            impl BinaryGcd for $t {
+                fn gcd(self, b: Self) -> Self {
+                    let (mut a, mut b) = (self, b);
+                    if a == 0 || b == 0 {
+                        return a | b;
+                    }
+                    let s = (a | b).trailing_zeros();
+                    a >>= a.trailing_zeros();
+                    loop {
+                        b >>= b.trailing_zeros();
+                        if a > b {
+                            (a, b) = (b, a);
+                        }
+                        b -= a;
+                        if b == 0 {
+                            return a << s;
+                        }
+                    }
+                }
+
+                fn checked_lcm(self, b: Self) -> Option<Self> {
+                    if self == 0 || b == 0 {
+                        return Some(0);
+                    }
+                    (self / self.gcd(b)).checked_mul(b)
+                }
+            }
+        )*
+    };
+}
+
+impl_binary_gcd!(u8, u16, u32, u64, u128, usize);
+
+macro_rules! impl_binary_gcd_signed {
+    ($($t:ty),*) => {
+        $(
+            impl BinaryGcd for $t {
+                /// wraps to MIN when the gcd is -MIN, e.g. gcd(MIN, 0)
+                fn gcd(self, b: Self) -> Self {
+                    self.unsigned_abs().gcd(b.unsigned_abs()) as Self
+                }
+
+                fn checked_lcm(self, b: Self) -> Option<Self> {
+                    self.unsigned_abs()
+                        .checked_lcm(b.unsigned_abs())?
+                        .try_into()
+                        .ok()
+                }
+            }
+        )*
+    };
+}
+
+impl_binary_gcd_signed!(i8, i16, i32, i64, i128, isize);
+
+/// gcd(0, 0) = 0
+pub fn binary_gcd<T: BinaryGcd>(a: T, b: T) -> T {
+    a.gcd(b)
+}
+
+/// panics on overflow, see `checked_lcm`
+pub fn lcm<T: BinaryGcd>(a: T, b: T) -> T {
+    a.checked_lcm(b).expect("lcm overflow!")
+}
+
+pub fn checked_lcm<T: BinaryGcd>(a: T, b: T) -> Option<T> {
+    a.checked_lcm(b)
+}
+
+/// gcd of nothing is 0
+pub fn gcd_slice<T: BinaryGcd + Default>(v: &[T]) -> T {
+    v.iter().fold(T::default(), |g, &x| g.gcd(x))
+}
+
+/// lcm of nothing is 1, panics on overflow
+pub fn lcm_slice<T: BinaryGcd + From<u8>>(v: &[T]) -> T {
+    checked_lcm_slice(v).expect("lcm overflow!")
+}
+
+/// None if the lcm overflows
+pub fn checked_lcm_slice<T: BinaryGcd + From<u8>>(v: &[T]) -> Option<T> {
+    v.iter().try_fold(T::from(1), |l, &x| l.checked_lcm(x))
+}
@@ -0,0 +1,197 @@
+use std::collections::HashMap;
+
+use crate::{binary_gcd, euler_vec, gen_all_factors, is_prime_u64, Montgomery};
+
+// some nontrivial factor of an odd composite n, iterates in Montgomery form
+// where gcds with n are unchanged
+fn pollard_rho(n: u64) -> u64 {
+    let mt = Montgomery::new(n);
+    for c in 1u64.. {
+        let f = |x: u64| mt.add(mt.mul(x, x), c);
+        let (mut y, mut q, mut g) = (2u64, mt.one(), 1u64);
+        // Brent's cycle finding, gcd taken once per 128 steps
+        let mut len = 1;
+        while g == 1 {
+            let x = y;
+            for _ in 0..len {
+                y = f(y);
+            }
+            let mut k = 0;
+            while k < len && g == 1 {
+                let ys = y;
+                for _ in 0..128.min(len - k) {
+                    y = f(y);
+                    q = mt.mul(q, x.abs_diff(y));
+                }
+                g = binary_gcd(q, n);
+                if g == n {
+                    // the batch overshot, redo it one step at a time
+                    y = ys;
+                    g = 1;
+                    while g == 1 {
+                        y = f(y);
+                        g = binary_gcd(x.abs_diff(y), n);
+                    }
+                }
+                k += 128;
+            }
+            len *= 2;
+        }
+        if g != n {
+            return g;
+        }
+    }
+    unreachable!()
+}
+
+fn rho_collect(x: u64, res: &mut Vec<u64>) {
+    if x == 1 {
+        return;
+    }
+    if is_prime_u64(x) {
+        res.push(x);
+        return;
+    }
+    let d = pollard_rho(x);
+    rho_collect(d, res);
+    rho_collect(x / d, res);
+}
+
+/// prime factors of n with exponents in increasing order, without any table \
+/// trial division by small numbers, then Miller-Rabin and Pollard rho on what is left;
+/// factor(0) and factor(1) are empty
+pub fn factor(mut n: u64) -> Vec<(u64, usize)> {
+    let mut res = vec![];
+    if n == 0 {
+        return res;
+    }
+    for p in (2..4).chain((5..1000).step_by(2)) {
+        if n.is_multiple_of(p) {
+            let mut c = 0;
+            while n.is_multiple_of(p) {
+                n /= p;
+                c += 1;
+            }
+            res.push((p, c));
+        }
+    }
+    let mut ps = vec![];
+    rho_collect(n, &mut ps);
+    ps.sort_unstable();
+    for p in ps {
+        match res.last_mut() {
+            Some((q, c)) if *q == p => *c += 1,
+            _ => res.push((p, 1)),
+        }
+    }
+    res
+}
+
+/// sieve up to n plus memoized Pollard rho for larger numbers, \
+/// answers every query for x < 2^64
+pub struct NumberTheoryCtx {
+    minf: Vec<usize>,
+    pr: Vec<usize>,
+    cache: HashMap<u64, Vec<(u64, usize)>>,
+}
+
+impl NumberTheoryCtx {
+    pub fn new(n: usize) -> Self {
+        let (minf, pr) = euler_vec(n);
+        Self {
+            minf,
+            pr,
+            cache: HashMap::new(),
+        }
+    }
+
+    /// sieve limit
+    pub fn limit(&self) -> usize {
+        self.minf.len() - 1
+    }
+
+    /// primes up to the sieve limit
+    pub fn primes(&self) -> &[usize] {
+        &self.pr
+    }
+
+    pub fn is_prime(&self, x: u64) -> bool {
+        if x <= self.limit() as u64 {
+            x >= 2 && self.minf[x as usize] == x as usize
+        } else {
+            is_prime_u64(x)
+        }
+    }
+
+    fn collect(&mut self, x: u64, res: &mut Vec<u64>) {
+        if x <= 1 {
+            return;
+        }
+        if x <= self.limit() as u64 {
+            let mut x = x as usize;
+            while x > 1 {
+                res.push(self.minf[x] as u64);
+                x /= self.minf[x];
+            }
+        } else if x.is_multiple_of(2) {
+            res.push(2);
+            self.collect(x / 2, res);
+        } else if self.is_prime(x) {
+            res.push(x);
+        } else {
+            let d = pollard_rho(x);
+            self.collect(d, res);
+            self.collect(x / d, res);
+        }
+    }
+
+    /// prime factors with exponents, in increasing order
+    pub fn factorize(&mut self, x: u64) -> Vec<(u64, usize)> {
+        if let Some(f) = self.cache.get(&x) {
+            return f.clone();
+        }
+        let mut ps = vec![];
+        self.collect(x, &mut ps);
+        ps.sort_unstable();
+        let mut res: Vec<(u64, usize)> = vec![];
+        for p in ps {
+            match res.last_mut() {
+                Some((q, c)) if *q == p => *c += 1,
+                _ => res.push((p, 1)),
+            }
+        }
+        if x > self.limit() as u64 {
+            self.cache.insert(x, res.clone());
+        }
+        res
+    }
+
+    /// all divisors, including 1 and x, unordered
+    pub fn divisors(&mut self, x: u64) -> Vec<u64> {
+        let f: Vec<(usize, usize)> = self
+            .factorize(x)
+            .into_iter()
+            .map(|(p, c)| (p as usize, c))
+            .collect();
+        gen_all_factors(&f).into_iter().map(|d| d as u64).collect()
+    }
+
+    pub fn phi(&mut self, x: u64) -> u64 {
+        self.factorize(x)
+            .into_iter()
+            .fold(x, |res, (p, _)| res / p * (p - 1))
+    }
+
+    /// x must be positive
+    pub fn mu(&mut self, x: u64) -> i32 {
+        assert!(x >= 1, "mu(0) is undefined!");
+        let f = self.factorize(x);
+        if f.iter().any(|&(_, c)| c > 1) {
+            0
+        } else if f.len().is_multiple_of(2) {
+            1
+        } else {
+            -1
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use std::ops::{Add, Sub};
+
+use crate::euler_vec;
+
+// primes up to a.len() - 1, the arrays below are indexed 1..=n with a[0] left alone
+fn primes<T>(a: &[T]) -> (usize, Vec<usize>) {
+    let n = a.len().saturating_sub(1);
+    (n, euler_vec(n).1)
+}
+
+/// a\[i\] becomes the sum of a\[d\] over d | i, O(n log log n)
+pub fn divisor_zeta<T: Copy + Add<Output = T>>(a: &mut [T]) {
+    let (n, pr) = primes(a);
+    for p in pr {
+        for i in 1..=n / p {
+            a[i * p] = a[i * p] + a[i];
+        }
+    }
+}
+
+/// inverse of divisor_zeta
+pub fn divisor_moebius<T: Copy + Sub<Output = T>>(a: &mut [T]) {
+    let (n, pr) = primes(a);
+    for p in pr {
+        for i in (1..=n / p).rev() {
+            a[i * p] = a[i * p] - a[i];
+        }
+    }
+}
+
+/// a\[i\] becomes the sum of a\[m\] over multiples m of i up to n, O(n log log n)
+pub fn multiple_zeta<T: Copy + Add<Output = T>>(a: &mut [T]) {
+    let (n, pr) = primes(a);
+    for p in pr {
+        for i in (1..=n / p).rev() {
+            a[i] = a[i] + a[i * p];
+        }
+    }
+}
+
+/// inverse of multiple_zeta
+pub fn multiple_moebius<T: Copy + Sub<Output = T>>(a: &mut [T]) {
+    let (n, pr) = primes(a);
+    for p in pr {
+        for i in 1..=n / p {
+            a[i] = a[i] - a[i * p];
+        }
+    }
+}
@@ -1,10 +1,123 @@
-use std::ops::{Add, Rem};
+use std::collections::HashMap;
+use std::ops::{Add, Mul, Rem};
 
-pub fn gcd<T>(a: T, b: T) -> T
+use arena::ScratchVec;
+
+mod binary_gcd;
+mod ctx;
+mod dirichlet;
+mod montgomery;
+pub use binary_gcd::{
+    binary_gcd, checked_lcm, checked_lcm_slice, gcd_slice, lcm, lcm_slice, BinaryGcd,
+};
+pub use ctx::{factor, NumberTheoryCtx};
+pub use dirichlet::{divisor_moebius, divisor_zeta, multiple_moebius, multiple_zeta};
+pub use montgomery::Montgomery;
+
+/// Euclid's gcd for any integer type, b + b must not overflow, see `binary_gcd` for unsigned types
+pub fn gcd<T>(mut a: T, mut b: T) -> T
 where
     T: Add<Output = T> + Rem<Output = T> + Copy + PartialEq,
 {
-    return if b + b == b { a } else { gcd(b, a % b) };
+    while b + b != b {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// modular multiplication and exponentiation without overflow
+pub trait ModArith: Copy {
+    fn mul_mod(self, b: Self, m: Self) -> Self;
+    fn pow_mod(self, e: Self, m: Self) -> Self;
+}
+
+impl ModArith for u64 {
+    fn mul_mod(self, b: Self, m: Self) -> Self {
+        (self as u128 * b as u128 % m as u128) as u64
+    }
+
+    fn pow_mod(self, mut e: Self, m: Self) -> Self {
+        let (mut a, mut res) = (self % m, 1 % m);
+        while e > 0 {
+            if e & 1 == 1 {
+                res = res.mul_mod(a, m);
+            }
+            a = a.mul_mod(a, m);
+            e >>= 1;
+        }
+        res
+    }
+}
+
+impl ModArith for u128 {
+    fn mul_mod(self, b: Self, m: Self) -> Self {
+        let (mut a, mut b) = (self % m, b % m);
+        if m <= u64::MAX as u128 {
+            return a * b % m;
+        }
+        // double and add, a + b can not overflow in the form below
+        let mut res = 0;
+        while b > 0 {
+            if b & 1 == 1 {
+                res = if res >= m - a { res - (m - a) } else { res + a };
+            }
+            a = if a >= m - a { a - (m - a) } else { a + a };
+            b >>= 1;
+        }
+        res
+    }
+
+    fn pow_mod(self, mut e: Self, m: Self) -> Self {
+        let (mut a, mut res) = (self % m, 1 % m);
+        while e > 0 {
+            if e & 1 == 1 {
+                res = res.mul_mod(a, m);
+            }
+            a = a.mul_mod(a, m);
+            e >>= 1;
+        }
+        res
+    }
+}
+
+/// a * b % m for u64 or u128
+pub fn mul_mod<T: ModArith>(a: T, b: T, m: T) -> T {
+    a.mul_mod(b, m)
+}
+
+/// base ^ exp % m for u64 or u128, 0 ^ 0 = 1
+pub fn pow_mod<T: ModArith>(base: T, exp: T, m: T) -> T {
+    base.pow_mod(exp, m)
+}
+
+/// deterministic Miller-Rabin, the 7 bases below are enough for every u64
+pub fn is_prime_u64(n: u64) -> bool {
+    if n < 2 {
+        return false;
+    }
+    for p in [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37] {
+        if n.is_multiple_of(p) {
+            return n == p;
+        }
+    }
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+    let mt = Montgomery::new(n);
+    let (one, minus_one) = (mt.one(), n - mt.one());
+    'outer: for a in [2, 325, 9375, 28178, 450775, 9780504, 1795265022] {
+        let mut x = mt.pow(mt.to_mont(a), d);
+        if x == 0 || x == one || x == minus_one {
+            continue;
+        }
+        for _ in 1..s {
+            x = mt.mul(x, x);
+            if x == minus_one {
+                continue 'outer;
+            }
+        }
+        return false;
+    }
+    true
 }
 
 pub fn euler_vec(n: usize) -> (Vec<usize>, Vec<usize>) {
@@ -25,15 +138,197 @@ pub fn euler_vec(n: usize) -> (Vec<usize>, Vec<usize>) {
     (minf, pr)
 }
 
+/// μ(i) for all i <= n by the linear sieve, μ(0) = 0
+pub fn mobius_vec(n: usize) -> Vec<i8> {
+    let mut mu = vec![0i8; n + 1];
+    let mut minf = ScratchVec::from_elem(0usize, n + 1);
+    let mut pr = ScratchVec::with_capacity(500);
+    if n >= 1 {
+        mu[1] = 1;
+    }
+    for i in 2..=n {
+        if minf[i] == 0 {
+            minf[i] = i;
+            mu[i] = -1;
+            pr.push(i);
+        }
+        for &p in &pr {
+            if p > minf[i] || p > n / i {
+                break;
+            }
+            minf[i * p] = p;
+            // p * p divides i * p when p is the smallest factor of i
+            mu[i * p] = if p == minf[i] { 0 } else { -mu[i] };
+        }
+    }
+    mu
+}
+
+/// φ(i) for all i <= n by the linear sieve, φ(0) = 0
+pub fn phi_vec(n: usize) -> Vec<usize> {
+    let mut phi = vec![0usize; n + 1];
+    let mut minf = ScratchVec::from_elem(0usize, n + 1);
+    let mut pr = ScratchVec::with_capacity(500);
+    if n >= 1 {
+        phi[1] = 1;
+    }
+    for i in 2..=n {
+        if minf[i] == 0 {
+            minf[i] = i;
+            phi[i] = i - 1;
+            pr.push(i);
+        }
+        for &p in &pr {
+            if p > minf[i] || p > n / i {
+                break;
+            }
+            minf[i * p] = p;
+            phi[i * p] = if p == minf[i] {
+                phi[i] * p
+            } else {
+                phi[i] * (p - 1)
+            };
+        }
+    }
+    phi
+}
+
+/// Mertens function M(i) = μ(1) + ... + μ(i) for all i <= n, M(0) = 0
+pub fn mertens_vec(n: usize) -> Vec<i64> {
+    let mut res = Vec::with_capacity(n + 1);
+    let mut sum = 0;
+    for m in mobius_vec(n) {
+        sum += m as i64;
+        res.push(sum);
+    }
+    res
+}
+
+/// d(i), the number of divisors, for all i <= n, d(0) = 0 \
+/// a linear pass over the `minf` table of `euler_vec`
+pub fn divisor_count_vec(n: usize) -> Vec<u32> {
+    let (minf, _) = euler_vec(n);
+    let mut d = vec![0u32; n + 1];
+    // exponent of the smallest prime factor
+    let mut e = ScratchVec::from_elem(0u32, n + 1);
+    if n >= 1 {
+        d[1] = 1;
+    }
+    for i in 2..=n {
+        let (p, j) = (minf[i], i / minf[i]);
+        if minf[j] == p {
+            e[i] = e[j] + 1;
+            d[i] = d[j] / (e[j] + 1) * (e[i] + 1);
+        } else {
+            e[i] = 1;
+            d[i] = d[j] * 2;
+        }
+    }
+    d
+}
+
+/// σ(i), the sum of divisors, for all i <= n, σ(0) = 0 \
+/// a linear pass over the `minf` table of `euler_vec`
+pub fn divisor_sum_vec(n: usize) -> Vec<u64> {
+    let (minf, _) = euler_vec(n);
+    let mut sigma = vec![0u64; n + 1];
+    // 1 + p + ... + p^e for the smallest prime factor p
+    let mut sp = ScratchVec::from_elem(0u64, n + 1);
+    if n >= 1 {
+        sigma[1] = 1;
+    }
+    for i in 2..=n {
+        let (p, j) = (minf[i], i / minf[i]);
+        if minf[j] == p {
+            sp[i] = sp[j] * p as u64 + 1;
+            sigma[i] = sigma[j] / sp[j] * sp[i];
+        } else {
+            sp[i] = p as u64 + 1;
+            sigma[i] = sigma[j] * sp[i];
+        }
+    }
+    sigma
+}
+
+/// f(i) for all i <= n of a multiplicative f, given fp(p, k) = f(p^k) for primes p and k >= 1 \
+/// f(0) = 0 and f(1) = 1, each prime power is passed to fp once, a linear pass over `euler_vec`
+pub fn multiplicative_sieve<T, F>(n: usize, mut fp: F) -> Vec<T>
+where
+    T: Clone + From<bool> + Mul<Output = T>,
+    F: FnMut(usize, u32) -> T,
+{
+    let (minf, _) = euler_vec(n);
+    let mut f = vec![T::from(false); n + 1];
+    // p^e, the part of the smallest prime factor, and its exponent
+    let mut low = ScratchVec::from_elem(0usize, n + 1);
+    let mut e = ScratchVec::from_elem(0u32, n + 1);
+    if n >= 1 {
+        f[1] = T::from(true);
+    }
+    for i in 2..=n {
+        let (p, j) = (minf[i], i / minf[i]);
+        if minf[j] == p {
+            (low[i], e[i]) = (low[j] * p, e[j] + 1);
+        } else {
+            (low[i], e[i]) = (p, 1);
+        }
+        f[i] = if low[i] == i {
+            fp(p, e[i])
+        } else {
+            f[i / low[i]].clone() * f[low[i]].clone()
+        };
+    }
+    f
+}
+
+/// is_prime\[i\] tells whether lo + i is prime, for lo <= hi up to about 1e12 \
+/// sieves the window with the primes up to sqrt(hi) from `euler_vec`, O((hi - lo) log log hi + sqrt(hi))
+pub fn segmented_sieve_bitmap(lo: u64, hi: u64) -> Vec<bool> {
+    if lo > hi {
+        return vec![];
+    }
+    let mut r = (hi as f64).sqrt() as u64;
+    while r * r > hi {
+        r -= 1;
+    }
+    while (r + 1) * (r + 1) <= hi {
+        r += 1;
+    }
+    let (_, pr) = euler_vec(r as usize);
+    let mut is_prime = vec![true; (hi - lo + 1) as usize];
+    for &p in &pr {
+        let p = p as u64;
+        // smallest multiple of p in the window that is not p itself
+        let mut m = (p * p).max(lo.div_ceil(p) * p);
+        while m <= hi {
+            is_prime[(m - lo) as usize] = false;
+            m += p;
+        }
+    }
+    for x in lo..lo.max(2).min(hi + 1) {
+        is_prime[(x - lo) as usize] = false;
+    }
+    is_prime
+}
+
+/// primes in [lo, hi], see `segmented_sieve_bitmap`
+pub fn segmented_sieve(lo: u64, hi: u64) -> Vec<u64> {
+    segmented_sieve_bitmap(lo, hi)
+        .into_iter()
+        .zip(lo..)
+        .filter_map(|(p, x)| p.then_some(x))
+        .collect()
+}
+
 pub fn factorize(mut n: usize, pr: &Vec<usize>) -> Vec<(usize, usize)> {
     let mut ans = Vec::with_capacity(12);
     for &p in pr {
         if p * p > n {
             break;
         }
-        if n % p == 0 {
+        if n.is_multiple_of(p) {
             let mut cnt = 0;
-            while n % p == 0 {
+            while n.is_multiple_of(p) {
                 n /= p;
                 cnt += 1;
             }
@@ -46,6 +341,22 @@ pub fn factorize(mut n: usize, pr: &Vec<usize>) -> Vec<(usize, usize)> {
     ans
 }
 
+/// same output as `factorize` for 1 <= n < minf.len(), peeling smallest prime factors
+/// off with the `minf` table of `euler_vec` in O(log n)
+pub fn factorize_by_minf(mut n: usize, minf: &[usize]) -> Vec<(usize, usize)> {
+    let mut ans = Vec::with_capacity(12);
+    while n > 1 {
+        let p = minf[n];
+        let mut cnt = 0;
+        while minf[n] == p {
+            n /= p;
+            cnt += 1;
+        }
+        ans.push((p, cnt));
+    }
+    ans
+}
+
 // generate all factors by prime factors, including 1 and n
 pub fn gen_all_factors(pfactor: &Vec<(usize, usize)>) -> Vec<usize> {
     let mut ans = vec![1];
@@ -62,9 +373,281 @@ pub fn gen_all_factors(pfactor: &Vec<(usize, usize)>) -> Vec<usize> {
     ans
 }
 
+pub fn calc_phi(mut n: usize, pr: &Vec<usize>) -> usize {
+    let mut ans = n;
+    for &i in pr {
+        if i * i > ans {
+            break;
+        }
+        if n.is_multiple_of(i) {
+            ans = ans / i * (i - 1);
+            while n.is_multiple_of(i) {
+                n /= i;
+            }
+        }
+    }
+    if n > 1 {
+        ans = ans / n * (n - 1);
+    }
+    ans
+}
+
+/// (g, x, y) with a * x + b * y = g = gcd(a, b), g >= 0
+pub fn ext_gcd(a: i64, b: i64) -> (i64, i64, i64) {
+    let (mut r0, mut r1) = (a as i128, b as i128);
+    let (mut x0, mut x1) = (1i128, 0i128);
+    let (mut y0, mut y1) = (0i128, 1i128);
+    while r1 != 0 {
+        let q = r0.div_euclid(r1);
+        (r0, r1) = (r1, r0 - q * r1);
+        (x0, x1) = (x1, x0 - q * x1);
+        (y0, y1) = (y1, y0 - q * y1);
+    }
+    if r0 < 0 {
+        (r0, x0, y0) = (-r0, -x0, -y0);
+    }
+    (r0 as i64, x0 as i64, y0 as i64)
+}
+
+/// all x with a * x = b (mod m) as x = x0 (mod m'), return (x0, m') with 0 <= x0 < m',
+/// None if there is no solution
+pub fn solve_linear_congruence(a: i64, b: i64, m: i64) -> Option<(i64, i64)> {
+    assert!(m >= 1, "modulus must be positive!");
+    let (a, b) = (a.rem_euclid(m), b.rem_euclid(m));
+    let (g, x, _) = ext_gcd(a, m);
+    if b % g != 0 {
+        return None;
+    }
+    let m1 = (m / g) as i128;
+    let x0 = (x as i128 * (b / g) as i128).rem_euclid(m1);
+    Some((x0 as i64, m1 as i64))
+}
+
+/// all integer (x, y) with a * x + b * y = c as (x0 + k * dx, y0 + k * dy) for every k,
+/// return ((x0, y0), (dx, dy)) with 0 <= x0 < |dx| unless dx = 0 \
+/// None if there is no solution or (x0, y0) does not fit in i64, a and b not both zero
+pub fn solve_diophantine(a: i64, b: i64, c: i64) -> Option<((i64, i64), (i64, i64))> {
+    assert!(a != 0 || b != 0, "a and b must not both be zero!");
+    let (g, x, y) = ext_gcd(a, b);
+    if c % g != 0 {
+        return None;
+    }
+    let (a, b, c, g) = (a as i128, b as i128, c as i128, g as i128);
+    let (dx, dy) = (b / g, -a / g);
+    let (mut x0, mut y0) = (x as i128 * (c / g), y as i128 * (c / g));
+    if dx != 0 {
+        // shift by k steps so that x0 lands in [0, |dx|)
+        let k = (x0.rem_euclid(dx.abs()) - x0) / dx;
+        (x0, y0) = (x0 + k * dx, y0 + k * dy);
+    }
+    Some((
+        (i64::try_from(x0).ok()?, i64::try_from(y0).ok()?),
+        (dx as i64, dy as i64),
+    ))
+}
+
+/// x with x = r (mod m) for every (r, m), moduli need not be coprime \
+/// return (x, lcm) with 0 <= x < lcm, None if there is no solution
+/// or the lcm does not fit in i64
+pub fn crt(rm: &[(i64, i64)]) -> Option<(i64, i64)> {
+    let (mut r0, mut m0) = (0i128, 1i128);
+    for &(r, m) in rm {
+        assert!(m >= 1, "modulus must be positive!");
+        let (r1, m1) = ((r as i128).rem_euclid(m as i128), m as i128);
+        let (g, p, _) = ext_gcd(m0 as i64, m1 as i64);
+        let (g, p) = (g as i128, p as i128);
+        if (r1 - r0) % g != 0 {
+            return None;
+        }
+        // m0 * p = g (mod m1), so x = r0 + m0 * p * (r1 - r0) / g
+        let l = m0 / g * m1;
+        if l > i64::MAX as i128 {
+            return None;
+        }
+        let k = ((r1 - r0) / g % (m1 / g) * p).rem_euclid(m1 / g);
+        r0 = (r0 + m0 * k).rem_euclid(l);
+        m0 = l;
+    }
+    Some((r0 as i64, m0 as i64))
+}
+
+/// smallest x >= 0 with a^x = b (mod m), m need not be prime or coprime to a \
+/// baby-step giant-step after dividing out gcd(a, m), O(sqrt(m)) with a HashMap
+pub fn discrete_log(a: u64, b: u64, m: u64) -> Option<u64> {
+    assert!(m >= 1, "modulus must be positive!");
+    let (a, b) = (a % m, b % m);
+    // x < 64 by brute force, every reduction step below halves m at least
+    let mut e = 1 % m;
+    for x in 0..64 {
+        if e == b {
+            return Some(x);
+        }
+        e = mul_mod(e, a, m);
+    }
+    // a^x = t * a^(x - k) (mod m), t collects the divided out parts
+    let (mut k, mut t, mut m, mut b) = (0, 1 % m, m, b);
+    loop {
+        let g = binary_gcd(a, m);
+        if g == 1 {
+            break;
+        }
+        if b % g != 0 {
+            return None;
+        }
+        (b, m) = (b / g, m / g);
+        t = mul_mod(t % m, a / g, m);
+        k += 1;
+    }
+    // t * a^(i n - j) = b  <=>  t * a^(i n) = b * a^j, a is invertible now
+    let n = (m as f64).sqrt() as u64 + 1;
+    let mut baby = HashMap::with_capacity(n as usize);
+    let mut cur = b % m;
+    for j in 0..n {
+        // keep the largest j for the smallest i n - j
+        baby.insert(cur, j);
+        cur = mul_mod(cur, a, m);
+    }
+    let an = pow_mod(a, n, m);
+    let mut cur = t % m;
+    for i in 1..=n {
+        cur = mul_mod(cur, an, m);
+        if let Some(&j) = baby.get(&cur) {
+            return Some(i * n - j + k);
+        }
+    }
+    None
+}
+
+/// binomials modulo a small prime p < 2^32 for huge n and k, by Lucas' theorem \
+/// O(p) to build, O(log_p n) per query
+pub struct Lucas {
+    p: u64,
+    fact: Vec<u64>,
+    inv_fact: Vec<u64>,
+}
+
+impl Lucas {
+    pub fn new(p: u64) -> Self {
+        assert!(is_prime_u64(p), "modulus must be prime!");
+        let n = p as usize;
+        let mut fact = vec![1u64; n];
+        for i in 1..n {
+            fact[i] = fact[i - 1] * i as u64 % p;
+        }
+        let mut inv_fact = vec![1u64; n];
+        inv_fact[n - 1] = pow_mod(fact[n - 1], p - 2, p);
+        for i in (1..n).rev() {
+            inv_fact[i - 1] = inv_fact[i] * i as u64 % p;
+        }
+        Self { p, fact, inv_fact }
+    }
+
+    /// C(n, k) mod p, 0 if k > n
+    pub fn binom(&self, mut n: u64, mut k: u64) -> u64 {
+        let p = self.p;
+        let mut res = 1 % p;
+        while k > 0 {
+            let (a, b) = ((n % p) as usize, (k % p) as usize);
+            if a < b {
+                return 0;
+            }
+            res = res * self.fact[a] % p * self.inv_fact[b] % p * self.inv_fact[a - b] % p;
+            (n, k) = (n / p, k / p);
+        }
+        res
+    }
+}
+
+/// C(n, k) mod a prime p, builds a `Lucas` table, so reuse one for many queries
+pub fn binom_mod_p(n: u64, k: u64, p: u64) -> u64 {
+    Lucas::new(p).binom(n, k)
+}
+
+/// C(n, k) mod p^e for a prime p, 0 if k > n \
+/// generalized Lucas (Granville): n! = p^v(n) * prod over j of f(n / p^j), f(x) the product of
+/// i <= x coprime to p mod p^e; O(p^e + e log n) with a table of size p^e, so p^e must be small
+pub fn binom_mod_prime_power(n: u64, k: u64, p: u64, e: u32) -> u64 {
+    if k > n {
+        return 0;
+    }
+    let pe = p.pow(e);
+    // f[x] for x < p^e, f(x) = f[p^e - 1]^(x / p^e) * f[x mod p^e]
+    let mut f = vec![1 % pe; pe as usize];
+    for i in 1..pe as usize {
+        f[i] = if (i as u64).is_multiple_of(p) {
+            f[i - 1]
+        } else {
+            mul_mod(f[i - 1], i as u64, pe)
+        };
+    }
+    let full = f[pe as usize - 1];
+    // (v, prod f(x / p^j)) for x!
+    let fact = |mut x: u64| {
+        let (mut v, mut r) = (0u64, 1 % pe);
+        while x > 0 {
+            r = mul_mod(r, pow_mod(full, x / pe, pe), pe);
+            r = mul_mod(r, f[(x % pe) as usize], pe);
+            x /= p;
+            v += x;
+        }
+        (v, r)
+    };
+    let ((vn, rn), (vk, rk), (vm, rm)) = (fact(n), fact(k), fact(n - k));
+    let v = vn - vk - vm;
+    if v >= e as u64 {
+        return 0;
+    }
+    let (_, inv, _) = ext_gcd(mul_mod(rk, rm, pe) as i64, pe as i64);
+    let inv = inv.rem_euclid(pe as i64) as u64;
+    mul_mod(mul_mod(rn, inv, pe), p.pow(v as u32), pe)
+}
+
+/// C(n, k) mod any m >= 1, binom_mod_prime_power on each p^e of m combined by CRT \
+/// O(sum p^e), so every prime power of m must be small
+pub fn binom_mod(n: u64, k: u64, m: u64) -> u64 {
+    assert!(m >= 1, "modulus must be positive!");
+    let (mut r, mut ms) = (vec![], vec![]);
+    for (p, e) in factor(m) {
+        r.push(binom_mod_prime_power(n, k, p, e as u32));
+        ms.push(p.pow(e as u32));
+    }
+    garner(&r, &ms, m)
+}
+
+/// x mod modulo where x = r[i] (mod m[i]) and 0 <= x < prod m,
+/// the m[i] must be pairwise coprime \
+/// e.g. rebuilding a convolution result from several NTT primes
+pub fn garner(r: &[u64], m: &[u64], modulo: u64) -> u64 {
+    assert_eq!(r.len(), m.len());
+    let n = r.len();
+    // x = c[0] + c[1] m[0] + c[2] m[0] m[1] + ..., coef[j] = m[0] ... m[i - 1] mod m[j]
+    let mut coef = vec![1u64; n + 1];
+    let mut cons = vec![0u64; n + 1];
+    let ms: Vec<u64> = m.iter().copied().chain([modulo]).collect();
+    for i in 0..n {
+        let (_, inv, _) = ext_gcd(coef[i] as i64, m[i] as i64);
+        let inv = inv.rem_euclid(m[i] as i64) as u64;
+        let d = (r[i] % m[i] + m[i] - cons[i]) % m[i];
+        let t = mul_mod(d, inv, m[i]);
+        for j in i + 1..=n {
+            cons[j] = (cons[j] + mul_mod(t, coef[j], ms[j])) % ms[j];
+            coef[j] = mul_mod(coef[j], m[i], ms[j]);
+        }
+    }
+    cons[n]
+}
+
 #[cfg(test)]
 mod test {
-    use crate::{euler_vec, factorize, gen_all_factors};
+    use crate::{
+        binary_gcd, binom_mod, binom_mod_p, binom_mod_prime_power, calc_phi, checked_lcm,
+        checked_lcm_slice, crt, discrete_log, divisor_count_vec, divisor_moebius, divisor_sum_vec,
+        divisor_zeta, euler_vec, factor, factorize, factorize_by_minf, gcd, gcd_slice,
+        gen_all_factors, is_prime_u64, lcm_slice, mobius_vec, multiple_moebius, multiple_zeta,
+        multiplicative_sieve, phi_vec, pow_mod, solve_diophantine, solve_linear_congruence, Lucas,
+        NumberTheoryCtx,
+    };
+    use random::Random;
 
     #[test]
     fn factor_test() {
@@ -74,24 +657,322 @@ mod test {
                 assert!(n % x == 0);
             }
         }
+        let (minf, pr) = euler_vec(100000);
+        for n in 1..100000 {
+            assert_eq!(factorize_by_minf(n, &minf), factorize(n, &pr));
+        }
     }
-}
 
-pub fn calc_phi(mut n: usize, pr: &Vec<usize>) -> usize {
-    let mut ans = n;
-    for &i in pr {
-        if i * i > ans {
-            break;
+    #[test]
+    fn is_prime_u64_test() {
+        let (minf, _) = euler_vec(10000);
+        for n in 0..=10000u64 {
+            assert_eq!(is_prime_u64(n), n >= 2 && minf[n as usize] == n as usize);
         }
-        if n % i == 0 {
-            ans = ans / i * (i - 1);
-            while n % i == 0 {
-                n /= i;
+        assert!(is_prime_u64(1_000_000_007));
+        assert!(is_prime_u64(18446744073709551557));
+        // strong pseudoprime to bases 2, 3, 5, 7, 11, 13, 17, 19, 23
+        assert!(!is_prime_u64(3825123056546413051));
+        assert!(!is_prime_u64(1_000_000_007 * 998_244_353));
+    }
+
+    #[test]
+    fn phi_vec_test() {
+        let (_, pr) = euler_vec(1000);
+        let phi = phi_vec(5000);
+        assert_eq!(phi[..3], [0, 1, 1]);
+        for (n, &x) in phi.iter().enumerate().skip(1) {
+            assert_eq!(x, calc_phi(n, &pr));
+        }
+    }
+
+    #[test]
+    fn multiplicative_sieve_test() {
+        let n = 5000;
+        let phi = multiplicative_sieve(n, |p, k| p.pow(k - 1) * (p - 1));
+        assert_eq!(phi, phi_vec(n));
+        let mu = multiplicative_sieve(n, |_, k| if k == 1 { -1i8 } else { 0 });
+        assert_eq!(mu, mobius_vec(n));
+        assert_eq!(multiplicative_sieve(n, |_, k| k + 1), divisor_count_vec(n));
+        let sigma = multiplicative_sieve(n, |p, k| (0..=k).map(|i| p.pow(i) as u64).sum::<u64>());
+        assert_eq!(sigma, divisor_sum_vec(n));
+    }
+
+    #[test]
+    fn binary_gcd_test() {
+        for a in 0..200u32 {
+            for b in 0..200u32 {
+                assert_eq!(binary_gcd(a, b), gcd(a, b));
             }
         }
+        assert_eq!(binary_gcd(u64::MAX, u64::MAX - 2), 1);
+        assert_eq!(binary_gcd(3u128 << 100, 9u128 << 70), 3 << 70);
+        assert_eq!(gcd_slice::<u32>(&[]), 0);
+        assert_eq!(gcd_slice(&[12u64, 18, 30]), 6);
+        assert_eq!(lcm_slice(&[4usize, 6, 10]), 60);
+        assert_eq!(checked_lcm(u64::MAX, u64::MAX - 1), None);
+        assert_eq!(checked_lcm_slice(&[0u8, 200]), Some(0));
+        assert_eq!(checked_lcm_slice::<u8>(&[16, 17]), None);
+        assert_eq!((binary_gcd(-12i64, 18), binary_gcd(0i32, -5)), (6, 5));
+        assert_eq!(checked_lcm(-4i8, 6), Some(12));
+        assert_eq!(checked_lcm(i8::MIN, 3), None);
+        assert_eq!(
+            (binary_gcd(i8::MIN, 0), binary_gcd(i8::MIN, i8::MIN)),
+            (i8::MIN, i8::MIN)
+        );
+        assert_eq!(binary_gcd(i8::MIN, 6), 2);
     }
-    if n > 1 {
-        ans = ans / n * (n - 1);
+
+    #[test]
+    fn factor_u64_test() {
+        let (minf, _) = euler_vec(100000);
+        for n in 1..100000u64 {
+            let e: Vec<(u64, usize)> = factorize_by_minf(n as usize, &minf)
+                .into_iter()
+                .map(|(p, c)| (p as u64, c))
+                .collect();
+            assert_eq!(factor(n), e);
+        }
+        assert_eq!(factor(0), []);
+        assert_eq!(
+            factor(1_000_000_007 * 998_244_353),
+            [(998_244_353, 1), (1_000_000_007, 1)]
+        );
+        assert_eq!(factor(1 << 63), [(2, 63)]);
+        assert_eq!(
+            factor(999_983 * 999_983 * 997 * 17),
+            [(17, 1), (997, 1), (999_983, 2)]
+        );
+        assert_eq!(factor(18446744073709551557), [(18446744073709551557, 1)]);
+    }
+
+    #[test]
+    fn linear_equation_test() {
+        for m in 1..30i64 {
+            for a in -30..30i64 {
+                for b in -30..30i64 {
+                    let xs: Vec<i64> = (0..m).filter(|x| (a * x - b) % m == 0).collect();
+                    match solve_linear_congruence(a, b, m) {
+                        None => assert!(xs.is_empty()),
+                        Some((x0, m1)) => {
+                            assert_eq!(xs, (x0..m).step_by(m1 as usize).collect::<Vec<_>>())
+                        }
+                    }
+                    if a == 0 && b == 0 {
+                        continue;
+                    }
+                    let c = m - 15;
+                    let any = c % binary_gcd(a, b) == 0;
+                    match solve_diophantine(a, b, c) {
+                        None => assert!(!any),
+                        Some(((x0, y0), (dx, dy))) => {
+                            assert!(dx == 0 || (0 <= x0 && x0 < dx.abs()));
+                            for k in -3..3 {
+                                assert_eq!(a * (x0 + k * dx) + b * (y0 + k * dy), c);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        assert_eq!(
+            solve_diophantine(i64::MAX, i64::MAX - 1, 1),
+            Some(((1, -1), (i64::MAX - 1, -i64::MAX)))
+        );
+    }
+
+    #[test]
+    fn binom_mod_test() {
+        let mut c = vec![vec![1u128]];
+        for n in 1..=120usize {
+            let mut row = vec![1u128; n + 1];
+            for k in 1..n {
+                row[k] = c[n - 1][k - 1] + c[n - 1][k];
+            }
+            c.push(row);
+        }
+        for (p, e) in [(2, 1), (2, 5), (3, 4), (5, 2), (7, 1), (11, 3)] {
+            let pe = (p as u128).pow(e);
+            for n in (0..=120u64).step_by(7) {
+                for k in 0..=n + 1 {
+                    let want = c[n as usize].get(k as usize).map_or(0, |x| x % pe) as u64;
+                    assert_eq!(binom_mod_prime_power(n, k, p, e), want);
+                }
+            }
+        }
+        for m in [1u64, 12, 360, 1001, 9973 * 4] {
+            for n in [0u64, 37, 100, 120] {
+                for k in [0, 1, n / 3, n / 2, n] {
+                    let want = c[n as usize].get(k as usize).map_or(0, |x| x % m as u128) as u64;
+                    assert_eq!(binom_mod(n, k, m), want);
+                }
+            }
+        }
+        // adding 2^59 to itself carries once, so C(2^60, 2^59) is 2 times an odd number
+        assert_eq!(binom_mod(1 << 60, 1 << 59, 4), 2);
+    }
+
+    #[test]
+    fn dirichlet_test() {
+        let n = 300;
+        let a: Vec<i64> = (0..=n as i64).map(|i| i * i % 17 - 8).collect();
+        let (mut d, mut m) = (a.clone(), a.clone());
+        divisor_zeta(&mut d);
+        multiple_zeta(&mut m);
+        for i in 1..=n {
+            let sd: i64 = (1..=i).filter(|d| i % d == 0).map(|d| a[d]).sum();
+            let sm: i64 = (i..=n).step_by(i).map(|k| a[k]).sum();
+            assert_eq!((d[i], m[i]), (sd, sm));
+        }
+        // gcd convolution: #{(x, y) in [1, n]^2 : gcd(x, y) = g} by moebius over multiples
+        let mut c: Vec<i64> = (0..=n as i64)
+            .map(|g| if g == 0 { 0 } else { (n as i64 / g).pow(2) })
+            .collect();
+        multiple_moebius(&mut c);
+        assert_eq!(
+            c[1],
+            (1..=n)
+                .flat_map(|x| (1..=n).map(move |y| (x, y)))
+                .filter(|&(x, y)| gcd(x, y) == 1)
+                .count() as i64
+        );
+        divisor_moebius(&mut d);
+        multiple_zeta(&mut c);
+        assert_eq!(d, a);
+        assert_eq!(c[7], (n as i64 / 7).pow(2));
+        divisor_zeta::<i64>(&mut []);
+    }
+
+    #[test]
+    fn ctx_test() {
+        let n = 20000;
+        let (minf, _) = euler_vec(n);
+        let (phi, mu) = (phi_vec(n), mobius_vec(n));
+        for limit in [0, 1, 100, n] {
+            let mut ctx = NumberTheoryCtx::new(limit);
+            assert_eq!(ctx.factorize(0), []);
+            assert_eq!(ctx.factorize(1), []);
+            for x in 1..=n {
+                let f = factorize_by_minf(x, &minf);
+                let g: Vec<_> = ctx
+                    .factorize(x as u64)
+                    .into_iter()
+                    .map(|(p, c)| (p as usize, c))
+                    .collect();
+                assert_eq!(g, f);
+                let mut d = ctx.divisors(x as u64);
+                let mut e = gen_all_factors(&f);
+                d.sort_unstable();
+                e.sort_unstable();
+                assert!(d.into_iter().map(|x| x as usize).eq(e));
+                assert_eq!(ctx.phi(x as u64), phi[x] as u64);
+                assert_eq!(ctx.mu(x as u64), mu[x] as i32);
+            }
+        }
+        let mut ctx = NumberTheoryCtx::new(1000);
+        for x in [1_000_000_007 * 998_244_353, 1 << 63, 600851475143, u64::MAX] {
+            assert_eq!(ctx.factorize(x), factor(x));
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn ctx_mu_zero_test() {
+        NumberTheoryCtx::new(100).mu(0);
+    }
+
+    #[test]
+    fn crt_test() {
+        // smallest x in [0, lcm) satisfying every congruence, by trying them all
+        let brute = |rm: &[(i64, i64)]| {
+            let l = rm.iter().fold(1, |l, &(_, m)| l / gcd(l, m) * m);
+            (0..l)
+                .find(|x| rm.iter().all(|&(r, m)| (x - r).rem_euclid(m) == 0))
+                .map(|x| (x, l))
+        };
+        for m1 in 1..=12 {
+            for m2 in 1..=12 {
+                for r1 in -3..m1 {
+                    for r2 in 0..m2 {
+                        assert_eq!(crt(&[(r1, m1), (r2, m2)]), brute(&[(r1, m1), (r2, m2)]));
+                    }
+                }
+            }
+        }
+        let rm = [(1, 4), (3, 6), (5, 10)];
+        assert_eq!(crt(&rm), brute(&rm));
+        assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+        assert_eq!(crt(&[]), Some((0, 1)));
+        let (p, q) = (1_000_000_007, 998_244_353);
+        let (x, l) = crt(&[(5, p), (7, q)]).unwrap();
+        assert_eq!((x % p, x % q, l), (5, 7, p * q));
+        // the lcm is about 2^80
+        assert_eq!(crt(&[(0, 1 << 40), (0, (1 << 40) - 1)]), None);
+        assert_eq!(crt(&[(3, 1 << 62), (3, 1 << 61)]), Some((3, 1 << 62)));
+    }
+
+    #[test]
+    fn discrete_log_test() {
+        // first x with a^x = v for every v, the powers repeat within m + 64 steps
+        let table = |a: u64, m: u64| {
+            let mut first = vec![None; m as usize];
+            let mut e = 1 % m;
+            for x in 0..m + 64 {
+                first[e as usize].get_or_insert(x);
+                e = e * a % m;
+            }
+            first
+        };
+        for m in 1..=60 {
+            for a in 0..m {
+                let first = table(a, m);
+                for b in 0..m {
+                    assert_eq!(discrete_log(a, b, m), first[b as usize]);
+                }
+            }
+        }
+        let mut rd = Random::new(17);
+        for _ in 0..200 {
+            // a shares factors with m half of the time
+            let m = rd.next_bounds(100, 100000);
+            let a = match rd.next(2) {
+                0 => rd.next(m),
+                _ => gcd(rd.next(m), m) * rd.next_bounds(1, 50) % m,
+            };
+            let first = table(a, m);
+            for _ in 0..20 {
+                let b = match rd.next(2) {
+                    0 => rd.next(m),
+                    _ => pow_mod(a, rd.next(2 * m), m),
+                };
+                assert_eq!(discrete_log(a, b, m), first[b as usize]);
+            }
+        }
+        assert_eq!(discrete_log(5, 7, 1), Some(0));
+        assert_eq!(discrete_log(2, 0, 1 << 20), Some(20));
+        assert_eq!(discrete_log(3, 1, 1_000_000_007), Some(0));
+    }
+
+    #[test]
+    fn lucas_test() {
+        for p in [2, 3, 5, 7, 13] {
+            let lucas = Lucas::new(p);
+            let mut row = vec![1u64];
+            for n in 0..200u64 {
+                for k in 0..=n + 1 {
+                    let e = row.get(k as usize).copied().unwrap_or(0);
+                    assert_eq!(lucas.binom(n, k), e);
+                    assert_eq!(binom_mod_p(n, k, p), e);
+                }
+                let mut next = vec![1u64; row.len() + 1];
+                for k in 1..row.len() {
+                    next[k] = (row[k - 1] + row[k]) % p;
+                }
+                row = next;
+            }
+        }
+        // base p digits of n and k are (1, 1, 3) and (1, 2), so C(1, 0) * C(1, 1) * C(3, 2)
+        let p = 1_000_003u64;
+        assert_eq!(Lucas::new(p).binom(p * p + p + 3, p + 2), 3);
     }
-    ans
 }
@@ -0,0 +1,86 @@
+/// arithmetic modulo an odd n < 2^64 in Montgomery form x * 2^64 mod n,
+/// multiplication without any u128 division \
+/// values passed around are in Montgomery form and below n, convert with `to_mont` and `from_mont`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Montgomery {
+    n: u64,
+    n_inv: u64, // n * n_inv = 1 mod 2^64
+    r2: u64,    // 2^128 mod n
+}
+
+impl Montgomery {
+    pub fn new(n: u64) -> Self {
+        assert!(n % 2 == 1, "modulus must be odd!");
+        // Newton's iteration doubles the correct low bits each step, n is right mod 2^3
+        let mut n_inv = n;
+        for _ in 0..5 {
+            n_inv = n_inv.wrapping_mul(2u64.wrapping_sub(n.wrapping_mul(n_inv)));
+        }
+        let r = (n.wrapping_neg() % n) as u128;
+        let r2 = (r * r % n as u128) as u64;
+        Self { n, n_inv, r2 }
+    }
+
+    pub fn modulus(&self) -> u64 {
+        self.n
+    }
+
+    // t / 2^64 mod n for t < n * 2^64
+    fn reduce(&self, t: u128) -> u64 {
+        let (hi, lo) = ((t >> 64) as u64, t as u64);
+        // m * n has the same low half as t, so t - m * n is a multiple of 2^64
+        let m = lo.wrapping_mul(self.n_inv);
+        let mh = ((m as u128 * self.n as u128) >> 64) as u64;
+        if hi >= mh {
+            hi - mh
+        } else {
+            hi.wrapping_sub(mh).wrapping_add(self.n)
+        }
+    }
+
+    pub fn to_mont(&self, x: u64) -> u64 {
+        self.reduce((x % self.n) as u128 * self.r2 as u128)
+    }
+
+    pub fn from_mont(&self, x: u64) -> u64 {
+        self.reduce(x as u128)
+    }
+
+    /// 1 in Montgomery form
+    pub fn one(&self) -> u64 {
+        self.n.wrapping_neg() % self.n
+    }
+
+    pub fn add(&self, a: u64, b: u64) -> u64 {
+        let (s, o) = a.overflowing_add(b);
+        if o || s >= self.n {
+            s.wrapping_sub(self.n)
+        } else {
+            s
+        }
+    }
+
+    pub fn sub(&self, a: u64, b: u64) -> u64 {
+        if a >= b {
+            a - b
+        } else {
+            a.wrapping_sub(b).wrapping_add(self.n)
+        }
+    }
+
+    pub fn mul(&self, a: u64, b: u64) -> u64 {
+        self.reduce(a as u128 * b as u128)
+    }
+
+    pub fn pow(&self, mut a: u64, mut e: u64) -> u64 {
+        let mut res = self.one();
+        while e > 0 {
+            if e & 1 == 1 {
+                res = self.mul(res, a);
+            }
+            a = self.mul(a, a);
+            e >>= 1;
+        }
+        res
+    }
+}
@@ -0,0 +1,223 @@
+use std::ops::Range;
+
+/// Manacher, over the 2n - 1 centers: rad\[2i\] is the number of odd palindromes
+/// centered at i, rad\[2i + 1\] the number of even ones centered between i and i + 1 \
+/// so rad is also the radius of the longest palindrome at each center, O(n)
+pub fn palindromes_per_center<T: PartialEq>(s: &[T]) -> Vec<usize> {
+    let n = s.len();
+    // odd\[i\]: s[i - k + 1..i + k] is a palindrome for k <= odd\[i\]
+    let mut odd = vec![0; n];
+    // [l, r) is the palindrome reaching furthest right so far
+    let (mut l, mut r) = (0, 0);
+    for i in 0..n {
+        let mut k = if i < r {
+            odd[l + r - 1 - i].min(r - i)
+        } else {
+            1
+        };
+        while i >= k && i + k < n && s[i - k] == s[i + k] {
+            k += 1;
+        }
+        odd[i] = k;
+        if i + k > r {
+            (l, r) = (i + 1 - k, i + k);
+        }
+    }
+    // even\[i\]: s[i - k..i + k] is a palindrome for k <= even\[i\]
+    let mut even = vec![0; n];
+    (l, r) = (0, 0);
+    for i in 0..n {
+        let mut k = if i < r { even[l + r - i].min(r - i) } else { 0 };
+        while i > k && i + k < n && s[i - k - 1] == s[i + k] {
+            k += 1;
+        }
+        even[i] = k;
+        if i + k > r {
+            (l, r) = (i - k, i + k);
+        }
+    }
+    (0..(2 * n).saturating_sub(1))
+        .map(|c| {
+            if c % 2 == 0 {
+                odd[c / 2]
+            } else {
+                even[c / 2 + 1]
+            }
+        })
+        .collect()
+}
+
+/// number of palindromic substrings, counted by position
+pub fn count_palindromes<T: PartialEq>(s: &[T]) -> u64 {
+    palindromes_per_center(s)
+        .into_iter()
+        .map(|k| k as u64)
+        .sum()
+}
+
+/// range of the leftmost longest palindromic substring
+pub fn longest_palindrome<T: PartialEq>(s: &[T]) -> Range<usize> {
+    let mut res = 0..0;
+    for (c, k) in palindromes_per_center(s).into_iter().enumerate() {
+        let (lo, len) = (c / 2 + 1 - k, 2 * k - (c % 2 == 0) as usize);
+        if len > res.len() {
+            res = lo..lo + len;
+        }
+    }
+    res
+}
+
+/// palindromic tree, node 0 is the root of length -1 and node 1 the empty palindrome \
+/// also keeps series links: slink\[v\] is the longest suffix palindrome of v whose
+/// difference to its own link differs from diff\[v\]
+pub struct Eertree<T> {
+    s: Vec<T>,
+    len: Vec<i32>,
+    link: Vec<usize>,
+    diff: Vec<i32>,
+    slink: Vec<usize>,
+    next: Vec<Vec<(T, usize)>>,
+    last: usize,
+}
+
+impl<T> Default for Eertree<T>
+where
+    T: Copy + PartialEq,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Eertree<T>
+where
+    T: Copy + PartialEq,
+{
+    pub fn new() -> Self {
+        Self {
+            s: vec![],
+            len: vec![-1, 0],
+            link: vec![0, 0],
+            diff: vec![0, 0],
+            slink: vec![0, 0],
+            next: vec![vec![], vec![]],
+            last: 1,
+        }
+    }
+
+    fn go(&self, v: usize, c: T) -> Option<usize> {
+        self.next[v].iter().find(|e| e.0 == c).map(|e| e.1)
+    }
+
+    // longest suffix palindrome of v that can be extended by the last char
+    fn fit(&self, mut v: usize) -> usize {
+        let i = self.s.len() - 1;
+        loop {
+            let l = self.len[v];
+            if l == -1 || (i > l as usize && self.s[i - 1 - l as usize] == self.s[i]) {
+                return v;
+            }
+            v = self.link[v];
+        }
+    }
+
+    /// append c, return the node of the longest palindromic suffix
+    pub fn push(&mut self, c: T) -> usize {
+        self.s.push(c);
+        let p = self.fit(self.last);
+        if let Some(v) = self.go(p, c) {
+            self.last = v;
+            return v;
+        }
+        let v = self.len.len();
+        let len = self.len[p] + 2;
+        let link = if len == 1 {
+            1
+        } else {
+            let q = self.fit(self.link[p]);
+            self.go(q, c).unwrap()
+        };
+        let diff = len - self.len[link];
+        self.len.push(len);
+        self.link.push(link);
+        self.diff.push(diff);
+        self.slink.push(if diff == self.diff[link] {
+            self.slink[link]
+        } else {
+            link
+        });
+        self.next.push(vec![]);
+        self.next[p].push((c, v));
+        self.last = v;
+        v
+    }
+
+    /// length of the palindrome of node v, -1 for the root
+    pub fn node_len(&self, v: usize) -> i32 {
+        self.len[v]
+    }
+
+    /// longest proper palindromic suffix of node v
+    pub fn link(&self, v: usize) -> usize {
+        self.link[v]
+    }
+
+    /// number of distinct nonempty palindromic substrings so far
+    pub fn distinct(&self) -> usize {
+        self.len.len() - 2
+    }
+}
+
+/// minimum number of palindromes s can be cut into, 0 for an empty s \
+/// dp over the series links of an eertree, O(n log n)
+pub fn palindromic_factorization_min<T>(s: &[T]) -> usize
+where
+    T: Copy + PartialEq,
+{
+    let mut tree = Eertree::new();
+    let mut ans = vec![0usize; s.len() + 1];
+    // best over the series of v, kept for the last position where v ended
+    let mut series = vec![];
+    for (i, &c) in s.iter().enumerate() {
+        let i = i + 1;
+        let mut v = tree.push(c);
+        series.resize(tree.len.len(), 0);
+        ans[i] = usize::MAX;
+        while tree.len[v] > 0 {
+            let u = tree.slink[v];
+            series[v] = ans[i - (tree.len[u] + tree.diff[v]) as usize];
+            if tree.diff[v] == tree.diff[tree.link[v]] {
+                series[v] = series[v].min(series[tree.link[v]]);
+            }
+            ans[i] = ans[i].min(series[v] + 1);
+            v = u;
+        }
+    }
+    ans[s.len()]
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        count_palindromes, longest_palindrome, palindromes_per_center,
+        palindromic_factorization_min, Eertree,
+    };
+
+    #[test]
+    fn palindrome_test() {
+        let s = b"abacaba";
+        assert_eq!(
+            palindromes_per_center(s),
+            vec![1, 0, 2, 0, 1, 0, 4, 0, 1, 0, 2, 0, 1]
+        );
+        assert_eq!(count_palindromes(s), 12);
+        assert_eq!(longest_palindrome(b"xabbay"), 1..5);
+        let mut t = Eertree::new();
+        for &c in b"eertree" {
+            t.push(c);
+        }
+        assert_eq!(t.distinct(), 7);
+        assert_eq!(palindromic_factorization_min(b"abacabaxyx"), 2);
+        assert_eq!(palindromic_factorization_min(b"abcbd"), 3);
+    }
+}
@@ -0,0 +1,198 @@
+use std::convert::Infallible;
+use std::marker::PhantomData;
+use std::ops::{Add, BitXor, Rem};
+
+pub trait Monoid {
+    type S: Clone;
+    fn identity() -> Self::S;
+    fn binary_operation(a: &Self::S, b: &Self::S) -> Self::S;
+}
+
+/// smallest and largest values of a type, identities of Max and Min
+pub trait Bounded {
+    const MIN: Self;
+    const MAX: Self;
+}
+
+macro_rules! impl_bounded {
+    ($($ty:ty),*) => {
+        $(
+            impl Bounded for $ty {
+                const MIN: Self = <$ty>::MIN;
+                const MAX: Self = <$ty>::MAX;
+            }
+        )*
+    };
+}
+
+impl_bounded!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+pub struct Additive<S>(Infallible, PhantomData<fn() -> S>);
+impl<S> Monoid for Additive<S>
+where
+    S: Copy + Default + Add<Output = S>,
+{
+    type S = S;
+    fn identity() -> Self::S {
+        S::default()
+    }
+    fn binary_operation(a: &Self::S, b: &Self::S) -> Self::S {
+        *a + *b
+    }
+}
+
+pub struct Max<S>(Infallible, PhantomData<fn() -> S>);
+impl<S> Monoid for Max<S>
+where
+    S: Copy + Ord + Bounded,
+{
+    type S = S;
+    fn identity() -> Self::S {
+        S::MIN
+    }
+    fn binary_operation(a: &Self::S, b: &Self::S) -> Self::S {
+        *a.max(b)
+    }
+}
+
+pub struct Min<S>(Infallible, PhantomData<fn() -> S>);
+impl<S> Monoid for Min<S>
+where
+    S: Copy + Ord + Bounded,
+{
+    type S = S;
+    fn identity() -> Self::S {
+        S::MAX
+    }
+    fn binary_operation(a: &Self::S, b: &Self::S) -> Self::S {
+        *a.min(b)
+    }
+}
+
+pub struct Xor<S>(Infallible, PhantomData<fn() -> S>);
+impl<S> Monoid for Xor<S>
+where
+    S: Copy + Default + BitXor<Output = S>,
+{
+    type S = S;
+    fn identity() -> Self::S {
+        S::default()
+    }
+    fn binary_operation(a: &Self::S, b: &Self::S) -> Self::S {
+        *a ^ *b
+    }
+}
+
+/// gcd of nonnegative values, gcd of nothing is 0
+pub struct Gcd<S>(Infallible, PhantomData<fn() -> S>);
+impl<S> Monoid for Gcd<S>
+where
+    S: Copy + Default + PartialEq + Rem<Output = S>,
+{
+    type S = S;
+    fn identity() -> Self::S {
+        S::default()
+    }
+    fn binary_operation(a: &Self::S, b: &Self::S) -> Self::S {
+        let (mut a, mut b) = (*a, *b);
+        while b != S::default() {
+            (a, b) = (b, a % b);
+        }
+        a
+    }
+}
+
+/// prefix and suffix folds of an array, so the fold of everything outside
+/// one index or one range is O(1)
+pub struct PrefixSuffix<M>
+where
+    M: Monoid,
+{
+    pre: Vec<M::S>, // pre[i] = a[0] * ... * a[i - 1]
+    suf: Vec<M::S>, // suf[i] = a[i] * ... * a[n - 1]
+}
+
+impl<M> PrefixSuffix<M>
+where
+    M: Monoid,
+{
+    pub fn new(a: &[M::S]) -> Self {
+        let n = a.len();
+        let mut pre = Vec::with_capacity(n + 1);
+        pre.push(M::identity());
+        for x in a {
+            pre.push(M::binary_operation(&pre[pre.len() - 1], x));
+        }
+        let mut suf = vec![M::identity(); n + 1];
+        for i in (0..n).rev() {
+            suf[i] = M::binary_operation(&a[i], &suf[i + 1]);
+        }
+        Self { pre, suf }
+    }
+
+    pub fn len(&self) -> usize {
+        self.pre.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// fold of a[0, i)
+    pub fn prefix(&self, i: usize) -> M::S {
+        self.pre[i].clone()
+    }
+
+    /// fold of a[i, n)
+    pub fn suffix(&self, i: usize) -> M::S {
+        self.suf[i].clone()
+    }
+
+    /// fold of the whole array
+    pub fn all(&self) -> M::S {
+        self.pre[self.len()].clone()
+    }
+
+    /// fold of the array without a[i]
+    pub fn except(&self, i: usize) -> M::S {
+        self.except_range(i, i + 1)
+    }
+
+    /// fold of the array without a[l, r), order kept
+    pub fn except_range(&self, l: usize, r: usize) -> M::S {
+        assert!(l <= r && r <= self.len(), "index out of range!");
+        M::binary_operation(&self.pre[l], &self.suf[r])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Additive, Gcd, Max, PrefixSuffix};
+
+    #[test]
+    fn except_range_test() {
+        let a: Vec<i64> = vec![5, -3, 8, 0, 12, -7, 4];
+        let n = a.len();
+        let sum = PrefixSuffix::<Additive<i64>>::new(&a);
+        let max = PrefixSuffix::<Max<i64>>::new(&a);
+        for l in 0..=n {
+            for r in l..=n {
+                let rest = a[..l].iter().chain(&a[r..]);
+                assert_eq!(sum.except_range(l, r), rest.clone().sum::<i64>());
+                assert_eq!(
+                    max.except_range(l, r),
+                    rest.copied().max().unwrap_or(i64::MIN)
+                );
+            }
+            assert_eq!(sum.prefix(l), a[..l].iter().sum::<i64>());
+            assert_eq!(sum.suffix(l), a[l..].iter().sum::<i64>());
+        }
+        for (i, x) in a.iter().enumerate() {
+            assert_eq!(sum.except(i), sum.all() - x);
+        }
+        assert_eq!(max.except_range(0, n), i64::MIN);
+        let g = PrefixSuffix::<Gcd<u64>>::new(&[12, 18, 7, 30]);
+        assert_eq!((g.except(2), g.all(), g.except_range(1, 3)), (6, 1, 6));
+        assert!(PrefixSuffix::<Additive<u8>>::new(&[]).is_empty());
+    }
+}
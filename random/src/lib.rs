@@ -1,5 +1,12 @@
 use std::time::{SystemTime, UNIX_EPOCH};
 
+#[cfg(feature = "parallel")]
+mod parallel;
+mod weighted;
+#[cfg(feature = "parallel")]
+pub use parallel::{num_threads, par_map, par_seeds};
+pub use weighted::{AliasTable, WeightedReservoir};
+
 const NN: usize = 312;
 const MM: usize = 156;
 const MATRIX_A: u64 = 0xB5026F5AA96619E9;
@@ -58,18 +65,24 @@ impl Random {
     pub fn next_bounds(&mut self, f: u64, t: u64) -> u64 {
         f + self.next(t - f + 1)
     }
+
+    /// uniform in [0, 1)
+    pub fn gen_f64(&mut self) -> f64 {
+        (self.gen() >> 11) as f64 / (1u64 << 53) as f64
+    }
 }
 
 static mut RAND: Option<Random> = None;
 
 pub fn random() -> &'static mut Random {
     unsafe {
-        if RAND.is_none() {
-            RAND = Some(Random::new(
+        let rand = &mut *std::ptr::addr_of_mut!(RAND);
+        if rand.is_none() {
+            *rand = Some(Random::new(
                 (SystemTime::UNIX_EPOCH.elapsed().unwrap().as_nanos() & 0xFFFFFFFFFFFFFFFF) as u64,
             ));
         }
-        RAND.as_mut().unwrap()
+        rand.as_mut().unwrap()
     }
 }
 
@@ -88,8 +101,70 @@ impl<T> Shuffle for &mut [T] {
 }
 
 pub fn now_time_u64() -> u64 {
+    try_now_time_u64().expect("Time went backwards!")
+}
+
+/// None if the system clock is before the unix epoch
+pub fn try_now_time_u64() -> Option<u64> {
     SystemTime::now()
         .duration_since(UNIX_EPOCH)
-        .expect("Time went backwards!")
-        .as_secs()
+        .ok()
+        .map(|d| d.as_secs())
+}
+
+#[cfg(test)]
+mod test {
+    #[cfg(feature = "parallel")]
+    use crate::{par_map, par_seeds};
+    use crate::{AliasTable, Random, WeightedReservoir};
+
+    #[test]
+    fn weighted_test() {
+        let w = [1.0, 2.0, 3.0, 0.0, 4.0];
+        let table = AliasTable::new(&w);
+        let mut rd = Random::new(1);
+        let n = 200000;
+        let mut cnt = [0usize; 5];
+        for _ in 0..n {
+            cnt[table.sample(&mut rd)] += 1;
+        }
+        assert_eq!(cnt[3], 0);
+        for (c, x) in cnt.iter().zip(w) {
+            assert!((*c as f64 / n as f64 - x / 10.0).abs() < 0.01);
+        }
+
+        // a reservoir of one picks each item with probability w / sum
+        let mut cnt = [0usize; 5];
+        let trials = 50000;
+        for _ in 0..trials {
+            let mut res = WeightedReservoir::new(1);
+            for (i, &x) in w.iter().enumerate() {
+                res.push(&mut rd, i, x);
+            }
+            cnt[res.into_vec()[0]] += 1;
+        }
+        assert_eq!(cnt[3], 0);
+        for (c, x) in cnt.iter().zip(w) {
+            assert!((*c as f64 / trials as f64 - x / 10.0).abs() < 0.015);
+        }
+
+        let mut res = WeightedReservoir::new(3);
+        for i in 0..10 {
+            res.push(&mut rd, i, if i % 2 == 0 { 1.0 } else { -1.0 });
+        }
+        let mut got = res.into_vec();
+        got.sort_unstable();
+        assert!(got.len() == 3 && got.iter().all(|i| i % 2 == 0));
+    }
+
+    #[test]
+    #[cfg(feature = "parallel")]
+    fn parallel_test() {
+        let a = par_seeds(0..100, |s, rd| (s, rd.gen()));
+        let b: Vec<_> = (0..100).map(|s| (s, Random::new(s).gen())).collect();
+        assert_eq!(a, b);
+        let v = par_map((0..1000u64).collect(), |x| x * x);
+        assert_eq!(v, (0..1000u64).map(|x| x * x).collect::<Vec<_>>());
+        assert!(par_map(Vec::<u8>::new(), |x| x).is_empty());
+    }
 }
@@ -0,0 +1,52 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+use crate::Random;
+
+/// number of threads to use, the available parallelism or 1
+pub fn num_threads() -> usize {
+    thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+/// f applied to every input on all cores, results in input order \
+/// inputs are handed out one at a time, so uneven work is balanced
+pub fn par_map<T, R, F>(inputs: Vec<T>, f: F) -> Vec<R>
+where
+    T: Send,
+    R: Send,
+    F: Fn(T) -> R + Sync,
+{
+    let n = inputs.len();
+    let inputs: Vec<Mutex<Option<T>>> = inputs.into_iter().map(|x| Mutex::new(Some(x))).collect();
+    let results: Vec<Mutex<Option<R>>> = (0..n).map(|_| Mutex::new(None)).collect();
+    let next = AtomicUsize::new(0);
+    thread::scope(|sc| {
+        for _ in 0..num_threads().min(n) {
+            sc.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                if i >= n {
+                    break;
+                }
+                let x = inputs[i].lock().unwrap().take().unwrap();
+                let r = f(x);
+                *results[i].lock().unwrap() = Some(r);
+            });
+        }
+    });
+    results
+        .into_iter()
+        .map(|r| r.into_inner().unwrap().unwrap())
+        .collect()
+}
+
+/// f(seed, rng) for every seed on all cores, the rng is `Random::new(seed)`
+/// so each result only depends on its seed, results in seed order \
+/// e.g. stress tests over many random inputs, or independent annealing restarts
+pub fn par_seeds<R, F>(seeds: impl IntoIterator<Item = u64>, f: F) -> Vec<R>
+where
+    R: Send,
+    F: Fn(u64, &mut Random) -> R + Sync,
+{
+    par_map(seeds.into_iter().collect(), |s| f(s, &mut Random::new(s)))
+}
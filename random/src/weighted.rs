@@ -0,0 +1,121 @@
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+
+use crate::Random;
+
+/// Walker's alias method, O(n) to build and O(1) per sample from a fixed distribution
+pub struct AliasTable {
+    prob: Vec<f64>,
+    alias: Vec<usize>,
+}
+
+impl AliasTable {
+    /// weights must be nonnegative with a positive sum
+    pub fn new(w: &[f64]) -> Self {
+        let n = w.len();
+        let sum: f64 = w.iter().sum();
+        assert!(sum > 0.0, "weights sum to zero!");
+        let mut prob: Vec<f64> = w.iter().map(|&x| x * n as f64 / sum).collect();
+        let mut alias: Vec<usize> = (0..n).collect();
+        let (mut small, mut large): (Vec<usize>, Vec<usize>) = (0..n).partition(|&i| prob[i] < 1.0);
+        while let (Some(&s), Some(&l)) = (small.last(), large.last()) {
+            small.pop();
+            // the rest of column s is filled by l
+            alias[s] = l;
+            prob[l] -= 1.0 - prob[s];
+            if prob[l] < 1.0 {
+                large.pop();
+                small.push(l);
+            }
+        }
+        // leftovers are 1 up to rounding
+        for i in small.into_iter().chain(large) {
+            prob[i] = 1.0;
+        }
+        Self { prob, alias }
+    }
+
+    pub fn len(&self) -> usize {
+        self.prob.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.prob.is_empty()
+    }
+
+    /// index i with probability w\[i\] / sum
+    pub fn sample(&self, rd: &mut Random) -> usize {
+        let i = rd.next(self.len() as u64) as usize;
+        if rd.gen_f64() < self.prob[i] {
+            i
+        } else {
+            self.alias[i]
+        }
+    }
+}
+
+struct Keyed<T>(f64, T);
+
+impl<T> PartialEq for Keyed<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.total_cmp(&other.0) == Ordering::Equal
+    }
+}
+
+impl<T> Eq for Keyed<T> {}
+
+impl<T> PartialOrd for Keyed<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for Keyed<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+/// weighted sampling of k items without replacement from a stream (Efraimidis-Spirakis) \
+/// every item gets the key u^(1 / w), the k largest keys are kept, O(log k) per item
+pub struct WeightedReservoir<T> {
+    k: usize,
+    heap: BinaryHeap<Reverse<Keyed<T>>>,
+}
+
+impl<T> WeightedReservoir<T> {
+    pub fn new(k: usize) -> Self {
+        Self {
+            k,
+            heap: BinaryHeap::with_capacity(k + 1),
+        }
+    }
+
+    /// offer item with weight w > 0, items with w <= 0 are never taken
+    pub fn push(&mut self, rd: &mut Random, item: T, w: f64) {
+        if w <= 0.0 || self.k == 0 {
+            return;
+        }
+        // ln(u) / w orders the same as u^(1 / w)
+        let key = (1.0 - rd.gen_f64()).ln() / w;
+        if self.heap.len() < self.k {
+            self.heap.push(Reverse(Keyed(key, item)));
+        } else if self.heap.peek().is_some_and(|t| t.0 .0 < key) {
+            self.heap.pop();
+            self.heap.push(Reverse(Keyed(key, item)));
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// the sampled items, in no particular order
+    pub fn into_vec(self) -> Vec<T> {
+        self.heap.into_iter().map(|t| t.0 .1).collect()
+    }
+}
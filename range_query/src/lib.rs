@@ -0,0 +1,100 @@
+use std::ops::{Bound, RangeBounds};
+
+/// integer positions of a range structure
+pub trait Position: Copy + Ord {
+    /// None past the largest value
+    fn succ(self) -> Option<Self>;
+    /// None past the smallest value
+    fn pred(self) -> Option<Self>;
+}
+
+macro_rules! impl_position {
+    ($($t:ty),*) => {
+        $(
+            impl Position for $t {
+                fn succ(self) -> Option<Self> {
+                    self.checked_add(1)
+                }
+                fn pred(self) -> Option<Self> {
+                    self.checked_sub(1)
+                }
+            }
+        )*
+    };
+}
+
+impl_position!(u32, u64, usize, i32, i64);
+
+/// inclusive [l, r] of range, an unbounded end is min or max \
+/// None if the range is empty, `..0` included
+pub fn closed<T: Position, R: RangeBounds<T>>(range: R, min: T, max: T) -> Option<(T, T)> {
+    let l = match range.start_bound() {
+        Bound::Included(&l) => l,
+        Bound::Excluded(&l) => l.succ()?,
+        Bound::Unbounded => min,
+    };
+    let r = match range.end_bound() {
+        Bound::Included(&r) => r,
+        Bound::Excluded(&r) => r.pred()?,
+        Bound::Unbounded => max,
+    };
+    (l <= r).then_some((l, r))
+}
+
+/// half-open [l, r) of range with l <= r, an unbounded end is start or end
+pub fn half_open<T: Position, R: RangeBounds<T>>(range: R, start: T, end: T) -> (T, T) {
+    let l = match range.start_bound() {
+        Bound::Included(&l) => l,
+        Bound::Excluded(&l) => l.succ().expect("range start overflow!"),
+        Bound::Unbounded => start,
+    };
+    let r = match range.end_bound() {
+        Bound::Included(&r) => r.succ().expect("range end overflow!"),
+        Bound::Excluded(&r) => r,
+        Bound::Unbounded => end,
+    };
+    (l, r.max(l))
+}
+
+/// binary search on the products of a range structure, same semantics as ACL \
+/// f(identity) must be true
+pub trait Bisect {
+    type Index;
+    type S;
+    /// max r such that f(prod of [l, r)) is true
+    fn max_right<F>(&self, l: Self::Index, f: F) -> Self::Index
+    where
+        F: Fn(&Self::S) -> bool;
+    /// min l such that f(prod of [l, r)) is true
+    fn min_left<F>(&self, r: Self::Index, f: F) -> Self::Index
+    where
+        F: Fn(&Self::S) -> bool;
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{closed, half_open};
+    use std::ops::Bound;
+
+    #[test]
+    fn range_test() {
+        assert_eq!(closed(2..5usize, 1, 9), Some((2, 4)));
+        assert_eq!(closed(.., 1, 9usize), Some((1, 9)));
+        assert_eq!(closed(..0usize, 0, 9), None);
+        assert_eq!(closed(3..3usize, 0, 9), None);
+        assert_eq!(closed(-5..=-5i64, -9, 9), Some((-5, -5)));
+        assert_eq!(closed(..i64::MIN, i64::MIN, 0), None);
+        let after_max = (Bound::Excluded(u32::MAX), Bound::Unbounded);
+        assert_eq!(closed(after_max, 0, u32::MAX), None);
+
+        assert_eq!(half_open(2..=4usize, 0, 9), (2, 5));
+        assert_eq!(half_open(..usize::MIN, 0, 9), (0, 0));
+        assert_eq!(half_open(3.., 0, 9usize), (3, 9));
+        let (l, r) = (5, 3);
+        assert_eq!(half_open(l..r, 0, 9usize), (5, 5));
+        assert_eq!(
+            half_open((Bound::Excluded(1), Bound::Excluded(2)), 0, 9usize),
+            (2, 2)
+        );
+    }
+}
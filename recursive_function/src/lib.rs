@@ -2,6 +2,7 @@ use std::marker::PhantomData;
 macro_rules! recursive_function {
         ($name: ident, $trait: ident, ($($type: ident $arg: ident,)*)) => {
             pub trait $trait<$($type, )*Output> {
+                #[allow(clippy::too_many_arguments)]
                 fn call(&mut self, $($arg: $type,)*) -> Output;
             }
 
@@ -33,6 +34,7 @@ macro_rules! recursive_function {
             where
                 F: FnMut(&mut dyn $trait<$($type, )*Output>, $($type, )*) -> Output,
             {
+                #[allow(clippy::too_many_arguments)]
                 fn call(&mut self, $($arg: $type,)*) -> Output {
                     unsafe { (*self.f.get())(self, $($arg, )*) }
                 }
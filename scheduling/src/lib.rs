@@ -0,0 +1,159 @@
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+
+/// compare a1/b1 with a2/b2 exactly, b == 0 means infinity (sign of a) \
+/// a and b can't be both 0
+pub fn cmp_frac(a1: i64, b1: i64, a2: i64, b2: i64) -> Ordering {
+    if b1 == 0 && b2 == 0 {
+        // both cross products are 0, only the signs of the infinities differ
+        return a1.signum().cmp(&a2.signum());
+    }
+    let (a1, b1) = if b1 < 0 {
+        (-(a1 as i128), -(b1 as i128))
+    } else {
+        (a1 as i128, b1 as i128)
+    };
+    let (a2, b2) = if b2 < 0 {
+        (-(a2 as i128), -(b2 as i128))
+    } else {
+        (a2 as i128, b2 as i128)
+    };
+    (a1 * b2).cmp(&(a2 * b1))
+}
+
+/// comparer sorting items by key(x) = (a, b) in increasing a/b \
+/// e.g. minimize weighted completion time: key = (time, weight)
+pub fn ratio_cmp<T, F>(key: F) -> impl Fn(&T, &T) -> Ordering
+where
+    F: Fn(&T) -> (i64, i64),
+{
+    move |x, y| {
+        let ((a1, b1), (a2, b2)) = (key(x), key(y));
+        cmp_frac(a1, b1, a2, b2)
+    }
+}
+
+/// comparer putting x before y iff cost(x, y) < cost(y, x), where cost(x, y) is
+/// the cost of doing x right before y \
+/// only a valid order if the exchange argument holds (the relation is transitive)
+pub fn exchange_cmp<T, K, F>(cost: F) -> impl Fn(&T, &T) -> Ordering
+where
+    K: Ord,
+    F: Fn(&T, &T) -> K,
+{
+    move |x, y| cost(x, y).cmp(&cost(y, x))
+}
+
+/// jobs: (duration, deadline), one machine, start at time 0 \
+/// return a maximum set of jobs that all finish before their deadlines, in execution order
+pub fn max_jobs_by_deadline(jobs: &[(u64, u64)]) -> Vec<usize> {
+    let mut ord: Vec<usize> = (0..jobs.len()).collect();
+    ord.sort_by_key(|&i| jobs[i].1);
+    let mut heap = BinaryHeap::new();
+    let mut time = 0;
+    for i in ord {
+        let (dur, ddl) = jobs[i];
+        time += dur;
+        heap.push((dur, i));
+        if time > ddl {
+            // drop the longest one taken so far
+            let (dur, _) = heap.pop().unwrap();
+            time -= dur;
+        }
+    }
+    let mut res: Vec<usize> = heap.into_iter().map(|(_, i)| i).collect();
+    res.sort_by_key(|&i| (jobs[i].1, i));
+    res
+}
+
+/// jobs: (deadline, profit), every job takes one unit of time, slots are 1..=deadline \
+/// return the max total profit and the chosen jobs in execution order
+pub fn max_profit_unit_jobs(jobs: &[(u64, i64)]) -> (i64, Vec<usize>) {
+    let mut ord: Vec<usize> = (0..jobs.len()).collect();
+    ord.sort_by_key(|&i| jobs[i].0);
+    let mut heap = BinaryHeap::new();
+    for i in ord {
+        let (ddl, profit) = jobs[i];
+        if profit <= 0 {
+            continue;
+        }
+        heap.push(Reverse((profit, i)));
+        if heap.len() as u64 > ddl {
+            heap.pop();
+        }
+    }
+    let mut res: Vec<usize> = heap.into_iter().map(|Reverse((_, i))| i).collect();
+    res.sort_by_key(|&i| (jobs[i].0, i));
+    (res.iter().map(|&i| jobs[i].1).sum(), res)
+}
+
+#[cfg(test)]
+mod test {
+    use std::cmp::Ordering;
+
+    use random::Random;
+
+    use crate::{cmp_frac, max_jobs_by_deadline, max_profit_unit_jobs};
+
+    #[test]
+    fn cmp_frac_test() {
+        assert_eq!(cmp_frac(1, 0, -1, 0), Ordering::Greater);
+        assert_eq!(cmp_frac(-1, 0, 5, 0), Ordering::Less);
+        assert_eq!(cmp_frac(3, 0, 7, 0), Ordering::Equal);
+        assert_eq!(cmp_frac(1, 0, i64::MAX, 1), Ordering::Greater);
+        assert_eq!(cmp_frac(-1, 0, i64::MIN, 1), Ordering::Less);
+        assert_eq!(cmp_frac(1, -2, -2, 4), Ordering::Equal);
+        assert_eq!(cmp_frac(1, 3, 1, 2), Ordering::Less);
+        assert_eq!(cmp_frac(i64::MAX, i64::MAX - 1, 1, 1), Ordering::Greater);
+    }
+
+    // whether the jobs, run in the given order from time 0, all meet their deadlines
+    fn on_time(jobs: &[(u64, u64)], ord: &[usize]) -> bool {
+        let mut time = 0;
+        ord.iter().all(|&i| {
+            time += jobs[i].0;
+            time <= jobs[i].1
+        })
+    }
+
+    #[test]
+    fn deadline_test() {
+        let mut rd = Random::new(8);
+        for _ in 0..300 {
+            let n = rd.next(9) as usize;
+            let jobs: Vec<(u64, u64)> = (0..n)
+                .map(|_| (rd.next_bounds(1, 6), rd.next(20)))
+                .collect();
+            let res = max_jobs_by_deadline(&jobs);
+            assert!(on_time(&jobs, &res));
+            // a set is feasible iff it is on time in deadline order
+            let best = (0..1usize << n)
+                .filter(|s| {
+                    let mut ord: Vec<usize> = (0..n).filter(|i| s >> i & 1 == 1).collect();
+                    ord.sort_by_key(|&i| jobs[i].1);
+                    on_time(&jobs, &ord)
+                })
+                .map(|s| s.count_ones() as usize)
+                .max()
+                .unwrap();
+            assert_eq!(res.len(), best);
+
+            let jobs: Vec<(u64, i64)> = (0..n)
+                .map(|_| (rd.next(5), rd.next(21) as i64 - 5))
+                .collect();
+            let (profit, res) = max_profit_unit_jobs(&jobs);
+            assert_eq!(profit, res.iter().map(|&i| jobs[i].1).sum::<i64>());
+            assert!(res.iter().enumerate().all(|(t, &i)| (t as u64) < jobs[i].0));
+            let best = (0..1usize << n)
+                .filter_map(|s| {
+                    let mut ord: Vec<usize> = (0..n).filter(|i| s >> i & 1 == 1).collect();
+                    ord.sort_by_key(|&i| jobs[i].0);
+                    let ok = ord.iter().enumerate().all(|(t, &i)| (t as u64) < jobs[i].0);
+                    ok.then(|| ord.iter().map(|&i| jobs[i].1).sum::<i64>())
+                })
+                .max()
+                .unwrap();
+            assert_eq!(profit, best);
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::ops::Add;
+
+use crate::{SearchTree, StateMap};
+
+/// which of two states with the same f = g + h is expanded first
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TieBreak {
+    /// larger g first, usually reaches the goal sooner
+    Deeper,
+    /// smaller g first
+    Shallower,
+}
+
+struct Entry<D> {
+    f: D,
+    g: D,
+    id: usize,
+    tie: TieBreak,
+}
+
+impl<D: Ord> PartialEq for Entry<D> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<D: Ord> Eq for Entry<D> {}
+
+impl<D: Ord> PartialOrd for Entry<D> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// the max-heap pops the greatest entry, so smaller f is greater
+impl<D: Ord> Ord for Entry<D> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let g = match self.tie {
+            TieBreak::Deeper => self.g.cmp(&other.g),
+            TieBreak::Shallower => other.g.cmp(&self.g),
+        };
+        other.f.cmp(&self.f).then(g).then(other.id.cmp(&self.id))
+    }
+}
+
+/// A* with nonnegative costs, neighbors(s, buf) pushes (state, cost) into buf \
+/// heuristic must be admissible (never above the true distance to a goal) \
+/// if it is also consistent (h(u) <= w(u, v) + h(v)), pass consistent = true
+/// so settled states are never reopened \
+/// stops once a state with is_goal is settled
+pub fn astar<S, D, M, N, H, G>(
+    starts: Vec<S>,
+    map: M,
+    mut neighbors: N,
+    mut heuristic: H,
+    mut is_goal: G,
+    consistent: bool,
+    tie: TieBreak,
+) -> SearchTree<S, D, M>
+where
+    D: Copy + Ord + Add<Output = D> + Default,
+    M: StateMap<S>,
+    N: FnMut(&S, &mut Vec<(S, D)>),
+    H: FnMut(&S) -> D,
+    G: FnMut(&S) -> bool,
+{
+    let mut tree = SearchTree::new(map);
+    let mut h = vec![];
+    let mut heap = BinaryHeap::new();
+    for s in starts {
+        let hs = heuristic(&s);
+        let (id, new) = tree.discover(s, D::default(), usize::MAX);
+        if new {
+            h.push(hs);
+            heap.push(Entry {
+                f: hs,
+                g: D::default(),
+                id,
+                tie,
+            });
+        }
+    }
+    let mut done = vec![];
+    let mut buf = vec![];
+    while let Some(Entry { g, id: u, .. }) = heap.pop() {
+        done.resize(tree.states.len(), false);
+        if done[u] || g > tree.dist[u] {
+            continue;
+        }
+        done[u] = true;
+        if is_goal(&tree.states[u]) {
+            tree.goal = Some(u);
+            return tree;
+        }
+        neighbors(&tree.states[u], &mut buf);
+        for (s, w) in buf.drain(..) {
+            let ng = g + w;
+            let (id, new) = tree.discover(s, ng, u);
+            if new {
+                h.push(heuristic(&tree.states[id]));
+            } else if ng >= tree.dist[id] || consistent && done.get(id) == Some(&true) {
+                continue;
+            } else {
+                tree.dist[id] = ng;
+                tree.prev[id] = u;
+                if id < done.len() {
+                    done[id] = false;
+                }
+            }
+            heap.push(Entry {
+                f: ng + h[id],
+                g: ng,
+                id,
+                tie,
+            });
+        }
+    }
+    tree
+}
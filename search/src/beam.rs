@@ -0,0 +1,76 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::Budget;
+
+/// beam search maximizing a score, expand(s, buf) pushes (child, score) into buf \
+/// children with equal key(child) in a layer are merged, keeping the best scored one,
+/// then the width best children form the next layer \
+/// runs for at most depth layers, until a layer is empty, or until budget runs out
+/// (one node per expanded state); return the best scored state seen, None if start
+/// has no children \
+/// scores must be comparable with each other, panics on a NaN score
+pub fn beam_search<S, Sc, K, N, H>(
+    start: S,
+    width: usize,
+    depth: usize,
+    mut expand: N,
+    mut key: H,
+    budget: &mut Budget,
+) -> Option<(S, Sc)>
+where
+    S: Clone,
+    Sc: PartialOrd + Copy,
+    K: Hash + Eq,
+    N: FnMut(&S, &mut Vec<(S, Sc)>),
+    H: FnMut(&S) -> K,
+{
+    let mut beam = vec![start];
+    let mut best: Option<(S, Sc)> = None;
+    let mut buf = vec![];
+    let mut next: Vec<(S, Sc)> = vec![];
+    let mut seen: HashMap<K, usize> = HashMap::new();
+    'outer: for _ in 0..depth {
+        next.clear();
+        seen.clear();
+        for s in &beam {
+            if !budget.spend() {
+                break 'outer;
+            }
+            expand(s, &mut buf);
+            for (c, sc) in buf.drain(..) {
+                let k = key(&c);
+                match seen.get(&k) {
+                    Some(&i) if sc > next[i].1 => next[i] = (c, sc),
+                    Some(_) => {}
+                    None => {
+                        seen.insert(k, next.len());
+                        next.push((c, sc));
+                    }
+                }
+            }
+        }
+        if next.is_empty() {
+            break;
+        }
+        if next.len() > width {
+            if width > 0 {
+                next.select_nth_unstable_by(width - 1, |a, b| {
+                    b.1.partial_cmp(&a.1).expect("NaN score!")
+                });
+            }
+            next.truncate(width);
+        }
+        if let Some(top) = next
+            .iter()
+            .max_by(|a, b| a.1.partial_cmp(&b.1).expect("NaN score!"))
+        {
+            if best.as_ref().is_none_or(|b| top.1 > b.1) {
+                best = Some(top.clone());
+            }
+        }
+        beam.clear();
+        beam.extend(next.drain(..).map(|e| e.0));
+    }
+    best
+}
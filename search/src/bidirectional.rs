@@ -0,0 +1,70 @@
+use crate::{SearchTree, StateMap};
+
+/// shortest path from start to goal searching from both ends, one layer at a time
+/// from the smaller frontier \
+/// neighbors gives the forward moves, rev_neighbors the states that can move into s
+/// (pass the same function twice for reversible moves) \
+/// return the states on a shortest path, including start and goal
+pub fn bidirectional_bfs<S, M, N, R>(
+    start: S,
+    goal: S,
+    map: M,
+    mut neighbors: N,
+    mut rev_neighbors: R,
+) -> Option<Vec<S>>
+where
+    S: Clone,
+    M: StateMap<S> + Clone,
+    N: FnMut(&S, &mut Vec<S>),
+    R: FnMut(&S, &mut Vec<S>),
+{
+    let mut fwd = SearchTree::new(map.clone());
+    let mut bwd = SearchTree::new(map);
+    let (s, _) = fwd.discover(start, 0u64, usize::MAX);
+    let (g, _) = bwd.discover(goal, 0u64, usize::MAX);
+    if fwd.id(&bwd.states[g]).is_some() {
+        return Some(fwd.path(s));
+    }
+    let (mut fq, mut bq) = (vec![s], vec![g]);
+    let mut buf = vec![];
+    while !fq.is_empty() && !bq.is_empty() {
+        let forward = fq.len() <= bq.len();
+        let (tree, other, q) = if forward {
+            (&mut fwd, &bwd, &mut fq)
+        } else {
+            (&mut bwd, &fwd, &mut bq)
+        };
+        // (total length, id in tree, id in other)
+        let mut best: Option<(u64, usize, usize)> = None;
+        let mut next = vec![];
+        for &u in q.iter() {
+            if forward {
+                neighbors(&tree.states[u], &mut buf);
+            } else {
+                rev_neighbors(&tree.states[u], &mut buf);
+            }
+            let d = tree.dist[u] + 1;
+            for t in buf.drain(..) {
+                let meet = other.id(&t);
+                let (id, new) = tree.discover(t, d, u);
+                if new {
+                    next.push(id);
+                }
+                if let Some(o) = meet {
+                    let total = tree.dist[id] + other.dist[o];
+                    if best.is_none_or(|b| total < b.0) {
+                        best = Some((total, id, o));
+                    }
+                }
+            }
+        }
+        if let Some((_, id, o)) = best {
+            let (f, b) = if forward { (id, o) } else { (o, id) };
+            let mut path = fwd.path(f);
+            path.extend(bwd.path(b).into_iter().rev().skip(1));
+            return Some(path);
+        }
+        *q = next;
+    }
+    None
+}
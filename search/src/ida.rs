@@ -0,0 +1,200 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::ops::Add;
+
+use crate::Budget;
+
+/// hook to cut transpositions inside one IDA* iteration
+pub trait Transposition<S, D> {
+    /// called before each iteration with its f bound
+    fn new_bound(&mut self, _bound: D) {}
+    /// whether s, reached with cost g, can be skipped
+    fn prune(&mut self, s: &S, g: D) -> bool;
+}
+
+/// no pruning
+impl<S, D> Transposition<S, D> for () {
+    fn prune(&mut self, _s: &S, _g: D) -> bool {
+        false
+    }
+}
+
+/// skips a state already reached with cost <= g in the current iteration \
+/// keys by encode(s), memory grows with the number of distinct states visited
+pub struct BestCost<K, D, E> {
+    best: HashMap<K, D>,
+    encode: E,
+}
+
+impl<K, D, E> BestCost<K, D, E> {
+    pub fn new(encode: E) -> Self {
+        Self {
+            best: HashMap::new(),
+            encode,
+        }
+    }
+}
+
+impl<S, K, D, E> Transposition<S, D> for BestCost<K, D, E>
+where
+    K: Hash + Eq,
+    D: Copy + Ord,
+    E: Fn(&S) -> K,
+{
+    fn new_bound(&mut self, _bound: D) {
+        self.best.clear();
+    }
+
+    fn prune(&mut self, s: &S, g: D) -> bool {
+        match self.best.entry((self.encode)(s)) {
+            Entry::Occupied(e) if *e.get() <= g => true,
+            Entry::Occupied(mut e) => {
+                e.insert(g);
+                false
+            }
+            Entry::Vacant(e) => {
+                e.insert(g);
+                false
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum IdaResult<S, D> {
+    /// states on a cheapest path from start to a goal, and its cost
+    Found(Vec<S>, D),
+    /// the whole reachable graph was searched without meeting a goal
+    NotFound,
+    /// the budget ran out, bound is the last f bound that was being searched
+    OutOfBudget(D),
+}
+
+struct Ctx<'a, S, D, N, H, G, T> {
+    neighbors: N,
+    heuristic: H,
+    is_goal: G,
+    table: T,
+    budget: &'a mut Budget,
+    path: Vec<S>,
+    bufs: Vec<Vec<(S, D)>>,
+}
+
+impl<S, D, N, H, G, T> Ctx<'_, S, D, N, H, G, T>
+where
+    D: Copy + Ord + Add<Output = D> + Default,
+    N: FnMut(&S, &mut Vec<(S, D)>),
+    H: FnMut(&S) -> D,
+    G: FnMut(&S) -> bool,
+    T: Transposition<S, D>,
+{
+    // Ok(Some(cost)) when a goal is on top of path, next collects the smallest f above bound
+    fn dfs(&mut self, g: D, bound: D, next: &mut Option<D>) -> Result<Option<D>, ()> {
+        let depth = self.path.len() - 1;
+        let s = &self.path[depth];
+        let f = g + (self.heuristic)(s);
+        if f > bound {
+            if next.is_none_or(|x| f < x) {
+                *next = Some(f);
+            }
+            return Ok(None);
+        }
+        if (self.is_goal)(s) {
+            return Ok(Some(g));
+        }
+        if !self.budget.spend() {
+            return Err(());
+        }
+        if self.bufs.len() <= depth {
+            self.bufs.push(vec![]);
+        }
+        let mut buf = std::mem::take(&mut self.bufs[depth]);
+        (self.neighbors)(s, &mut buf);
+        let mut res = Ok(None);
+        for (t, w) in buf.drain(..) {
+            if self.table.prune(&t, g + w) {
+                continue;
+            }
+            self.path.push(t);
+            res = self.dfs(g + w, bound, next);
+            if res != Ok(None) {
+                break;
+            }
+            self.path.pop();
+        }
+        buf.clear();
+        self.bufs[depth] = buf;
+        res
+    }
+}
+
+/// IDA* with nonnegative costs and an admissible heuristic, memory O(depth) \
+/// neighbors(s, buf) pushes (state, cost) into buf, table cuts transpositions
+/// (pass () for none) \
+/// every expanded node is charged to budget
+pub fn ida_star<S, D, N, H, G, T>(
+    start: S,
+    neighbors: N,
+    mut heuristic: H,
+    is_goal: G,
+    mut table: T,
+    budget: &mut Budget,
+) -> IdaResult<S, D>
+where
+    D: Copy + Ord + Add<Output = D> + Default,
+    N: FnMut(&S, &mut Vec<(S, D)>),
+    H: FnMut(&S) -> D,
+    G: FnMut(&S) -> bool,
+    T: Transposition<S, D>,
+{
+    let mut bound = heuristic(&start);
+    table.new_bound(bound);
+    table.prune(&start, D::default());
+    let mut ctx = Ctx {
+        neighbors,
+        heuristic,
+        is_goal,
+        table,
+        budget,
+        path: vec![start],
+        bufs: vec![],
+    };
+    loop {
+        let mut next = None;
+        match ctx.dfs(D::default(), bound, &mut next) {
+            Err(()) => return IdaResult::OutOfBudget(bound),
+            Ok(Some(cost)) => return IdaResult::Found(ctx.path, cost),
+            Ok(None) => match next {
+                None => return IdaResult::NotFound,
+                Some(b) => {
+                    bound = b;
+                    ctx.table.new_bound(bound);
+                    ctx.table.prune(&ctx.path[0], D::default());
+                }
+            },
+        }
+    }
+}
+
+/// iterative deepening on unit costs, ida_star without a heuristic \
+/// neighbors(s, buf) pushes the states reachable from s into buf
+pub fn iterative_deepening<S, N, G, T>(
+    start: S,
+    mut neighbors: N,
+    is_goal: G,
+    table: T,
+    budget: &mut Budget,
+) -> IdaResult<S, u64>
+where
+    N: FnMut(&S, &mut Vec<S>),
+    G: FnMut(&S) -> bool,
+    T: Transposition<S, u64>,
+{
+    let mut tmp = vec![];
+    let weighted = |s: &S, buf: &mut Vec<(S, u64)>| {
+        neighbors(s, &mut tmp);
+        buf.extend(tmp.drain(..).map(|t| (t, 1)));
+    };
+    ida_star(start, weighted, |_| 0, is_goal, table, budget)
+}
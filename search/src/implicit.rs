@@ -0,0 +1,98 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+use std::ops::Add;
+
+use crate::{SearchTree, StateMap};
+
+/// unweighted search, neighbors(s, buf) pushes the states reachable from s into buf \
+/// stops once a state with is_goal is discovered, pass |_| false to explore everything
+pub fn bfs<S, M, N, G>(
+    starts: Vec<S>,
+    map: M,
+    mut neighbors: N,
+    mut is_goal: G,
+) -> SearchTree<S, u64, M>
+where
+    M: StateMap<S>,
+    N: FnMut(&S, &mut Vec<S>),
+    G: FnMut(&S) -> bool,
+{
+    let mut tree = SearchTree::new(map);
+    let mut q = VecDeque::new();
+    for s in starts {
+        let goal = is_goal(&s);
+        let (id, new) = tree.discover(s, 0, usize::MAX);
+        if new && goal {
+            tree.goal = Some(id);
+            return tree;
+        }
+        if new {
+            q.push_back(id);
+        }
+    }
+    let mut buf = vec![];
+    while let Some(u) = q.pop_front() {
+        neighbors(&tree.states[u], &mut buf);
+        let d = tree.dist[u] + 1;
+        for s in buf.drain(..) {
+            let goal = is_goal(&s);
+            let (id, new) = tree.discover(s, d, u);
+            if new {
+                if goal {
+                    tree.goal = Some(id);
+                    return tree;
+                }
+                q.push_back(id);
+            }
+        }
+    }
+    tree
+}
+
+/// weighted search with nonnegative costs, neighbors(s, buf) pushes (state, cost) into buf \
+/// stops once a state with is_goal is settled, pass |_| false to explore everything
+pub fn dijkstra<S, D, M, N, G>(
+    starts: Vec<S>,
+    map: M,
+    mut neighbors: N,
+    mut is_goal: G,
+) -> SearchTree<S, D, M>
+where
+    D: Copy + Ord + Add<Output = D> + Default,
+    M: StateMap<S>,
+    N: FnMut(&S, &mut Vec<(S, D)>),
+    G: FnMut(&S) -> bool,
+{
+    let mut tree = SearchTree::new(map);
+    let mut heap = BinaryHeap::new();
+    for s in starts {
+        let (id, new) = tree.discover(s, D::default(), usize::MAX);
+        if new {
+            heap.push(Reverse((D::default(), id)));
+        }
+    }
+    let mut done = vec![];
+    let mut buf = vec![];
+    while let Some(Reverse((d, u))) = heap.pop() {
+        done.resize(tree.states.len(), false);
+        if done[u] || d > tree.dist[u] {
+            continue;
+        }
+        done[u] = true;
+        if is_goal(&tree.states[u]) {
+            tree.goal = Some(u);
+            return tree;
+        }
+        neighbors(&tree.states[u], &mut buf);
+        for (s, w) in buf.drain(..) {
+            let nd = d + w;
+            let (id, new) = tree.discover(s, nd, u);
+            if new || nd < tree.dist[id] {
+                tree.dist[id] = nd;
+                tree.prev[id] = u;
+                heap.push(Reverse((nd, id)));
+            }
+        }
+    }
+    tree
+}
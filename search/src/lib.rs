@@ -0,0 +1,554 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+mod astar;
+mod beam;
+mod bidirectional;
+mod ida;
+mod implicit;
+mod timer;
+
+pub use astar::{astar, TieBreak};
+pub use beam::beam_search;
+pub use bidirectional::bidirectional_bfs;
+pub use ida::{ida_star, iterative_deepening, BestCost, IdaResult, Transposition};
+pub use implicit::{bfs, dijkstra};
+pub use timer::{Budget, Timer};
+
+/// maps a state to the id it got when first discovered
+pub trait StateMap<S> {
+    fn id(&self, s: &S) -> Option<usize>;
+    fn insert(&mut self, s: &S, id: usize);
+}
+
+/// states keyed by encode(s) in a HashMap
+#[derive(Clone)]
+pub struct Hashed<K, E> {
+    map: HashMap<K, usize>,
+    encode: E,
+}
+
+impl<K, E> Hashed<K, E> {
+    pub fn new(encode: E) -> Self {
+        Self {
+            map: HashMap::new(),
+            encode,
+        }
+    }
+}
+
+impl<S, K, E> StateMap<S> for Hashed<K, E>
+where
+    K: Hash + Eq,
+    E: Fn(&S) -> K,
+{
+    fn id(&self, s: &S) -> Option<usize> {
+        self.map.get(&(self.encode)(s)).copied()
+    }
+    fn insert(&mut self, s: &S, id: usize) {
+        self.map.insert((self.encode)(s), id);
+    }
+}
+
+/// states with encode(s) < n, kept in a Vec
+#[derive(Clone)]
+pub struct Dense<E> {
+    ids: Vec<usize>,
+    encode: E,
+}
+
+impl<E> Dense<E> {
+    pub fn new(n: usize, encode: E) -> Self {
+        Self {
+            ids: vec![usize::MAX; n],
+            encode,
+        }
+    }
+}
+
+impl<S, E> StateMap<S> for Dense<E>
+where
+    E: Fn(&S) -> usize,
+{
+    fn id(&self, s: &S) -> Option<usize> {
+        let id = self.ids[(self.encode)(s)];
+        (id != usize::MAX).then_some(id)
+    }
+    fn insert(&mut self, s: &S, id: usize) {
+        self.ids[(self.encode)(s)] = id;
+    }
+}
+
+/// every discovered state with its distance and the state it was reached from
+pub struct SearchTree<S, D, M> {
+    pub states: Vec<S>,
+    pub dist: Vec<D>,
+    pub prev: Vec<usize>, // usize::MAX for start states
+    pub goal: Option<usize>,
+    map: M,
+}
+
+impl<S, D, M> SearchTree<S, D, M>
+where
+    M: StateMap<S>,
+{
+    fn new(map: M) -> Self {
+        Self {
+            states: vec![],
+            dist: vec![],
+            prev: vec![],
+            goal: None,
+            map,
+        }
+    }
+
+    // return the id, and whether s is new
+    fn discover(&mut self, s: S, d: D, prev: usize) -> (usize, bool) {
+        if let Some(id) = self.map.id(&s) {
+            return (id, false);
+        }
+        let id = self.states.len();
+        self.map.insert(&s, id);
+        self.states.push(s);
+        self.dist.push(d);
+        self.prev.push(prev);
+        (id, true)
+    }
+
+    pub fn id(&self, s: &S) -> Option<usize> {
+        self.map.id(s)
+    }
+
+    /// ids of the states on the path from a start state to id
+    pub fn path_ids(&self, mut id: usize) -> Vec<usize> {
+        let mut res = vec![id];
+        while self.prev[id] != usize::MAX {
+            id = self.prev[id];
+            res.push(id);
+        }
+        res.reverse();
+        res
+    }
+
+    /// states on the path from a start state to id
+    pub fn path(&self, id: usize) -> Vec<S>
+    where
+        S: Clone,
+    {
+        self.path_ids(id)
+            .into_iter()
+            .map(|i| self.states[i].clone())
+            .collect()
+    }
+
+    pub fn goal_path(&self) -> Option<Vec<S>>
+    where
+        S: Clone,
+    {
+        self.goal.map(|g| self.path(g))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        astar, beam_search, bfs, bidirectional_bfs, dijkstra, ida_star, iterative_deepening,
+        BestCost, Budget, Dense, Hashed, IdaResult, TieBreak,
+    };
+    use random::Random;
+
+    type Cell = (usize, usize);
+
+    // h x w grid with about a quarter of the cells blocked, and a cost in 1..=9 to enter each cell
+    fn grid(rd: &mut Random, h: usize, w: usize) -> (Vec<Vec<bool>>, Vec<Vec<u64>>) {
+        let free = (0..h)
+            .map(|_| (0..w).map(|_| rd.next(4) != 0).collect())
+            .collect();
+        let cost = (0..h)
+            .map(|_| (0..w).map(|_| rd.next_bounds(1, 9)).collect())
+            .collect();
+        (free, cost)
+    }
+
+    fn moves(free: &[Vec<bool>], (r, c): Cell) -> Vec<Cell> {
+        let (h, w) = (free.len(), free[0].len());
+        [
+            (r.wrapping_sub(1), c),
+            (r + 1, c),
+            (r, c.wrapping_sub(1)),
+            (r, c + 1),
+        ]
+        .into_iter()
+        .filter(|&(x, y)| x < h && y < w && free[x][y])
+        .collect()
+    }
+
+    // distances from s, relaxing every cell until nothing changes, unit costs without cost
+    fn brute(free: &[Vec<bool>], cost: Option<&[Vec<u64>]>, s: Cell) -> Vec<Vec<Option<u64>>> {
+        let (h, w) = (free.len(), free[0].len());
+        let mut d = vec![vec![None; w]; h];
+        d[s.0][s.1] = Some(0);
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for u in (0..h).flat_map(|r| (0..w).map(move |c| (r, c))) {
+                let Some(x) = d[u.0][u.1] else { continue };
+                for (a, b) in moves(free, u) {
+                    let nd = x + cost.map_or(1, |k| k[a][b]);
+                    if d[a][b].is_none_or(|y| nd < y) {
+                        d[a][b] = Some(nd);
+                        changed = true;
+                    }
+                }
+            }
+        }
+        d
+    }
+
+    fn check_path(free: &[Vec<bool>], path: &[Cell], s: Cell, g: Cell) {
+        assert_eq!((path.first(), path.last()), (Some(&s), Some(&g)));
+        assert!(path.windows(2).all(|p| moves(free, p[0]).contains(&p[1])));
+    }
+
+    #[test]
+    fn implicit_test() {
+        let mut rd = Random::new(3);
+        for _ in 0..100 {
+            let (h, w) = (
+                rd.next_bounds(1, 12) as usize,
+                rd.next_bounds(1, 12) as usize,
+            );
+            let (mut free, cost) = grid(&mut rd, h, w);
+            free[0][0] = true;
+            let goal = (rd.next(h as u64) as usize, rd.next(w as u64) as usize);
+            let unit = brute(&free, None, (0, 0));
+            let weighted = brute(&free, Some(&cost), (0, 0));
+
+            let dense = Dense::new(h * w, |&(r, c): &Cell| r * w + c);
+            let t = bfs(
+                vec![(0, 0)],
+                dense.clone(),
+                |&u, buf| buf.extend(moves(&free, u)),
+                |&u| u == goal,
+            );
+            assert_eq!(t.goal.map(|g| t.dist[g]), unit[goal.0][goal.1]);
+            if let Some(path) = t.goal_path() {
+                check_path(&free, &path, (0, 0), goal);
+                assert_eq!(path.len() as u64, t.dist[t.goal.unwrap()] + 1);
+            }
+            let t = bfs(
+                vec![(0, 0)],
+                Hashed::new(|&u: &Cell| u),
+                |&u, buf| buf.extend(moves(&free, u)),
+                |_| false,
+            );
+            for (r, row) in unit.iter().enumerate() {
+                for (c, &d) in row.iter().enumerate() {
+                    assert_eq!(t.id(&(r, c)).map(|i| t.dist[i]), d);
+                }
+            }
+
+            let t = dijkstra(
+                vec![(0, 0)],
+                dense,
+                |&u, buf| buf.extend(moves(&free, u).into_iter().map(|v| (v, cost[v.0][v.1]))),
+                |&u| u == goal,
+            );
+            assert_eq!(t.goal.map(|g| t.dist[g]), weighted[goal.0][goal.1]);
+            if let Some(path) = t.goal_path() {
+                check_path(&free, &path, (0, 0), goal);
+                let total: u64 = path[1..].iter().map(|&(r, c)| cost[r][c]).sum();
+                assert_eq!(total, t.dist[t.goal.unwrap()]);
+            }
+            let t = dijkstra(
+                vec![(0, 0)],
+                Hashed::new(|&u: &Cell| u),
+                |&u, buf| buf.extend(moves(&free, u).into_iter().map(|v| (v, cost[v.0][v.1]))),
+                |_| false,
+            );
+            for (r, row) in weighted.iter().enumerate() {
+                for (c, &d) in row.iter().enumerate() {
+                    assert_eq!(t.id(&(r, c)).map(|i| t.dist[i]), d);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn bidirectional_test() {
+        let mut rd = Random::new(5);
+        for _ in 0..300 {
+            let n = rd.next_bounds(1, 30) as usize;
+            let mut adj = vec![vec![]; n];
+            let mut radj = vec![vec![]; n];
+            for _ in 0..rd.next(3 * n as u64) {
+                let (u, v) = (rd.next(n as u64) as usize, rd.next(n as u64) as usize);
+                adj[u].push(v);
+                radj[v].push(u);
+            }
+            let (s, g) = (rd.next(n as u64) as usize, rd.next(n as u64) as usize);
+            let t = bfs(
+                vec![s],
+                Dense::new(n, |&u: &usize| u),
+                |&u, buf| buf.extend(&adj[u]),
+                |&u| u == g,
+            );
+            let path = bidirectional_bfs(
+                s,
+                g,
+                Dense::new(n, |&u: &usize| u),
+                |&u, buf| buf.extend(&adj[u]),
+                |&u, buf| buf.extend(&radj[u]),
+            );
+            assert_eq!(
+                path.as_ref().map(|p| p.len() as u64 - 1),
+                t.goal.map(|i| t.dist[i])
+            );
+            if let Some(p) = path {
+                assert_eq!((p[0], p[p.len() - 1]), (s, g));
+                assert!(p.windows(2).all(|e| adj[e[0]].contains(&e[1])));
+            }
+        }
+        let path = bidirectional_bfs(4, 4, Hashed::new(|&u: &u32| u), |_, _| {}, |_, _| {});
+        assert_eq!(path, Some(vec![4]));
+    }
+
+    #[test]
+    fn astar_test() {
+        let mut rd = Random::new(9);
+        for _ in 0..100 {
+            let (h, w) = (
+                rd.next_bounds(1, 10) as usize,
+                rd.next_bounds(1, 10) as usize,
+            );
+            let (mut free, cost) = grid(&mut rd, h, w);
+            let goal = (rd.next(h as u64) as usize, rd.next(w as u64) as usize);
+            free[0][0] = true;
+            free[goal.0][goal.1] = true;
+            let unit = brute(&free, None, (0, 0))[goal.0][goal.1];
+            let weighted = brute(&free, Some(&cost), (0, 0))[goal.0][goal.1];
+            // admissible and consistent since every step costs at least 1
+            let manhattan = |&(r, c): &Cell| (r.abs_diff(goal.0) + c.abs_diff(goal.1)) as u64;
+            for (consistent, tie) in [(true, TieBreak::Deeper), (false, TieBreak::Shallower)] {
+                let t = astar(
+                    vec![(0, 0)],
+                    Hashed::new(|&u: &Cell| u),
+                    |&u, buf| buf.extend(moves(&free, u).into_iter().map(|v| (v, cost[v.0][v.1]))),
+                    manhattan,
+                    |&u| u == goal,
+                    consistent,
+                    tie,
+                );
+                assert_eq!(t.goal.map(|g| t.dist[g]), weighted);
+                if let Some(path) = t.goal_path() {
+                    check_path(&free, &path, (0, 0), goal);
+                }
+            }
+
+            let t = bfs(
+                vec![(0, 0)],
+                Hashed::new(|&u: &Cell| u),
+                |&u, buf| buf.extend(moves(&free, u)),
+                |&u| u == goal,
+            );
+            assert_eq!(t.goal.map(|g| t.dist[g]), unit);
+            let t = dijkstra(
+                vec![(0, 0)],
+                Hashed::new(|&u: &Cell| u),
+                |&u, buf| buf.extend(moves(&free, u).into_iter().map(|v| (v, 1u64))),
+                |&u| u == goal,
+            );
+            assert_eq!(t.goal.map(|g| t.dist[g]), unit);
+            let path = bidirectional_bfs(
+                (0, 0),
+                goal,
+                Hashed::new(|&u: &Cell| u),
+                |&u, buf| buf.extend(moves(&free, u)),
+                |&u, buf| buf.extend(moves(&free, u)),
+            );
+            assert_eq!(path.map(|p| p.len() as u64 - 1), unit);
+            let res = ida_star(
+                (0, 0),
+                |&u, buf| buf.extend(moves(&free, u).into_iter().map(|v| (v, 1u64))),
+                manhattan,
+                |&u| u == goal,
+                BestCost::new(|&u: &Cell| u),
+                &mut Budget::unlimited(),
+            );
+            match res {
+                IdaResult::Found(path, d) => {
+                    check_path(&free, &path, (0, 0), goal);
+                    assert_eq!(Some(d), unit);
+                }
+                IdaResult::NotFound => assert_eq!(unit, None),
+                IdaResult::OutOfBudget(_) => unreachable!(),
+            }
+        }
+
+        // 1 is discovered and improved while 0 is expanded
+        let t = astar(
+            vec![0usize],
+            Dense::new(3, |&u: &usize| u),
+            |&u, buf| match u {
+                0 => buf.extend([(1, 5), (1, 3)]),
+                1 => buf.push((2, 1)),
+                _ => {}
+            },
+            |_| 0u64,
+            |&u| u == 2,
+            true,
+            TieBreak::Deeper,
+        );
+        assert_eq!(t.goal_path(), Some(vec![0, 1, 2]));
+        assert_eq!(t.dist[t.goal.unwrap()], 4);
+    }
+
+    #[test]
+    fn ida_test() {
+        let mut rd = Random::new(13);
+        for _ in 0..300 {
+            // a DAG, so that IDA* also terminates when the goal can't be reached
+            let n = rd.next_bounds(1, 9) as usize;
+            let mut adj = vec![vec![]; n];
+            for _ in 0..rd.next(2 * n as u64) {
+                let (u, v) = (rd.next(n as u64) as usize, rd.next(n as u64) as usize);
+                if u < v {
+                    adj[u].push((v, rd.next(6)));
+                }
+            }
+            let g = n - 1;
+            let dist: Vec<Option<u64>> = (0..n)
+                .map(|s| {
+                    let t = dijkstra(
+                        vec![s],
+                        Dense::new(n, |&u: &usize| u),
+                        |&u, buf| buf.extend(&adj[u]),
+                        |&u| u == g,
+                    );
+                    t.goal.map(|i| t.dist[i])
+                })
+                .collect();
+            // half the true distance is admissible
+            let h = |&u: &usize| dist[u].map_or(0, |d| d / 2);
+            let check = |res: IdaResult<usize, u64>| match res {
+                IdaResult::Found(path, d) => {
+                    assert_eq!((path[0], path[path.len() - 1]), (0, g));
+                    let w: u64 = path
+                        .windows(2)
+                        .map(|e| {
+                            adj[e[0]]
+                                .iter()
+                                .filter(|x| x.0 == e[1])
+                                .map(|x| x.1)
+                                .min()
+                                .unwrap()
+                        })
+                        .sum();
+                    assert_eq!((Some(d), w), (dist[0], d));
+                }
+                IdaResult::NotFound => assert_eq!(dist[0], None),
+                IdaResult::OutOfBudget(_) => unreachable!(),
+            };
+            let neighbors = |&u: &usize, buf: &mut Vec<(usize, u64)>| buf.extend(&adj[u]);
+            let mut budget = Budget::unlimited();
+            check(ida_star(0, neighbors, h, |&u| u == g, (), &mut budget));
+            let table = BestCost::new(|&u: &usize| u);
+            check(ida_star(
+                0,
+                neighbors,
+                |_| 0,
+                |&u| u == g,
+                table,
+                &mut budget,
+            ));
+        }
+
+        for _ in 0..100 {
+            // cycles are fine once the goal is reachable
+            let n = rd.next_bounds(1, 12) as usize;
+            let mut adj = vec![vec![]; n];
+            for _ in 0..rd.next(3 * n as u64) {
+                adj[rd.next(n as u64) as usize].push(rd.next(n as u64) as usize);
+            }
+            let g = rd.next(n as u64) as usize;
+            let t = bfs(
+                vec![0],
+                Dense::new(n, |&u: &usize| u),
+                |&u, buf| buf.extend(&adj[u]),
+                |&u| u == g,
+            );
+            let Some(d) = t.goal.map(|i| t.dist[i]) else {
+                continue;
+            };
+            let res = iterative_deepening(
+                0,
+                |&u, buf| buf.extend(&adj[u]),
+                |&u| u == g,
+                BestCost::new(|&u: &usize| u),
+                &mut Budget::unlimited(),
+            );
+            let IdaResult::Found(path, cost) = res else {
+                panic!()
+            };
+            assert_eq!((cost, path.len() as u64 - 1), (d, d));
+        }
+
+        // bounds 0, 1, 2, 3 expand 1 + 2 + 3 + 4 nodes, then bound 4 runs out
+        let mut budget = Budget::new(10);
+        let res = iterative_deepening(
+            0u64,
+            |&u, buf| buf.push(u + 1),
+            |&u| u == 100,
+            (),
+            &mut budget,
+        );
+        assert_eq!(res, IdaResult::OutOfBudget(4));
+        assert_eq!(budget.nodes(), 10);
+    }
+
+    #[test]
+    fn beam_test() {
+        // returns the result and the states expanded, in order
+        let run = |width, depth, budget: &mut Budget, children: fn(u32) -> Vec<(u32, i64)>| {
+            let mut expanded = vec![];
+            let res = beam_search(
+                0u32,
+                width,
+                depth,
+                |&s, buf| {
+                    expanded.push(s);
+                    buf.extend(children(s));
+                },
+                |&s| s % 10,
+                budget,
+            );
+            (res, expanded)
+        };
+        let unlimited = &mut Budget::unlimited();
+
+        // 1, 11 and 21 share a key, only 11 with the best score is kept
+        let dup = |s| match s {
+            0 => vec![(1, 1), (11, 7), (21, 4), (2, 3)],
+            _ => vec![],
+        };
+        assert_eq!(run(5, 2, unlimited, dup), (Some((11, 7)), vec![0, 11, 2]));
+
+        // the 2 best of 1..=6 form the next layer
+        let fan = |s| match s {
+            0 => (1..=6).map(|c| (c, c as i64)).collect(),
+            _ => vec![],
+        };
+        let (res, mut expanded) = run(2, 2, unlimited, fan);
+        expanded.sort();
+        assert_eq!((res, expanded), (Some((6, 6)), vec![0, 5, 6]));
+        assert_eq!(run(0, 3, unlimited, fan), (None, vec![0]));
+
+        // a chain 0 -> 1 -> 2 -> ... scoring each state by itself
+        let chain = |s| vec![(s + 1, s as i64 + 1)];
+        assert_eq!(run(3, 3, unlimited, chain), (Some((3, 3)), vec![0, 1, 2]));
+        assert_eq!(run(3, 0, unlimited, chain), (None, vec![]));
+        let mut budget = Budget::new(2);
+        assert_eq!(run(3, 5, &mut budget, chain), (Some((2, 2)), vec![0, 1]));
+        assert_eq!(budget.nodes(), 2);
+    }
+}
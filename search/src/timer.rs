@@ -0,0 +1,78 @@
+use std::time::{Duration, Instant};
+
+/// wall clock deadline for anytime searches
+#[derive(Clone, Copy, Debug)]
+pub struct Timer {
+    start: Instant,
+    limit: Duration,
+}
+
+impl Timer {
+    /// starts now, runs out after limit_ms milliseconds
+    pub fn new(limit_ms: u64) -> Self {
+        Self {
+            start: Instant::now(),
+            limit: Duration::from_millis(limit_ms),
+        }
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    pub fn elapsed_ms(&self) -> u64 {
+        self.elapsed().as_millis() as u64
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.elapsed() >= self.limit
+    }
+
+    /// fraction of the limit used, 0.0 at start and 1.0 at the deadline
+    pub fn progress(&self) -> f64 {
+        self.elapsed().as_secs_f64() / self.limit.as_secs_f64()
+    }
+}
+
+/// limit on the number of expanded nodes, optionally also on time \
+/// the timer is only polled every 1024 nodes
+#[derive(Clone, Debug)]
+pub struct Budget {
+    nodes: u64,
+    limit: u64,
+    timer: Option<Timer>,
+}
+
+impl Budget {
+    /// at most limit nodes, u64::MAX for no limit
+    pub fn new(limit: u64) -> Self {
+        Self {
+            nodes: 0,
+            limit,
+            timer: None,
+        }
+    }
+
+    pub fn unlimited() -> Self {
+        Self::new(u64::MAX)
+    }
+
+    pub fn with_timer(mut self, timer: Timer) -> Self {
+        self.timer = Some(timer);
+        self
+    }
+
+    /// nodes spent so far
+    pub fn nodes(&self) -> u64 {
+        self.nodes
+    }
+
+    /// spend one node, false once the budget is exhausted
+    pub fn spend(&mut self) -> bool {
+        if self.nodes >= self.limit {
+            return false;
+        }
+        self.nodes += 1;
+        !(self.nodes.is_multiple_of(1024) && self.timer.is_some_and(|t| t.is_over()))
+    }
+}
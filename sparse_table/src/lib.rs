@@ -6,7 +6,10 @@ pub trait Repeatable {
 use std::cmp::{max, min};
 use std::convert::Infallible;
 use std::marker::PhantomData;
-use std::ops::{BitAnd, BitOr};
+use std::ops::{BitAnd, BitOr, RangeBounds};
+
+use number_theory::BinaryGcd;
+use range_query::half_open;
 
 pub struct Max<S>(Infallible, PhantomData<fn() -> S>);
 impl<S> Repeatable for Max<S>
@@ -52,18 +55,27 @@ where
     }
 }
 
+/// any primitive integer, signed ones give the gcd of absolute values
 pub struct Gcd<S>(Infallible, PhantomData<fn() -> S>);
 impl<S> Repeatable for Gcd<S>
 where
-    S: Copy + Ord + std::ops::Rem<Output = S> + std::cmp::PartialEq<i32>,
+    S: BinaryGcd,
 {
     type S = S;
     fn operation(a: Self::S, b: Self::S) -> Self::S {
-        if b == 0 {
-            a
-        } else {
-            Self::operation(b, a % b)
-        }
+        a.gcd(b)
+    }
+}
+
+/// values are Some(x), None once the lcm overflows S and for every range containing it
+pub struct Lcm<S>(Infallible, PhantomData<fn() -> S>);
+impl<S> Repeatable for Lcm<S>
+where
+    S: BinaryGcd,
+{
+    type S = Option<S>;
+    fn operation(a: Self::S, b: Self::S) -> Self::S {
+        a?.checked_lcm(b?)
     }
 }
 
@@ -99,9 +111,55 @@ where
         Self { st }
     }
 
-    pub fn query(&self, l: usize, r: usize) -> T::S {
+    /// None if a is empty
+    pub fn try_new(a: Vec<T::S>) -> Option<Self> {
+        (!a.is_empty()).then(|| Self::new(a))
+    }
+
+    /// None if range is empty or goes past n
+    pub fn try_query<R: RangeBounds<usize>>(&self, range: R) -> Option<T::S> {
+        let (l, r) = half_open(range, 0, self.st[0].len());
+        (l < r && r <= self.st[0].len()).then(|| self.query_inclusive(l, r - 1))
+    }
+
+    /// range must be nonempty, `st.query(l..=r)` or `st.query(..)`
+    pub fn query<R: RangeBounds<usize>>(&self, range: R) -> T::S {
+        let (l, r) = half_open(range, 0, self.st[0].len());
+        assert!(l < r, "empty range!");
+        self.query_inclusive(l, r - 1)
+    }
+
+    /// both included
+    pub fn query_inclusive(&self, l: usize, r: usize) -> T::S {
         let len = r + 1 - l;
         let pw = ilog2(len);
         T::operation(self.st[pw][l], self.st[pw][r + 1 - (1 << pw)])
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{Gcd, Lcm, SparseTable};
+
+    #[test]
+    fn gcd_lcm_test() {
+        let a = vec![12u64, 18, 30, 7, 1 << 40, 3 << 40, 1_000_000_007];
+        let g = SparseTable::<Gcd<u64>>::new(a.clone());
+        assert_eq!(
+            (g.query(0..=2), g.query(..4), g.query_inclusive(4, 5)),
+            (6, 1, 1 << 40)
+        );
+        let l = SparseTable::<Lcm<u64>>::new(a.into_iter().map(Some).collect());
+        assert_eq!((l.query(0..=3), l.query(4..6)), (Some(1260), Some(3 << 40)));
+        assert_eq!(l.query(2..), None);
+        let g = SparseTable::<Gcd<i32>>::new(vec![-4, 6, -8]);
+        assert_eq!(g.query(..), 2);
+        assert_eq!((g.try_query(1..=2), g.try_query(1..=3)), (Some(2), None));
+        assert_eq!(g.try_query(2..2), None);
+        assert_eq!(
+            (g.try_query(..0), g.try_query(3..), g.try_query(..)),
+            (None, None, Some(2))
+        );
+        assert!(SparseTable::<Gcd<i32>>::try_new(vec![]).is_none());
+    }
+}
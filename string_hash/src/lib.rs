@@ -1,3 +1,7 @@
+use std::ops::RangeBounds;
+
+use range_query::half_open;
+
 #[allow(dead_code)]
 pub struct StringHash {
     h: Vec<Vec<u32>>,
@@ -8,7 +12,7 @@ pub struct StringHash {
 }
 
 impl StringHash {
-    pub fn new(s: &Vec<u8>, base: usize, p: Vec<u32>) -> Self {
+    pub fn new(s: &[u8], base: usize, p: Vec<u32>) -> Self {
         let n = s.len();
         let way = p.len();
         let mut h = vec![vec![0u32; n + 1]; way];
@@ -29,13 +33,49 @@ impl StringHash {
         }
     }
 
+    /// hash of s[range], `h.query(l..=r)` is `h.get(l, r)`
+    pub fn query<R: RangeBounds<usize>>(&self, range: R) -> Vec<u32> {
+        let n = self.h.first().map_or(0, |h| h.len() - 1);
+        let (l, r) = half_open(range, 0, n);
+        if l >= r {
+            return vec![0; self.way];
+        }
+        self.get(l, r - 1)
+    }
+
     // both included, 0-indexed
     pub fn get(&self, l: usize, r: usize) -> Vec<u32> {
         let mut ans = vec![0; self.way];
-        for w in 0..self.way {
+        for (w, a) in ans.iter_mut().enumerate() {
             let t = (self.h[w][l] as u64 * self.pw[w][r - l + 1] as u64 % self.p[w] as u64) as u32;
-            ans[w] = (self.h[w][r + 1] + self.p[w] - t) % self.p[w];
+            *a = (self.h[w][r + 1] + self.p[w] - t) % self.p[w];
         }
         ans
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::StringHash;
+
+    #[test]
+    fn query_test() {
+        let s = b"abracadabra";
+        let n = s.len();
+        let h = StringHash::new(s, 131, vec![1_000_000_007, 998_244_353]);
+        for l in 0..n {
+            for r in l..n {
+                assert_eq!(h.query(l..=r), h.get(l, r));
+                assert_eq!(h.query(l..r + 1), h.get(l, r));
+            }
+            assert_eq!(h.query(l..), h.get(l, n - 1));
+            assert_eq!(h.query(..=l), h.get(0, l));
+        }
+        assert_eq!(h.query(..), h.get(0, n - 1));
+        assert_eq!(h.query(..4), h.query(7..));
+        assert_ne!(h.query(..4), h.query(1..5));
+        for empty in [h.query(..0), h.query(3..3), h.query(n..)] {
+            assert_eq!(empty, [0, 0]);
+        }
+    }
+}
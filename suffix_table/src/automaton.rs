@@ -0,0 +1,245 @@
+use std::collections::BTreeMap;
+
+/// link-cut forest over the suffix links, val\[x\] is the size of the subtree of x \
+/// node 0 is the null node, state s is node s + 1
+struct LinkCut {
+    ch: Vec<[usize; 2]>,
+    fa: Vec<usize>, // splay parent, or path parent for a splay root
+    val: Vec<u64>,
+    tag: Vec<i64>, // pending add for the splay subtree below
+}
+
+impl LinkCut {
+    fn new() -> Self {
+        Self {
+            ch: vec![[0, 0]],
+            fa: vec![0],
+            val: vec![0],
+            tag: vec![0],
+        }
+    }
+
+    fn add_node(&mut self) -> usize {
+        self.ch.push([0, 0]);
+        self.fa.push(0);
+        self.val.push(0);
+        self.tag.push(0);
+        self.ch.len() - 1
+    }
+
+    fn is_root(&self, x: usize) -> bool {
+        let f = self.fa[x];
+        self.ch[f][0] != x && self.ch[f][1] != x
+    }
+
+    fn apply(&mut self, x: usize, v: i64) {
+        if x != 0 {
+            self.val[x] = self.val[x].wrapping_add_signed(v);
+            self.tag[x] += v;
+        }
+    }
+
+    fn push(&mut self, x: usize) {
+        if self.tag[x] != 0 {
+            let [l, r] = self.ch[x];
+            self.apply(l, self.tag[x]);
+            self.apply(r, self.tag[x]);
+            self.tag[x] = 0;
+        }
+    }
+
+    fn rotate(&mut self, x: usize) {
+        let (y, z) = (self.fa[x], self.fa[self.fa[x]]);
+        let k = (self.ch[y][1] == x) as usize;
+        if !self.is_root(y) {
+            let i = (self.ch[z][1] == y) as usize;
+            self.ch[z][i] = x;
+        }
+        self.fa[x] = z;
+        let w = self.ch[x][k ^ 1];
+        self.ch[y][k] = w;
+        if w != 0 {
+            self.fa[w] = y;
+        }
+        self.ch[x][k ^ 1] = y;
+        self.fa[y] = x;
+    }
+
+    fn splay(&mut self, x: usize) {
+        let mut path = vec![x];
+        let mut y = x;
+        while !self.is_root(y) {
+            y = self.fa[y];
+            path.push(y);
+        }
+        while let Some(y) = path.pop() {
+            self.push(y);
+        }
+        while !self.is_root(x) {
+            let (y, z) = (self.fa[x], self.fa[self.fa[x]]);
+            if !self.is_root(y) {
+                if (self.ch[y][0] == x) ^ (self.ch[z][0] == y) {
+                    self.rotate(x);
+                } else {
+                    self.rotate(y);
+                }
+            }
+            self.rotate(x);
+        }
+    }
+
+    // make the root to x path preferred, x ends as the root of its splay tree
+    fn access(&mut self, x: usize) {
+        let (mut last, mut y) = (0, x);
+        while y != 0 {
+            self.splay(y);
+            self.ch[y][1] = last;
+            last = y;
+            y = self.fa[y];
+        }
+        self.splay(x);
+    }
+
+    fn get(&mut self, x: usize) -> u64 {
+        self.access(x);
+        self.val[x]
+    }
+
+    // add v to x and all its ancestors
+    fn add_to_root(&mut self, x: usize, v: i64) {
+        self.access(x);
+        self.apply(x, v);
+    }
+
+    // x is a tree root
+    fn link(&mut self, x: usize, p: usize) {
+        self.access(x);
+        self.fa[x] = p;
+        let v = self.val[x] as i64;
+        self.add_to_root(p, v);
+    }
+
+    // detach x from its parent p
+    fn cut(&mut self, x: usize, p: usize) {
+        self.access(x);
+        let l = self.ch[x][0];
+        self.fa[l] = 0;
+        self.ch[x][0] = 0;
+        let v = self.val[x] as i64;
+        self.add_to_root(p, -v);
+    }
+}
+
+/// suffix automaton of a growing byte string, push is amortized O(log n) \
+/// occurrence counts are kept online on the suffix link tree with a link-cut tree,
+/// so appends and count queries can be interleaved freely
+pub struct SuffixAutomaton {
+    next: Vec<BTreeMap<u8, usize>>,
+    link: Vec<usize>, // usize::MAX for the initial state
+    len: Vec<usize>,
+    last: usize,
+    lct: LinkCut,
+}
+
+impl Default for SuffixAutomaton {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SuffixAutomaton {
+    pub fn new() -> Self {
+        let mut lct = LinkCut::new();
+        lct.add_node();
+        Self {
+            next: vec![BTreeMap::new()],
+            link: vec![usize::MAX],
+            len: vec![0],
+            last: 0,
+            lct,
+        }
+    }
+
+    fn new_state(&mut self, len: usize) -> usize {
+        self.next.push(BTreeMap::new());
+        self.link.push(usize::MAX);
+        self.len.push(len);
+        self.lct.add_node();
+        self.len.len() - 1
+    }
+
+    fn set_link(&mut self, s: usize, t: usize) {
+        if self.link[s] != usize::MAX {
+            self.lct.cut(s + 1, self.link[s] + 1);
+        }
+        self.link[s] = t;
+        self.lct.link(s + 1, t + 1);
+    }
+
+    /// append byte c
+    pub fn push(&mut self, c: u8) {
+        let cur = self.new_state(self.len[self.last] + 1);
+        // cur is the end of exactly one new position
+        self.lct.add_to_root(cur + 1, 1);
+        let mut p = self.last;
+        self.last = cur;
+        while p != usize::MAX && !self.next[p].contains_key(&c) {
+            self.next[p].insert(c, cur);
+            p = self.link[p];
+        }
+        if p == usize::MAX {
+            self.set_link(cur, 0);
+            return;
+        }
+        let q = self.next[p][&c];
+        if self.len[p] + 1 == self.len[q] {
+            self.set_link(cur, q);
+            return;
+        }
+        let clone = self.new_state(self.len[p] + 1);
+        self.next[clone] = self.next[q].clone();
+        self.set_link(clone, self.link[q]);
+        self.set_link(q, clone);
+        self.set_link(cur, clone);
+        while p != usize::MAX && self.next[p].get(&c) == Some(&q) {
+            self.next[p].insert(c, clone);
+            p = self.link[p];
+        }
+    }
+
+    pub fn extend(&mut self, s: &str) {
+        for &c in s.as_bytes() {
+            self.push(c);
+        }
+    }
+
+    /// number of bytes pushed so far
+    pub fn len(&self) -> usize {
+        self.len[self.last]
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn walk(&self, query: &str) -> Option<usize> {
+        let mut s = 0;
+        for c in query.as_bytes() {
+            s = *self.next[s].get(c)?;
+        }
+        Some(s)
+    }
+
+    /// whether query is a substring, O(|query| log sigma)
+    pub fn contains(&self, query: &str) -> bool {
+        self.walk(query).is_some()
+    }
+
+    /// number of occurrences of a nonempty query, overlapping ones included
+    pub fn count(&mut self, query: &str) -> usize {
+        match self.walk(query) {
+            Some(s) if s != 0 => self.lct.get(s + 1) as usize,
+            _ => 0,
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use crate::SuffixTable;
+
+/// bits with a rank directory, rank1(i) is the number of ones in [0, i)
+struct RankBits {
+    bits: Vec<u64>,
+    ranks: Vec<u32>, // ones before each word
+}
+
+impl RankBits {
+    fn new(b: &[bool]) -> Self {
+        let mut bits = vec![0u64; b.len() / 64 + 1];
+        for (i, _) in b.iter().enumerate().filter(|e| *e.1) {
+            bits[i / 64] |= 1 << (i % 64);
+        }
+        let mut ranks = Vec::with_capacity(bits.len());
+        let mut sum = 0;
+        for w in &bits {
+            ranks.push(sum);
+            sum += w.count_ones();
+        }
+        Self { bits, ranks }
+    }
+
+    fn rank1(&self, i: usize) -> usize {
+        let mask = (1u64 << (i % 64)) - 1;
+        self.ranks[i / 64] as usize + (self.bits[i / 64] & mask).count_ones() as usize
+    }
+}
+
+/// FM-index of a text: its Burrows-Wheeler transform in a wavelet matrix \
+/// 8 levels of a bit plus a u32 rank per 64 bits, about 1.5 bytes per text byte,
+/// count(pattern) in O(|pattern| * 8)
+pub struct FmIndex {
+    levels: Vec<RankBits>, // highest bit first
+    zeros: Vec<usize>,
+    c: Vec<usize>,   // c[x] = 1 + number of text bytes less than x
+    sentinel: usize, // the row whose bwt char is the sentinel, stored as 0
+    n: usize,
+}
+
+impl FmIndex {
+    pub fn new(text: &str) -> Self {
+        Self::from_table(&SuffixTable::new(text))
+    }
+
+    pub fn from_table(st: &SuffixTable) -> Self {
+        let (text, sa) = (st.text().as_bytes(), st.table());
+        let n = text.len();
+        // rows are the suffixes in order with the empty one first
+        let mut bwt = Vec::with_capacity(n + 1);
+        bwt.push(text.last().copied().unwrap_or(0));
+        let mut sentinel = 0;
+        for (r, &i) in sa.iter().enumerate() {
+            if i == 0 {
+                sentinel = r + 1;
+                bwt.push(0);
+            } else {
+                bwt.push(text[i as usize - 1]);
+            }
+        }
+        let mut c = vec![0; 257];
+        for &x in text {
+            c[x as usize + 1] += 1;
+        }
+        c[0] = 1;
+        for x in 0..256 {
+            c[x + 1] += c[x];
+        }
+        let (mut levels, mut zeros) = (vec![], vec![]);
+        for bit in (0..8).rev() {
+            let b: Vec<bool> = bwt.iter().map(|&x| x >> bit & 1 == 1).collect();
+            levels.push(RankBits::new(&b));
+            // stable partition, zeros first
+            let (lo, hi): (Vec<u8>, Vec<u8>) = bwt.iter().partition(|&&x| x >> bit & 1 == 0);
+            zeros.push(lo.len());
+            bwt = lo;
+            bwt.extend(hi);
+        }
+        Self {
+            levels,
+            zeros,
+            c,
+            sentinel,
+            n,
+        }
+    }
+
+    /// occurrences of x among the first i bwt chars
+    fn rank(&self, x: u8, i: usize) -> usize {
+        let (mut s, mut p) = (0, i);
+        for (k, lv) in self.levels.iter().enumerate() {
+            if x >> (7 - k) & 1 == 1 {
+                s = self.zeros[k] + lv.rank1(s);
+                p = self.zeros[k] + lv.rank1(p);
+            } else {
+                s -= lv.rank1(s);
+                p -= lv.rank1(p);
+            }
+        }
+        p - s - (x == 0 && self.sentinel < i) as usize
+    }
+
+    /// number of occurrences of pattern in the text, overlapping ones included
+    pub fn count(&self, pattern: &str) -> usize {
+        let (mut s, mut e) = (0, self.n + 1);
+        for &x in pattern.as_bytes().iter().rev() {
+            s = self.c[x as usize] + self.rank(x, s);
+            e = self.c[x as usize] + self.rank(x, e);
+            if s >= e {
+                return 0;
+            }
+        }
+        e - s
+    }
+}
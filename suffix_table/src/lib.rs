@@ -2,10 +2,18 @@ use std::borrow::Cow;
 use std::fmt;
 use std::iter;
 use std::slice;
-use std::u32;
+
+use arena::ScratchVec;
 
 use self::SuffixType::{Ascending, Descending, Valley};
 
+mod automaton;
+mod fm_index;
+#[cfg(feature = "validate")]
+mod validate;
+pub use automaton::SuffixAutomaton;
+pub use fm_index::FmIndex;
+
 #[derive(Clone, Eq, PartialEq)]
 pub struct SuffixTable<'s, 't> {
     text: Cow<'s, str>,
@@ -26,11 +34,24 @@ impl<'s, 't> SuffixTable<'s, 't> {
         let text = text.into();
         let table = Cow::Owned(sais_table(&text));
         SuffixTable {
-            text: text,
-            table: table,
+            text,
+            table,
         }
     }
 
+    /// Like `new`, but returns `None` instead of panicking when `text` is
+    /// too long for 32 bit suffix indices.
+    pub fn try_new<S>(text: S) -> Option<SuffixTable<'s, 't>>
+    where
+        S: Into<Cow<'s, str>>,
+    {
+        let text = text.into();
+        if text.len() > u32::MAX as usize {
+            return None;
+        }
+        Some(SuffixTable::new(text))
+    }
+
     /// Creates a new suffix table from an existing list of lexicographically
     /// sorted suffix indices.
     ///
@@ -48,9 +69,26 @@ impl<'s, 't> SuffixTable<'s, 't> {
         let (text, table) = (text.into(), table.into());
         assert_eq!(text.len(), table.len());
         SuffixTable {
-            text: text,
-            table: table,
+            text,
+            table,
+        }
+    }
+
+    /// Like `from_parts`, but returns `None` instead of panicking when the
+    /// lengths of `text` and `table` differ.
+    pub fn try_from_parts<S, T>(text: S, table: T) -> Option<SuffixTable<'s, 't>>
+    where
+        S: Into<Cow<'s, str>>,
+        T: Into<Cow<'t, [u32]>>,
+    {
+        let (text, table) = (text.into(), table.into());
+        if text.len() != table.len() {
+            return None;
         }
+        Some(SuffixTable {
+            text,
+            table,
+        })
     }
 
     /// Extract the parts of a suffix table.
@@ -124,7 +162,7 @@ impl<'s, 't> SuffixTable<'s, 't> {
     /// Build a suffix array of some text and test existence of a substring:
     ///
     /// ```rust
-    /// use suffix::SuffixTable;
+    /// use suffix_table::SuffixTable;
     ///
     /// let sa = SuffixTable::new("The quick brown fox.");
     /// assert!(sa.contains("quick"));
@@ -150,7 +188,7 @@ impl<'s, 't> SuffixTable<'s, 't> {
     /// substring:
     ///
     /// ```rust
-    /// use suffix::SuffixTable;
+    /// use suffix_table::SuffixTable;
     ///
     /// let sa = SuffixTable::new("The quick brown fox was very quick.");
     /// assert_eq!(sa.positions("quick"), &[4, 29]);
@@ -160,8 +198,8 @@ impl<'s, 't> SuffixTable<'s, 't> {
 
         // We can quickly decide whether the query won't match at all if
         // it's outside the range of suffixes.
-        if text.len() == 0
-            || query.len() == 0
+        if text.is_empty()
+            || query.is_empty()
             || (query < self.suffix_bytes(0) && !self.suffix_bytes(0).starts_with(query))
             || query > self.suffix_bytes(self.len() - 1)
         {
@@ -202,7 +240,7 @@ impl<'s, 't> SuffixTable<'s, 't> {
     /// # Example
     ///
     /// ```
-    /// use suffix::SuffixTable;
+    /// use suffix_table::SuffixTable;
     ///
     /// let sa = SuffixTable::new("The quick brown fox was very quick.");
     /// let position = sa.any_position("quick");
@@ -210,7 +248,7 @@ impl<'s, 't> SuffixTable<'s, 't> {
     /// ```
     pub fn any_position(&self, query: &str) -> Option<u32> {
         let (text, query) = (self.text.as_bytes(), query.as_bytes());
-        if query.len() == 0 {
+        if query.is_empty() {
             return None;
         }
         self.table
@@ -292,13 +330,13 @@ fn lcp_len(a: &[u8], b: &[u8]) -> u32 {
         .count() as u32
 }
 
-fn sais_table<'s>(text: &'s str) -> Vec<u32> {
+fn sais_table(text: &str) -> Vec<u32> {
     let text = text.as_bytes();
     assert!(text.len() <= u32::MAX as usize);
     let mut sa = vec![0u32; text.len()];
     let mut stypes = SuffixTypes::new(text.len() as u32);
     let mut bins = Bins::new();
-    sais(&mut *sa, &mut stypes, &mut bins, &Utf8(text));
+    sais(&mut sa, &mut stypes, &mut bins, &Utf8(text));
     sa
 }
 
@@ -496,7 +534,7 @@ where
 }
 
 struct SuffixTypes {
-    types: Vec<SuffixType>,
+    types: ScratchVec<SuffixType>,
 }
 
 #[derive(Clone, Copy, Debug, Eq)]
@@ -509,11 +547,11 @@ enum SuffixType {
 impl SuffixTypes {
     fn new(num_bytes: u32) -> SuffixTypes {
         SuffixTypes {
-            types: vec![SuffixType::Ascending; num_bytes as usize],
+            types: ScratchVec::from_elem(SuffixType::Ascending, num_bytes as usize),
         }
     }
 
-    fn compute<'a, T>(&mut self, text: &T)
+    fn compute<T>(&mut self, text: &T)
     where
         T: Text,
         <<T as Text>::IdxChars as Iterator>::Item: IdxChar,
@@ -565,28 +603,17 @@ impl SuffixTypes {
 impl SuffixType {
     #[inline]
     fn is_asc(&self) -> bool {
-        match *self {
-            Ascending | Valley => true,
-            _ => false,
-        }
+        matches!(*self, Ascending | Valley)
     }
 
     #[inline]
     fn is_desc(&self) -> bool {
-        if let Descending = *self {
-            true
-        } else {
-            false
-        }
+        matches!(*self, Descending)
     }
 
     #[inline]
     fn is_valley(&self) -> bool {
-        if let Valley = *self {
-            true
-        } else {
-            false
-        }
+        matches!(*self, Valley)
     }
 
     fn inherit(&self) -> SuffixType {
@@ -604,18 +631,19 @@ impl PartialEq for SuffixType {
     }
 }
 
+// the buffers come from the scratch pool, building many tables reuses them
 struct Bins {
-    alphas: Vec<u32>,
-    sizes: Vec<u32>,
-    ptrs: Vec<u32>,
+    alphas: ScratchVec<u32>,
+    sizes: ScratchVec<u32>,
+    ptrs: ScratchVec<u32>,
 }
 
 impl Bins {
     fn new() -> Bins {
         Bins {
-            alphas: Vec::with_capacity(10_000),
-            sizes: Vec::with_capacity(10_000),
-            ptrs: Vec::new(), // re-allocated later, no worries
+            alphas: ScratchVec::with_capacity(10_000),
+            sizes: ScratchVec::with_capacity(10_000),
+            ptrs: ScratchVec::with_capacity(0), // resized later, no worries
         }
     }
 
@@ -636,7 +664,8 @@ impl Bins {
         self.alphas.sort();
 
         let ptrs_len = self.alphas[self.alphas.len() - 1] + 1;
-        self.ptrs = vec![0u32; ptrs_len as usize];
+        self.ptrs.clear();
+        self.ptrs.resize(ptrs_len as usize, 0);
     }
 
     fn find_head_pointers(&mut self) {
@@ -807,14 +836,14 @@ trait IdxChar {
     fn idx_char(self) -> (usize, u32);
 }
 
-impl<'a> IdxChar for (usize, &'a u8) {
+impl IdxChar for (usize, &u8) {
     #[inline]
     fn idx_char(self) -> (usize, u32) {
         (self.0, *self.1 as u32)
     }
 }
 
-impl<'a> IdxChar for (usize, &'a u32) {
+impl IdxChar for (usize, &u32) {
     #[inline]
     fn idx_char(self) -> (usize, u32) {
         (self.0, *self.1)
@@ -848,3 +877,64 @@ where
     }
     left
 }
+
+#[cfg(test)]
+mod test {
+    use random::Random;
+    use test_support::{naive_suffix_array, random_string};
+
+    use super::{FmIndex, SuffixAutomaton, SuffixTable};
+
+    #[test]
+    fn differential_test() {
+        let mut rd = Random::new(1);
+        for _ in 0..500 {
+            let (n, k) = (rd.next(60) as usize, rd.next_bounds(1, 4) as u8);
+            let s = random_string(&mut rd, n, k);
+            let st = SuffixTable::new(s.as_str());
+            #[cfg(feature = "validate")]
+            st.validate();
+            assert_eq!(st.table(), naive_suffix_array(&s));
+            let m = rd.next_bounds(1, 3) as usize;
+            let q = random_string(&mut rd, m, k);
+            let pos: Vec<u32> = (0..n as u32)
+                .filter(|&i| s[i as usize..].starts_with(q.as_str()))
+                .collect();
+            let mut got = st.positions(&q).to_vec();
+            got.sort();
+            assert_eq!(got, pos);
+            assert_eq!(st.contains(&q), !pos.is_empty());
+            assert_eq!(FmIndex::from_table(&st).count(&q), pos.len());
+            // the same text and query with 'a' turned into byte 0
+            let (z, qz) = (s.replace('a', "\0"), q.replace('a', "\0"));
+            assert_eq!(FmIndex::new(&z).count(&qz), pos.len());
+        }
+        let st = SuffixTable::try_new("banana").unwrap();
+        let table = st.table().to_vec();
+        assert!(SuffixTable::try_from_parts("banana", &table[1..]).is_none());
+        assert!(SuffixTable::try_from_parts("banana", table).is_some());
+    }
+
+    #[test]
+    fn automaton_test() {
+        let mut rd = Random::new(2);
+        for _ in 0..50 {
+            let k = rd.next_bounds(1, 3) as u8;
+            let mut sam = SuffixAutomaton::new();
+            let mut s = String::new();
+            for _ in 0..rd.next(80) {
+                let c = random_string(&mut rd, 1, k);
+                sam.push(c.as_bytes()[0]);
+                s.push_str(&c);
+                assert_eq!(sam.len(), s.len());
+                for _ in 0..3 {
+                    let m = rd.next_bounds(1, 4) as usize;
+                    let q = random_string(&mut rd, m, k);
+                    let cnt = (0..s.len()).filter(|&i| s[i..].starts_with(&q)).count();
+                    assert_eq!(sam.count(&q), cnt);
+                    assert_eq!(sam.contains(&q), cnt > 0);
+                }
+            }
+        }
+    }
+}
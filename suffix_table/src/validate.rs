@@ -0,0 +1,23 @@
+use crate::SuffixTable;
+
+impl SuffixTable<'_, '_> {
+    /// panic unless the table lists every suffix once in increasing order
+    pub fn validate(&self) {
+        let n = self.text().len();
+        assert_eq!(self.len(), n, "table and text lengths differ");
+        let mut seen = vec![false; n];
+        for &i in self.table() {
+            assert!((i as usize) < n, "suffix {} out of range", i);
+            assert!(!seen[i as usize], "suffix {} listed twice", i);
+            seen[i as usize] = true;
+        }
+        for i in 1..n {
+            assert!(
+                self.suffix_bytes(i - 1) < self.suffix_bytes(i),
+                "suffixes {} and {} out of order",
+                i - 1,
+                i
+            );
+        }
+    }
+}
@@ -0,0 +1,48 @@
+/// cycles of an edge cactus, a graph where every edge lies on at most one simple cycle \
+/// adj as in `tarjan_bridge`, edge ids in 0..m \
+/// return the cycle id of every edge, usize::MAX off cycles, None if it isn't a cactus
+pub fn cactus_cycles(adj: &[Vec<(usize, usize)>]) -> Option<Vec<usize>> {
+    let n = adj.len();
+    let m = adj.iter().flatten().map(|&(_, e)| e + 1).max().unwrap_or(0);
+    let mut cyc = vec![usize::MAX; m];
+    let mut dfn = vec![0; n];
+    // parent vertex and edge in the DFS tree
+    let mut fa = vec![(n, usize::MAX); n];
+    let (mut time, mut c) = (0, 0);
+    let mut call = vec![];
+    for i in 0..n {
+        if dfn[i] != 0 {
+            continue;
+        }
+        time += 1;
+        dfn[i] = time;
+        call.push((i, 0));
+        while let Some(&(u, j)) = call.last() {
+            let Some(&(v, e)) = adj[u].get(j) else {
+                call.pop();
+                continue;
+            };
+            call.last_mut().unwrap().1 += 1;
+            if dfn[v] == 0 {
+                time += 1;
+                dfn[v] = time;
+                fa[v] = (u, e);
+                call.push((v, 0));
+            } else if dfn[v] <= dfn[u] && e != fa[u].1 && cyc[e] == usize::MAX {
+                // back edge to an ancestor, the tree path up to v closes a new cycle
+                cyc[e] = c;
+                let mut x = u;
+                while x != v {
+                    let (p, pe) = fa[x];
+                    if cyc[pe] != usize::MAX {
+                        return None;
+                    }
+                    cyc[pe] = c;
+                    x = p;
+                }
+                c += 1;
+            }
+        }
+    }
+    Some(cyc)
+}
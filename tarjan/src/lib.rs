@@ -1,35 +1,272 @@
-use recursive_function::{Callable2, RecursiveFunction2};
+mod cactus;
+mod scc;
 
+pub use cactus::cactus_cycles;
+pub use scc::{condensation, scc};
+
+/// adj\[u\] holds (v, edge id) for every edge u - v, parallel edges and self loops allowed \
 /// return fa, bridge \
-/// bridge\[u\] means edge between u and fa\[u\] is bridge
-pub fn tarjan_bridge(adj: &Vec<Vec<usize>>) -> (Vec<usize>, Vec<bool>) {
+/// bridge\[u\] means edge between u and fa\[u\] is bridge, fa\[root\] = n
+pub fn tarjan_bridge(adj: &[Vec<(usize, usize)>]) -> (Vec<usize>, Vec<bool>) {
     let n = adj.len();
     let mut fa = vec![n; n];
     let mut low = vec![0; n];
     let mut dfn = vec![0; n];
     let mut time = 0;
     let mut bridge = vec![false; n];
+    // (vertex, edge we came by, index of the next edge to visit)
+    let mut call = vec![];
     for i in 0..n {
-        if dfn[i] == 0 {
-            let mut dfs = RecursiveFunction2::new(|sf, u: usize, f: usize| {
-                fa[u] = f;
-                time += 1;
-                dfn[u] = time;
-                low[u] = time;
-                for &v in &adj[u] {
-                    if dfn[v] == 0 {
-                        sf.call(v, u);
-                        low[u] = low[u].min(low[v]);
-                        if low[v] > dfn[u] {
-                            bridge[v] = true;
-                        }
-                    } else if dfn[v] < dfn[u] && v != f {
-                        low[u] = low[u].min(dfn[v]);
-                    }
+        if dfn[i] != 0 {
+            continue;
+        }
+        time += 1;
+        (dfn[i], low[i]) = (time, time);
+        call.push((i, usize::MAX, 0));
+        while let Some(&(u, fe, j)) = call.last() {
+            if let Some(&(v, e)) = adj[u].get(j) {
+                call.last_mut().unwrap().2 += 1;
+                if dfn[v] == 0 {
+                    fa[v] = u;
+                    time += 1;
+                    (dfn[v], low[v]) = (time, time);
+                    call.push((v, e, 0));
+                } else if e != fe {
+                    // skip the edge we came by, not every edge back to the parent
+                    low[u] = low[u].min(dfn[v]);
+                }
+                continue;
+            }
+            call.pop();
+            if let Some(&(p, _, _)) = call.last() {
+                low[p] = low[p].min(low[u]);
+                if low[u] > dfn[p] {
+                    bridge[u] = true;
                 }
-            });
-            dfs.call(i, n);
+            }
         }
     }
     (fa, bridge)
 }
+
+/// contract the 2-edge-connected components, adj as in `tarjan_bridge`, return (comp, tree) \
+/// comp\[u\] is the component of u, tree is the adjacency of the components
+/// joined by the bridges, a forest if the graph isn't connected
+pub fn bridge_tree(adj: &[Vec<(usize, usize)>]) -> (Vec<usize>, Vec<Vec<usize>>) {
+    let n = adj.len();
+    let (fa, bridge) = tarjan_bridge(adj);
+    let is_bridge = |u: usize, v: usize| (fa[v] == u && bridge[v]) || (fa[u] == v && bridge[u]);
+    let mut comp = vec![usize::MAX; n];
+    let mut c = 0;
+    for s in 0..n {
+        if comp[s] != usize::MAX {
+            continue;
+        }
+        comp[s] = c;
+        let mut stack = vec![s];
+        while let Some(u) = stack.pop() {
+            for &(v, _) in &adj[u] {
+                if comp[v] == usize::MAX && !is_bridge(u, v) {
+                    comp[v] = c;
+                    stack.push(v);
+                }
+            }
+        }
+        c += 1;
+    }
+    let mut tree = vec![vec![]; c];
+    for v in (0..n).filter(|&v| bridge[v]) {
+        let (a, b) = (comp[v], comp[fa[v]]);
+        tree[a].push(b);
+        tree[b].push(a);
+    }
+    (comp, tree)
+}
+
+/// cut\[u\] means removing u disconnects its component \
+/// a DFS root is a cut vertex iff it has at least two DFS children
+pub fn tarjan_cut_vertices(adj: &[Vec<usize>]) -> Vec<bool> {
+    let n = adj.len();
+    let mut low = vec![0; n];
+    let mut dfn = vec![0; n];
+    let mut time = 0;
+    let mut cut = vec![false; n];
+    // (vertex, parent, index of the next edge to visit)
+    let mut call = vec![];
+    for i in 0..n {
+        if dfn[i] != 0 {
+            continue;
+        }
+        let mut children = 0;
+        time += 1;
+        (dfn[i], low[i]) = (time, time);
+        call.push((i, n, 0));
+        while let Some(&(u, f, j)) = call.last() {
+            if let Some(&v) = adj[u].get(j) {
+                call.last_mut().unwrap().2 += 1;
+                if dfn[v] == 0 {
+                    time += 1;
+                    (dfn[v], low[v]) = (time, time);
+                    call.push((v, u, 0));
+                } else if v != f {
+                    low[u] = low[u].min(dfn[v]);
+                }
+                continue;
+            }
+            call.pop();
+            if f == n {
+                continue;
+            }
+            low[f] = low[f].min(low[u]);
+            if f == i {
+                children += 1;
+            } else if low[u] >= dfn[f] {
+                cut[f] = true;
+            }
+        }
+        cut[i] = children >= 2;
+    }
+    cut
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        bridge_tree, cactus_cycles, condensation, scc, tarjan_bridge, tarjan_cut_vertices,
+    };
+
+    #[test]
+    fn scc_test() {
+        // {0, 1, 2} -> {3, 4} -> {5}, 6 alone
+        let adj = vec![
+            vec![1],
+            vec![2, 3],
+            vec![0, 4],
+            vec![4],
+            vec![3, 5, 5],
+            vec![5],
+            vec![],
+        ];
+        let (comp, count) = scc(&adj);
+        assert_eq!(count, 4);
+        assert!(comp[0] == comp[1] && comp[1] == comp[2] && comp[3] == comp[4]);
+        for (u, vs) in adj.iter().enumerate() {
+            assert!(vs.iter().all(|&v| comp[u] >= comp[v]));
+        }
+        let dag = condensation(&adj, &comp, count);
+        assert_eq!(dag[comp[0]], [comp[3]]);
+        assert_eq!(dag[comp[3]], [comp[5]]);
+        assert!(dag[comp[5]].is_empty() && dag[comp[6]].is_empty());
+    }
+
+    fn undirected(n: usize, e: &[(usize, usize)]) -> Vec<Vec<usize>> {
+        let mut adj = vec![vec![]; n];
+        for &(u, v) in e {
+            adj[u].push(v);
+            adj[v].push(u);
+        }
+        adj
+    }
+
+    fn with_ids(n: usize, e: &[(usize, usize)]) -> Vec<Vec<(usize, usize)>> {
+        let mut adj = vec![vec![]; n];
+        for (i, &(u, v)) in e.iter().enumerate() {
+            adj[u].push((v, i));
+            adj[v].push((u, i));
+        }
+        adj
+    }
+
+    #[test]
+    fn cut_vertices_test() {
+        // triangle 0-1-2 hanging 3-4 off 2, plus a star centered at 5 and isolated 9
+        let e = [
+            (0, 1),
+            (1, 2),
+            (2, 0),
+            (2, 3),
+            (3, 4),
+            (5, 6),
+            (5, 7),
+            (5, 8),
+        ];
+        let cut = tarjan_cut_vertices(&undirected(10, &e));
+        let got: Vec<usize> = (0..10).filter(|&u| cut[u]).collect();
+        assert_eq!(got, [2, 3, 5]);
+        // the root of a cycle is not a cut vertex
+        let cut = tarjan_cut_vertices(&undirected(4, &[(0, 1), (1, 2), (2, 3), (3, 0)]));
+        assert!(cut.iter().all(|&c| !c));
+    }
+
+    #[test]
+    fn bridge_tree_test() {
+        // cycles {0, 1, 2} and {3, 4, 5} joined by bridge 2-3, leaf 6 on 5, 7 alone
+        let e = [
+            (0, 1),
+            (1, 2),
+            (2, 0),
+            (2, 3),
+            (3, 4),
+            (4, 5),
+            (5, 3),
+            (5, 6),
+        ];
+        let (comp, tree) = bridge_tree(&with_ids(8, &e));
+        assert_eq!(tree.len(), 4);
+        assert!(comp[0] == comp[1] && comp[1] == comp[2]);
+        assert!(comp[3] == comp[4] && comp[4] == comp[5] && comp[0] != comp[3]);
+        let mut mid = tree[comp[3]].clone();
+        mid.sort();
+        let mut want = vec![comp[0], comp[6]];
+        want.sort();
+        assert_eq!(mid, want);
+        assert_eq!(tree[comp[0]], [comp[3]]);
+        assert_eq!((tree[comp[6]].len(), tree[comp[7]].len()), (1, 0));
+    }
+
+    #[test]
+    fn bridge_test() {
+        // 0 = 1 doubled, 1 - 2, 2 - 3 with a self loop on 3
+        let (fa, bridge) = tarjan_bridge(&with_ids(4, &[(0, 1), (1, 0), (1, 2), (2, 3), (3, 3)]));
+        assert_eq!(fa, [4, 0, 1, 2]);
+        assert_eq!(bridge, [false, false, true, true]);
+        // a long path doesn't overflow the stack, every edge is a bridge
+        let n = 1_000_000;
+        let e: Vec<(usize, usize)> = (1..n).map(|i| (i - 1, i)).collect();
+        let (_, bridge) = tarjan_bridge(&with_ids(n, &e));
+        assert_eq!(bridge.iter().filter(|&&b| b).count(), n - 1);
+        let cut = tarjan_cut_vertices(&undirected(n, &e));
+        assert_eq!(cut.iter().filter(|&&c| c).count(), n - 2);
+    }
+
+    #[test]
+    fn cactus_test() {
+        // triangle 0-1-2, square 2-3-4-5 sharing vertex 2, bridge 5-6, self loop on 6, 1 = 7 doubled
+        let e = [
+            (0, 1),
+            (1, 2),
+            (2, 0),
+            (2, 3),
+            (3, 4),
+            (4, 5),
+            (5, 2),
+            (5, 6),
+            (6, 6),
+            (1, 7),
+            (7, 1),
+        ];
+        let cyc = cactus_cycles(&with_ids(8, &e)).unwrap();
+        let m = usize::MAX;
+        assert!(cyc[0] == cyc[1] && cyc[1] == cyc[2] && cyc[0] != m);
+        assert!(cyc[3..7].iter().all(|&c| c == cyc[3]) && cyc[3] != cyc[0] && cyc[3] != m);
+        assert_eq!(cyc[7], m);
+        assert!(cyc[8] != m && cyc[9] == cyc[10] && cyc[9] != m);
+        let mut ids: Vec<usize> = cyc.iter().copied().filter(|&c| c != m).collect();
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids, [0, 1, 2, 3]);
+        // two triangles sharing edge 0-1
+        let e = [(0, 1), (1, 2), (2, 0), (1, 3), (3, 0)];
+        assert!(cactus_cycles(&with_ids(4, &e)).is_none());
+    }
+}
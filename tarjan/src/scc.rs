@@ -0,0 +1,70 @@
+/// strongly connected components by an iterative Tarjan \
+/// return (comp, count), components are numbered in reverse topological order,
+/// every edge u -> v has comp\[u\] >= comp\[v\]
+pub fn scc(adj: &[Vec<usize>]) -> (Vec<usize>, usize) {
+    let n = adj.len();
+    let (mut ord, mut low) = (vec![usize::MAX; n], vec![0; n]);
+    let mut comp = vec![usize::MAX; n];
+    let (mut stack, mut call) = (vec![], vec![]);
+    let (mut t, mut c) = (0, 0);
+    for s in 0..n {
+        if ord[s] != usize::MAX {
+            continue;
+        }
+        (ord[s], low[s], t) = (t, t, t + 1);
+        stack.push(s);
+        // (vertex, index of the next edge to visit)
+        call.push((s, 0));
+        while let Some(&(u, i)) = call.last() {
+            if let Some(&v) = adj[u].get(i) {
+                call.last_mut().unwrap().1 += 1;
+                if ord[v] == usize::MAX {
+                    (ord[v], low[v], t) = (t, t, t + 1);
+                    stack.push(v);
+                    call.push((v, 0));
+                } else if comp[v] == usize::MAX {
+                    low[u] = low[u].min(ord[v]);
+                }
+                continue;
+            }
+            call.pop();
+            if let Some(&(p, _)) = call.last() {
+                low[p] = low[p].min(low[u]);
+            }
+            if low[u] == ord[u] {
+                while let Some(x) = stack.pop() {
+                    comp[x] = c;
+                    if x == u {
+                        break;
+                    }
+                }
+                c += 1;
+            }
+        }
+    }
+    (comp, c)
+}
+
+/// adjacency of the DAG of components from `scc`, without duplicate edges or self loops \
+/// every edge c -> d has c > d
+pub fn condensation(adj: &[Vec<usize>], comp: &[usize], count: usize) -> Vec<Vec<usize>> {
+    let mut dag = vec![vec![]; count];
+    // last[d] = c once c -> d has been added
+    let mut last = vec![usize::MAX; count];
+    let mut by_comp = vec![vec![]; count];
+    for (u, &c) in comp.iter().enumerate() {
+        by_comp[c].push(u);
+    }
+    for (c, us) in by_comp.iter().enumerate() {
+        for &u in us {
+            for &v in &adj[u] {
+                let d = comp[v];
+                if d != c && last[d] != c {
+                    last[d] = c;
+                    dag[c].push(d);
+                }
+            }
+        }
+    }
+    dag
+}
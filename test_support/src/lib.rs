@@ -0,0 +1,135 @@
+//! naive reference models for differential tests, all obviously correct and slow
+
+use random::Random;
+
+/// ordered multiset kept as a sorted Vec, ranks and k are 1-indexed like `Treap`
+#[derive(Clone, Debug, Default)]
+pub struct NaiveMultiset<T> {
+    a: Vec<T>,
+}
+
+impl<T> NaiveMultiset<T>
+where
+    T: Ord + Clone,
+{
+    pub fn new() -> Self {
+        Self { a: vec![] }
+    }
+
+    pub fn len(&self) -> usize {
+        self.a.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.a.is_empty()
+    }
+
+    pub fn insert(&mut self, v: T, n: usize) {
+        let i = self.a.partition_point(|x| *x < v);
+        self.a.splice(i..i, std::iter::repeat_n(v, n));
+    }
+
+    /// delete at most n copies of v, return the number deleted
+    pub fn del(&mut self, v: &T, n: usize) -> usize {
+        let n = n.min(self.count(v));
+        let i = self.a.partition_point(|x| x < v);
+        self.a.drain(i..i + n);
+        n
+    }
+
+    pub fn count(&self, v: &T) -> usize {
+        self.a.iter().filter(|x| *x == v).count()
+    }
+
+    /// number of values less than v, plus one
+    pub fn rnk(&self, v: &T) -> usize {
+        self.a.iter().filter(|x| *x < v).count() + 1
+    }
+
+    pub fn kth(&self, k: usize) -> Option<&T> {
+        k.checked_sub(1).and_then(|k| self.a.get(k))
+    }
+
+    /// largest value less than v
+    pub fn pre(&self, v: &T) -> Option<&T> {
+        self.a.iter().rev().find(|x| *x < v)
+    }
+
+    /// smallest value greater than v
+    pub fn nxt(&self, v: &T) -> Option<&T> {
+        self.a.iter().find(|x| *x > v)
+    }
+
+    /// values in [lo, hi]
+    pub fn range(&self, lo: &T, hi: &T) -> Vec<T> {
+        self.a
+            .iter()
+            .filter(|x| lo <= *x && *x <= hi)
+            .cloned()
+            .collect()
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        &self.a
+    }
+}
+
+/// array answering range folds by scanning
+#[derive(Clone, Debug)]
+pub struct NaiveArray<S, F> {
+    a: Vec<S>,
+    e: S,
+    op: F,
+}
+
+impl<S, F> NaiveArray<S, F>
+where
+    S: Clone,
+    F: Fn(&S, &S) -> S,
+{
+    /// n copies of the identity e
+    pub fn new(n: usize, e: S, op: F) -> Self {
+        Self {
+            a: vec![e.clone(); n],
+            e,
+            op,
+        }
+    }
+
+    pub fn get(&self, i: usize) -> &S {
+        &self.a[i]
+    }
+
+    pub fn set(&mut self, i: usize, v: S) {
+        self.a[i] = v;
+    }
+
+    /// a[i] = a[i] * v
+    pub fn update(&mut self, i: usize, v: &S) {
+        self.a[i] = (self.op)(&self.a[i], v);
+    }
+
+    /// fold of a[l..=r], the identity when l > r
+    pub fn query(&self, l: usize, r: usize) -> S {
+        let mut res = self.e.clone();
+        for x in self.a.iter().take(r + 1).skip(l) {
+            res = (self.op)(&res, x);
+        }
+        res
+    }
+}
+
+/// suffix start byte positions in lexicographic order, by sorting the suffixes
+pub fn naive_suffix_array(s: &str) -> Vec<u32> {
+    let b = s.as_bytes();
+    let mut sa: Vec<u32> = (0..b.len() as u32).collect();
+    sa.sort_by(|&i, &j| b[i as usize..].cmp(&b[j as usize..]));
+    sa
+}
+
+/// random string of length n over the first k lowercase letters
+pub fn random_string(rd: &mut Random, n: usize, k: u8) -> String {
+    (0..n)
+        .map(|_| (b'a' + (rd.gen() % k as u64) as u8) as char)
+        .collect()
+}
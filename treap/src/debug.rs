@@ -0,0 +1,47 @@
+use std::fmt::{self, Debug, Formatter};
+
+use arena::NodeId;
+
+use crate::{Monoid, Treap};
+
+impl<T, M> Treap<T, M>
+where
+    T: Debug,
+    M: Monoid<T>,
+    M::S: Debug,
+{
+    fn dump(&self, f: &mut Formatter<'_>, x: NodeId, lo: usize, depth: usize) -> fmt::Result {
+        if x.is_null() {
+            return Ok(());
+        }
+        let (l, r) = (self.t[x].l, self.t[x].r);
+        let at = lo + self.t[l].sz as usize;
+        writeln!(
+            f,
+            "{:w$}{:?} x{} ranks [{}, {}) rnd {} agg {:?}",
+            "",
+            self.value(x),
+            self.t[x].w,
+            lo,
+            lo + self.t[x].sz as usize,
+            self.t[x].rnd,
+            self.t[x].agg,
+            w = 2 * depth
+        )?;
+        self.dump(f, l, lo, depth + 1)?;
+        self.dump(f, r, at + self.t[x].w as usize, depth + 1)
+    }
+}
+
+/// preorder dump, each node with its count, the ranks its subtree covers and its aggregate
+impl<T, M> Debug for Treap<T, M>
+where
+    T: Debug,
+    M: Monoid<T>,
+    M::S: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Treap (len {})", self.subtree_len(self.root))?;
+        self.dump(f, self.root, 0, 0)
+    }
+}
@@ -0,0 +1,504 @@
+#[cfg(feature = "debug")]
+use std::fmt::{self, Debug, Formatter};
+use std::marker::PhantomData;
+use std::ops::Add;
+
+use arena::{Arena, NodeId};
+use random::{now_time_u64, try_now_time_u64, Random};
+
+use crate::{Additive, Monoid};
+
+/// aggregate and lazy range update maintained by `ImplicitTreap`,
+/// `()` maintains nothing \
+/// reverse keeps the aggregate as is, so it should be commutative
+pub trait LazyAction<T> {
+    type S: Clone;
+    type F: Clone;
+    fn identity() -> Self::S;
+    fn binary_operation(a: &Self::S, b: &Self::S) -> Self::S;
+    fn lift(v: &T) -> Self::S;
+    /// f applied after g
+    fn composition(f: &Self::F, g: &Self::F) -> Self::F;
+    fn apply(f: &Self::F, v: &mut T);
+    /// f applied to the aggregate s of n elements
+    fn mapping(f: &Self::F, s: &Self::S, n: u32) -> Self::S;
+}
+
+impl<T> LazyAction<T> for () {
+    type S = ();
+    type F = ();
+    fn identity() -> Self::S {}
+    fn binary_operation(_: &Self::S, _: &Self::S) -> Self::S {}
+    fn lift(_: &T) -> Self::S {}
+    fn composition(_: &Self::F, _: &Self::F) -> Self::F {}
+    fn apply(_: &Self::F, _: &mut T) {}
+    fn mapping(_: &Self::F, _: &Self::S, _: u32) -> Self::S {}
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Update<T> {
+    Add(T),
+    Assign(T),
+}
+
+/// range add and range assign, with range sum
+pub struct AddAssignSum<T>(PhantomData<fn() -> T>);
+impl<T> LazyAction<T> for AddAssignSum<T>
+where
+    T: Clone + Default + Add<Output = T>,
+{
+    type S = T;
+    type F = Update<T>;
+    fn identity() -> Self::S {
+        T::default()
+    }
+    fn binary_operation(a: &Self::S, b: &Self::S) -> Self::S {
+        a.clone() + b.clone()
+    }
+    fn lift(v: &T) -> Self::S {
+        v.clone()
+    }
+    fn composition(f: &Self::F, g: &Self::F) -> Self::F {
+        match (f, g) {
+            (Update::Add(a), Update::Add(b)) => Update::Add(b.clone() + a.clone()),
+            (Update::Add(a), Update::Assign(b)) => Update::Assign(b.clone() + a.clone()),
+            (Update::Assign(a), _) => Update::Assign(a.clone()),
+        }
+    }
+    fn apply(f: &Self::F, v: &mut T) {
+        *v = match f {
+            Update::Add(a) => v.clone() + a.clone(),
+            Update::Assign(a) => a.clone(),
+        }
+    }
+    fn mapping(f: &Self::F, s: &Self::S, n: u32) -> Self::S {
+        match f {
+            Update::Add(a) => s.clone() + Additive::lift(a, n),
+            Update::Assign(a) => Additive::lift(a, n),
+        }
+    }
+}
+
+struct Node<T, S, F> {
+    l: NodeId,
+    r: NodeId,
+    val: Option<T>,
+    rnd: u32,
+    sz: u32,
+    rev: bool, // children of the subtree still need swapping
+    agg: S,
+    lazy: Option<F>, // already applied to the node, pending for its children
+}
+
+/// treap keyed by position, all indices are 0-indexed
+pub struct ImplicitTreap<T, A = ()>
+where
+    A: LazyAction<T>,
+{
+    t: Arena<Node<T, A::S, A::F>>, // t[NULL] is the empty sentinel
+    rd: Random,
+    root: NodeId,
+}
+
+impl<T> Default for ImplicitTreap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> ImplicitTreap<T> {
+    pub fn new() -> Self {
+        Self::with_seed(now_time_u64())
+    }
+
+    /// None if the clock can't seed the tree
+    pub fn try_new() -> Option<Self> {
+        try_now_time_u64().map(Self::with_seed)
+    }
+
+    /// same seed, same tree shapes
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_action_and_seed(seed)
+    }
+
+    pub fn get(&self, i: usize) -> Option<&T> {
+        if i >= self.len() {
+            return None;
+        }
+        let (mut x, mut k, mut flip) = (self.root, i, false);
+        loop {
+            flip ^= self.t[x].rev;
+            let (l, r) = if flip {
+                (self.t[x].r, self.t[x].l)
+            } else {
+                (self.t[x].l, self.t[x].r)
+            };
+            let ls = self.t[l].sz as usize;
+            if k < ls {
+                x = l;
+            } else if k == ls {
+                return self.t[x].val.as_ref();
+            } else {
+                k -= ls + 1;
+                x = r;
+            }
+        }
+    }
+}
+
+impl<T> ImplicitTreap<T, AddAssignSum<T>>
+where
+    T: Clone + Default + Add<Output = T>,
+{
+    /// add v to every element in [l, r)
+    pub fn add(&mut self, l: usize, r: usize, v: T) {
+        self.apply_range(l, r, Update::Add(v));
+    }
+
+    /// set every element in [l, r) to v
+    pub fn assign(&mut self, l: usize, r: usize, v: T) {
+        self.apply_range(l, r, Update::Assign(v));
+    }
+
+    /// sum of the elements in [l, r)
+    pub fn sum(&mut self, l: usize, r: usize) -> T {
+        self.query(l, r)
+    }
+}
+
+impl<T, A> ImplicitTreap<T, A>
+where
+    A: LazyAction<T>,
+{
+    pub fn with_action() -> Self {
+        Self::with_action_and_seed(now_time_u64())
+    }
+
+    /// None if the clock can't seed the tree
+    pub fn try_with_action() -> Option<Self> {
+        try_now_time_u64().map(Self::with_action_and_seed)
+    }
+
+    pub fn with_action_and_seed(seed: u64) -> Self {
+        let mut t = Arena::new();
+        t.alloc(Node {
+            l: NodeId::NULL,
+            r: NodeId::NULL,
+            val: None,
+            rnd: 0xFFFFFFFFu32,
+            sz: 0,
+            rev: false,
+            agg: A::identity(),
+            lazy: None,
+        });
+        Self {
+            t,
+            rd: Random::new(seed),
+            root: NodeId::NULL,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.t[self.root].sz as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root.is_null()
+    }
+
+    fn new_node(&mut self, v: T, rnd: u32) -> NodeId {
+        self.t.alloc(Node {
+            l: NodeId::NULL,
+            r: NodeId::NULL,
+            agg: A::lift(&v),
+            val: Some(v),
+            rnd,
+            sz: 1,
+            rev: false,
+            lazy: None,
+        })
+    }
+
+    // x is unlinked, drop its value and let new_node reuse it
+    fn release(&mut self, x: NodeId) -> T {
+        self.t.free(x);
+        self.t[x].lazy = None;
+        self.t[x].val.take().unwrap()
+    }
+
+    fn pushup(&mut self, x: NodeId) {
+        let (l, r) = (self.t[x].l, self.t[x].r);
+        self.t[x].sz = self.t[l].sz + self.t[r].sz + 1;
+        let v = A::lift(self.t[x].val.as_ref().unwrap());
+        let s = A::binary_operation(&self.t[l].agg, &v);
+        self.t[x].agg = A::binary_operation(&s, &self.t[r].agg);
+    }
+
+    fn all_apply(&mut self, x: NodeId, f: &A::F) {
+        if x.is_null() {
+            return;
+        }
+        let node = &mut self.t[x];
+        A::apply(f, node.val.as_mut().unwrap());
+        node.agg = A::mapping(f, &node.agg, node.sz);
+        node.lazy = Some(match &node.lazy {
+            Some(g) => A::composition(f, g),
+            None => f.clone(),
+        });
+    }
+
+    fn toggle_rev(&mut self, x: NodeId) {
+        if !x.is_null() {
+            self.t[x].rev ^= true;
+        }
+    }
+
+    fn pushdown(&mut self, x: NodeId) {
+        if self.t[x].rev {
+            let node = &mut self.t[x];
+            node.rev = false;
+            std::mem::swap(&mut node.l, &mut node.r);
+            let (l, r) = (node.l, node.r);
+            self.toggle_rev(l);
+            self.toggle_rev(r);
+        }
+        if let Some(f) = self.t[x].lazy.take() {
+            self.all_apply(self.t[x].l, &f);
+            self.all_apply(self.t[x].r, &f);
+        }
+    }
+
+    // split subtree x into (first k, rest)
+    fn split(&mut self, x: NodeId, k: usize) -> (NodeId, NodeId) {
+        if x.is_null() {
+            return (NodeId::NULL, NodeId::NULL);
+        }
+        self.pushdown(x);
+        let ls = self.t[self.t[x].l].sz as usize;
+        if k <= ls {
+            let (a, b) = self.split(self.t[x].l, k);
+            self.t[x].l = b;
+            self.pushup(x);
+            (a, x)
+        } else {
+            let (a, b) = self.split(self.t[x].r, k - ls - 1);
+            self.t[x].r = a;
+            self.pushup(x);
+            (x, b)
+        }
+    }
+
+    fn merge_nodes(&mut self, a: NodeId, b: NodeId) -> NodeId {
+        if a.is_null() {
+            return b;
+        }
+        if b.is_null() {
+            return a;
+        }
+        if self.t[a].rnd < self.t[b].rnd {
+            self.pushdown(a);
+            let t = self.merge_nodes(self.t[a].r, b);
+            self.t[a].r = t;
+            self.pushup(a);
+            a
+        } else {
+            self.pushdown(b);
+            let t = self.merge_nodes(a, self.t[b].l);
+            self.t[b].l = t;
+            self.pushup(b);
+            b
+        }
+    }
+
+    // move subtree x of src into self, keeping its shape; tags are pushed down first
+    // since pushup rebuilds the aggregate from the children
+    fn adopt(&mut self, src: &mut Self, x: NodeId) -> NodeId {
+        if x.is_null() {
+            return NodeId::NULL;
+        }
+        src.pushdown(x);
+        let l = self.adopt(src, src.t[x].l);
+        let y = self.new_node(src.release(x), src.t[x].rnd);
+        let r = self.adopt(src, src.t[x].r);
+        self.t[y].l = l;
+        self.t[y].r = r;
+        self.pushup(y);
+        y
+    }
+
+    /// insert v so that it becomes the i-th element
+    pub fn insert_at(&mut self, i: usize, v: T) {
+        assert!(i <= self.len(), "index out of range!");
+        let rnd = self.rd.gen() as u32;
+        let y = self.new_node(v, rnd);
+        let (a, b) = self.split(self.root, i);
+        let a = self.merge_nodes(a, y);
+        self.root = self.merge_nodes(a, b);
+    }
+
+    pub fn push_back(&mut self, v: T) {
+        self.insert_at(self.len(), v);
+    }
+
+    /// remove and return the i-th element
+    pub fn erase_at(&mut self, i: usize) -> T {
+        assert!(i < self.len(), "index out of range!");
+        let (a, b) = self.split(self.root, i);
+        let (y, c) = self.split(b, 1);
+        self.root = self.merge_nodes(a, c);
+        self.release(y)
+    }
+
+    /// the i-th element, pushing pending updates on the way down
+    pub fn value_at(&mut self, i: usize) -> &T {
+        assert!(i < self.len(), "index out of range!");
+        let (mut x, mut k) = (self.root, i);
+        loop {
+            self.pushdown(x);
+            let ls = self.t[self.t[x].l].sz as usize;
+            if k < ls {
+                x = self.t[x].l;
+            } else if k == ls {
+                return self.t[x].val.as_ref().unwrap();
+            } else {
+                k -= ls + 1;
+                x = self.t[x].r;
+            }
+        }
+    }
+
+    /// apply f to every element in [l, r)
+    pub fn apply_range(&mut self, l: usize, r: usize, f: A::F) {
+        assert!(l <= r && r <= self.len(), "index out of range!");
+        let (a, b) = self.split(self.root, l);
+        let (b, c) = self.split(b, r - l);
+        self.all_apply(b, &f);
+        let b = self.merge_nodes(a, b);
+        self.root = self.merge_nodes(b, c);
+    }
+
+    /// aggregate of the elements in [l, r)
+    pub fn query(&mut self, l: usize, r: usize) -> A::S {
+        assert!(l <= r && r <= self.len(), "index out of range!");
+        let (a, b) = self.split(self.root, l);
+        let (b, c) = self.split(b, r - l);
+        let res = self.t[b].agg.clone();
+        let b = self.merge_nodes(a, b);
+        self.root = self.merge_nodes(b, c);
+        res
+    }
+
+    /// aggregate of all elements
+    pub fn query_all(&self) -> A::S {
+        self.t[self.root].agg.clone()
+    }
+
+    /// reverse the elements in [l, r)
+    pub fn reverse(&mut self, l: usize, r: usize) {
+        assert!(l <= r && r <= self.len(), "index out of range!");
+        let (a, b) = self.split(self.root, l);
+        let (b, c) = self.split(b, r - l);
+        self.toggle_rev(b);
+        let b = self.merge_nodes(a, b);
+        self.root = self.merge_nodes(b, c);
+    }
+
+    /// split into (first i elements, the rest) \
+    /// the smaller part is moved out, O(log n + min size)
+    pub fn split_at(mut self, i: usize) -> (Self, Self) {
+        assert!(i <= self.len(), "index out of range!");
+        let (a, b) = self.split(self.root, i);
+        let (keep, moved) = if self.t[a].sz >= self.t[b].sz {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        let mut other = Self::with_action_and_seed(self.rd.gen());
+        other.root = other.adopt(&mut self, moved);
+        self.root = keep;
+        if keep == a {
+            (self, other)
+        } else {
+            (other, self)
+        }
+    }
+
+    /// concatenate other after self \
+    /// the smaller treap is moved into the larger one, O(log n + min size)
+    pub fn merge(mut self, mut other: Self) -> Self {
+        if self.len() >= other.len() {
+            let x = other.root;
+            let y = self.adopt(&mut other, x);
+            self.root = self.merge_nodes(self.root, y);
+            self
+        } else {
+            let x = self.root;
+            let y = other.adopt(&mut self, x);
+            other.root = other.merge_nodes(y, other.root);
+            other
+        }
+    }
+}
+
+#[cfg(feature = "debug")]
+impl<T, A> ImplicitTreap<T, A>
+where
+    T: Debug,
+    A: LazyAction<T>,
+    A::S: Debug,
+    A::F: Debug,
+{
+    // flip tells whether the reverses pending above x are odd
+    fn dump(
+        &self,
+        f: &mut Formatter<'_>,
+        x: NodeId,
+        lo: usize,
+        depth: usize,
+        flip: bool,
+    ) -> fmt::Result {
+        if x.is_null() {
+            return Ok(());
+        }
+        let node = &self.t[x];
+        // with a pending reverse the children are stored swapped
+        let flip = flip ^ node.rev;
+        let (l, r) = if flip {
+            (node.r, node.l)
+        } else {
+            (node.l, node.r)
+        };
+        let ls = self.t[l].sz as usize;
+        writeln!(
+            f,
+            "{:w$}{:?} at {} subtree [{}, {}) agg {:?} rev {} lazy {:?}",
+            "",
+            node.val.as_ref().unwrap(),
+            lo + ls,
+            lo,
+            lo + node.sz as usize,
+            node.agg,
+            node.rev,
+            node.lazy,
+            w = 2 * depth
+        )?;
+        self.dump(f, l, lo, depth + 1, flip)?;
+        self.dump(f, r, lo + ls + 1, depth + 1, flip)
+    }
+}
+
+#[cfg(feature = "debug")]
+/// preorder dump, each node with the positions its subtree covers and its pending tags \
+/// values below a pending tag are shown before the tag is applied
+impl<T, A> Debug for ImplicitTreap<T, A>
+where
+    T: Debug,
+    A: LazyAction<T>,
+    A::S: Debug,
+    A::F: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "ImplicitTreap (len {})", self.len())?;
+        self.dump(f, self.root, 0, 0, false)
+    }
+}
@@ -1,143 +1,283 @@
-use std::{
-    time::{SystemTime, UNIX_EPOCH},
-    vec,
-};
-
-use random::Random;
-
-pub struct Treap<T> {
-    size: usize,
-    l: Vec<u32>,
-    r: Vec<u32>,
-    val: Vec<T>,
-    rnd: Vec<u32>,
-    pub sz: Vec<u32>, // subtree size
-    pub w: Vec<u32>,  // duplicated value number
+use std::vec;
+
+use arena::{Arena, NodeId};
+use random::{now_time_u64, try_now_time_u64, Random};
+
+#[cfg(feature = "debug")]
+mod debug;
+mod implicit;
+mod map;
+mod monoid;
+#[cfg(feature = "validate")]
+mod validate;
+pub use implicit::{AddAssignSum, ImplicitTreap, LazyAction, Update};
+pub use map::TreapMap;
+pub use monoid::{Additive, Max, Min, Monoid};
+
+struct Node<T, S> {
+    l: NodeId,
+    r: NodeId,
+    val: Option<T>,
+    rnd: u32,
+    sz: u32, // subtree size
+    w: u32,  // duplicated value number
+    agg: S,  // subtree aggregate
+}
+
+pub struct Treap<T, M = ()>
+where
+    M: Monoid<T>,
+{
+    t: Arena<Node<T, M::S>>, // t[NULL] is the empty sentinel
     rd: Random,
-    root: usize,
+    root: NodeId,
 }
 
 impl<T> Treap<T>
 where
     T: Ord,
 {
-    pub fn new(no_use_val: T) -> Self {
+    pub fn new() -> Self {
+        Self::with_monoid()
+    }
+
+    /// None if the clock can't seed the tree
+    pub fn try_new() -> Option<Self> {
+        Self::try_with_monoid()
+    }
+
+    /// same seed, same tree shapes
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_monoid_and_seed(seed)
+    }
+
+    /// build from nondecreasing values in O(n)
+    pub fn from_sorted<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+    {
+        Self::from_sorted_with_monoid(iter)
+    }
+}
+
+impl<T> Default for Treap<T>
+where
+    T: Ord,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, M> Treap<T, M>
+where
+    T: Ord,
+    M: Monoid<T>,
+{
+    /// e.g. `Treap::<i64, Additive<i64>>::with_monoid()`
+    pub fn with_monoid() -> Self {
+        Self::with_monoid_and_seed(now_time_u64())
+    }
+
+    /// None if the clock can't seed the tree
+    pub fn try_with_monoid() -> Option<Self> {
+        try_now_time_u64().map(Self::with_monoid_and_seed)
+    }
+
+    pub fn with_monoid_and_seed(seed: u64) -> Self {
+        let mut t = Arena::new();
+        t.alloc(Self::sentinel());
         Self {
-            size: 0,
-            l: vec![0],
-            r: vec![0],
-            rd: Random::new(
-                SystemTime::now()
-                    .duration_since(UNIX_EPOCH)
-                    .expect("Time went backwards!")
-                    .as_secs(),
-            ),
-            val: vec![no_use_val],
-            rnd: vec![0xFFFFFFFFu32],
-            w: vec![0],
-            sz: vec![0],
-            root: 0,
-        }
-    }
-
-    fn pushup(&mut self, x: usize) {
-        self.sz[x] = self.sz[self.l[x] as usize] + self.sz[self.r[x] as usize] + self.w[x]
-    }
-
-    fn lrot(&mut self, x: &mut usize) {
-        let t = self.r[*x];
-        self.r[*x] = self.l[t as usize];
-        self.l[t as usize] = *x as u32;
-        self.sz[t as usize] = self.sz[*x];
+            t,
+            rd: Random::new(seed),
+            root: NodeId::NULL,
+        }
+    }
+
+    fn sentinel() -> Node<T, M::S> {
+        Node {
+            l: NodeId::NULL,
+            r: NodeId::NULL,
+            val: None,
+            rnd: 0xFFFFFFFFu32,
+            sz: 0,
+            w: 0,
+            agg: M::identity(),
+        }
+    }
+
+    /// remove every value, keeping the allocated memory
+    pub fn clear(&mut self) {
+        self.t.clear();
+        self.t.alloc(Self::sentinel());
+        self.root = NodeId::NULL;
+    }
+
+    /// build from nondecreasing values in O(n)
+    pub fn from_sorted_with_monoid<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut res = Self::with_monoid();
+        // right spine of the cartesian tree on rnd
+        let mut stack: Vec<NodeId> = vec![];
+        for v in iter {
+            if let Some(&x) = stack.last() {
+                debug_assert!(*res.value(x) <= v, "values are not sorted!");
+                if *res.value(x) == v {
+                    res.t[x].w += 1;
+                    continue;
+                }
+            }
+            let rnd = res.rd.gen() as u32;
+            let y = res.new_node(v, 1, rnd);
+            let mut last = NodeId::NULL;
+            while let Some(&x) = stack.last() {
+                if res.t[x].rnd < rnd {
+                    break;
+                }
+                stack.pop();
+                res.pushup(x);
+                last = x;
+            }
+            res.t[y].l = last;
+            if let Some(&x) = stack.last() {
+                res.t[x].r = y;
+            }
+            stack.push(y);
+        }
+        while let Some(x) = stack.pop() {
+            res.pushup(x);
+            res.root = x;
+        }
+        res
+    }
+
+    fn pushup(&mut self, x: NodeId) {
+        let (l, r) = (self.t[x].l, self.t[x].r);
+        self.t[x].sz = self.t[l].sz + self.t[r].sz + self.t[x].w;
+        let s = M::binary_operation(&self.t[l].agg, &M::lift(self.value(x), self.t[x].w));
+        self.t[x].agg = M::binary_operation(&s, &self.t[r].agg);
+    }
+
+    fn lrot(&mut self, x: &mut NodeId) {
+        let t = self.t[*x].r;
+        self.t[*x].r = self.t[t].l;
+        self.t[t].l = *x;
         self.pushup(*x);
-        *x = t as usize;
+        self.pushup(t);
+        *x = t;
     }
 
-    fn rrot(&mut self, x: &mut usize) {
-        let t = self.l[*x];
-        self.l[*x] = self.r[t as usize];
-        self.r[t as usize] = *x as u32;
-        self.sz[t as usize] = self.sz[*x];
+    fn rrot(&mut self, x: &mut NodeId) {
+        let t = self.t[*x].l;
+        self.t[*x].l = self.t[t].r;
+        self.t[t].r = *x;
         self.pushup(*x);
-        *x = t as usize;
-    }
-
-    fn _insert(&mut self, x: &mut usize, v: T, n: u32) {
-        if *x == 0 {
-            self.size += 1;
-            *x = self.size;
-            self.sz.push(n);
-            self.w.push(n);
-            self.val.push(v);
-            self.rnd.push(self.rd.gen() as u32);
-            self.l.push(0);
-            self.r.push(0);
-            return;
+        self.pushup(t);
+        *x = t;
+    }
+
+    fn new_node(&mut self, v: T, n: u32, rnd: u32) -> NodeId {
+        self.t.alloc(Node {
+            l: NodeId::NULL,
+            r: NodeId::NULL,
+            agg: M::lift(&v, n),
+            val: Some(v),
+            rnd,
+            sz: n,
+            w: n,
+        })
+    }
+
+    // x is unlinked, drop its value and let new_node reuse it
+    fn release(&mut self, x: NodeId) -> Option<T> {
+        self.t.free(x);
+        self.t[x].val.take()
+    }
+
+    // return the node holding v
+    fn _insert(&mut self, x: &mut NodeId, v: T, n: u32) -> NodeId {
+        if x.is_null() {
+            let rnd = self.rd.gen() as u32;
+            *x = self.new_node(v, n, rnd);
+            return *x;
         }
-        self.sz[*x] += n;
-        if self.val[*x] == v {
-            self.w[*x] += n;
-        } else if self.val[*x] < v {
-            let mut tmp = self.r[*x] as usize;
-            self._insert(&mut tmp, v, n);
-            self.r[*x] = tmp as u32;
-            if self.rnd[self.r[*x] as usize] < self.rnd[*x] {
+        let res;
+        if *self.value(*x) == v {
+            self.t[*x].w += n;
+            res = *x;
+        } else if *self.value(*x) < v {
+            let mut tmp = self.t[*x].r;
+            res = self._insert(&mut tmp, v, n);
+            self.t[*x].r = tmp;
+            if self.t[tmp].rnd < self.t[*x].rnd {
                 self.lrot(x);
             }
         } else {
-            let mut tmp = self.l[*x] as usize;
-            self._insert(&mut tmp, v, n);
-            self.l[*x] = tmp as u32;
-            if self.rnd[self.l[*x] as usize] < self.rnd[*x] {
+            let mut tmp = self.t[*x].l;
+            res = self._insert(&mut tmp, v, n);
+            self.t[*x].l = tmp;
+            if self.t[tmp].rnd < self.t[*x].rnd {
                 self.rrot(x);
             }
         }
+        self.pushup(*x);
+        res
     }
 
     /// insert n value v
     pub fn insert(&mut self, v: T, n: u32) {
+        self.insert_node(v, n);
+    }
+
+    // insert, return the node holding v
+    fn insert_node(&mut self, v: T, n: u32) -> NodeId {
         let mut tmp = self.root;
-        self._insert(&mut tmp, v, n);
+        let res = self._insert(&mut tmp, v, n);
         self.root = tmp;
+        res
     }
 
-    fn _del(&mut self, x: &mut usize, v: &T, n: u32) -> u32 {
-        if *x == 0 {
+    fn _del(&mut self, x: &mut NodeId, v: &T, n: u32) -> u32 {
+        if x.is_null() {
             return 0;
         }
-        if self.val[*x] == *v {
-            let w = self.w[*x];
-            if self.w[*x] > n {
-                self.w[*x] -= n;
-                self.sz[*x] -= n;
+        let (l, r) = (self.t[*x].l, self.t[*x].r);
+        if self.value(*x) == v {
+            let w = self.t[*x].w;
+            if w > n {
+                self.t[*x].w -= n;
+                self.pushup(*x);
                 return n;
             }
-            if self.l[*x] == 0 || self.r[*x] == 0 {
-                *x = (self.l[*x] + self.r[*x]) as usize;
-                return w;
-            } else if self.rnd[self.l[*x] as usize] < self.rnd[self.r[*x] as usize] {
+            if l.is_null() || r.is_null() {
+                self.release(*x);
+                *x = if l.is_null() { r } else { l };
+                w
+            } else if self.t[l].rnd < self.t[r].rnd {
                 self.rrot(x);
-                return self._del(x, v, n);
+                self._del(x, v, n)
             } else {
                 self.lrot(x);
-                return self._del(x, v, n);
+                self._del(x, v, n)
             }
-        } else if self.val[*x] < *v {
-            let mut tmp = self.r[*x] as usize;
+        } else if self.value(*x) < v {
+            let mut tmp = r;
             let succ = self._del(&mut tmp, v, n);
-            self.r[*x] = tmp as u32;
+            self.t[*x].r = tmp;
             if succ > 0 {
-                self.sz[*x] -= succ;
+                self.pushup(*x);
             }
-            return succ;
+            succ
         } else {
-            let mut tmp = self.l[*x] as usize;
+            let mut tmp = l;
             let succ = self._del(&mut tmp, v, n);
-            self.l[*x] = tmp as u32;
+            self.t[*x].l = tmp;
             if succ > 0 {
-                self.sz[*x] -= succ;
+                self.pushup(*x);
             }
-            return succ;
+            succ
         }
     }
 
@@ -149,36 +289,48 @@ where
         res as usize
     }
 
-    fn _idx(&self, x: usize, v: &T) -> Option<usize> {
-        if x == 0 {
+    /// delete every copy of value v; return the number deleted
+    pub fn erase_all(&mut self, v: &T) -> usize {
+        let mut tmp = self.root;
+        let res = self._del(&mut tmp, v, u32::MAX);
+        self.root = tmp;
+        res as usize
+    }
+
+    fn _idx(&self, x: NodeId, v: &T) -> Option<NodeId> {
+        if x.is_null() {
             return None;
         }
-        if self.val[x] == *v {
+        if self.value(x) == v {
             Some(x)
-        } else if self.val[x] < *v {
-            self._idx(self.r[x] as usize, v)
+        } else if self.value(x) < v {
+            self._idx(self.t[x].r, v)
         } else {
-            self._idx(self.l[x] as usize, v)
+            self._idx(self.t[x].l, v)
         }
     }
 
-    /// find the index of v
-    pub fn idx(&self, v: &T) -> Option<usize> {
+    /// find the node holding v
+    pub fn idx(&self, v: &T) -> Option<NodeId> {
         self._idx(self.root, v)
     }
 
-    fn _rnk(&self, x: usize, v: &T) -> usize {
-        if x == 0 {
+    /// number of copies of v
+    pub fn count(&self, v: &T) -> usize {
+        self.idx(v).map_or(0, |x| self.t[x].w as usize)
+    }
+
+    fn _rnk(&self, x: NodeId, v: &T) -> usize {
+        if x.is_null() {
             return 1;
         }
-        if self.val[x] == *v {
-            self.sz[self.l[x] as usize] as usize + 1
-        } else if self.val[x] < *v {
-            self.sz[self.l[x] as usize] as usize
-                + self.w[x] as usize
-                + self._rnk(self.r[x] as usize, v)
+        let ls = self.t[self.t[x].l].sz as usize;
+        if self.value(x) == v {
+            ls + 1
+        } else if self.value(x) < v {
+            ls + self.t[x].w as usize + self._rnk(self.t[x].r, v)
         } else {
-            self._rnk(self.l[x] as usize, v)
+            self._rnk(self.t[x].l, v)
         }
     }
 
@@ -188,40 +340,69 @@ where
         self._rnk(self.root, v)
     }
 
-    fn _kth(&self, x: usize, k: usize) -> Option<&T> {
-        if x == 0 {
+    /// number of values, counting duplicates
+    pub fn len(&self) -> usize {
+        self.t[self.root].sz as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root.is_null()
+    }
+
+    // number of values < v, or <= v if inclusive
+    fn count_below(&self, v: &T, inclusive: bool) -> usize {
+        let (mut x, mut res) = (self.root, 0);
+        while !x.is_null() {
+            if self.value(x) < v || (inclusive && self.value(x) == v) {
+                res += (self.t[self.t[x].l].sz + self.t[x].w) as usize;
+                x = self.t[x].r;
+            } else {
+                x = self.t[x].l;
+            }
+        }
+        res
+    }
+
+    /// number of values v with lo <= v <= hi
+    pub fn count_range(&self, lo: &T, hi: &T) -> usize {
+        if lo > hi {
+            return 0;
+        }
+        self.count_below(hi, true) - self.count_below(lo, false)
+    }
+
+    fn _kth(&self, x: NodeId, k: usize) -> Option<NodeId> {
+        if x.is_null() {
             None
         } else {
-            if k <= self.sz[self.l[x] as usize] as usize {
-                self._kth(self.l[x] as usize, k)
-            } else if k > self.sz[self.l[x] as usize] as usize + self.w[x] as usize {
-                self._kth(
-                    self.r[x] as usize,
-                    k - (self.sz[self.l[x] as usize] as usize + self.w[x] as usize),
-                )
+            let ls = self.t[self.t[x].l].sz as usize;
+            if k <= ls {
+                self._kth(self.t[x].l, k)
+            } else if k > ls + self.t[x].w as usize {
+                self._kth(self.t[x].r, k - (ls + self.t[x].w as usize))
             } else {
-                Some(&self.val[x])
+                Some(x)
             }
         }
     }
 
     pub fn kth(&self, k: usize) -> Option<&T> {
-        self._kth(self.root, k)
+        self._kth(self.root, k).map(|x| self.value(x))
     }
 
-    fn _pre(&self, x: usize, v: &T) -> Option<&T> {
-        if x == 0 {
+    fn _pre(&self, x: NodeId, v: &T) -> Option<&T> {
+        if x.is_null() {
             None
         } else {
-            if self.val[x] < *v {
-                let fnd = self._pre(self.r[x] as usize, v);
+            if self.value(x) < v {
+                let fnd = self._pre(self.t[x].r, v);
                 if fnd.is_none() {
-                    Some(&self.val[x])
+                    Some(self.value(x))
                 } else {
                     fnd
                 }
             } else {
-                self._pre(self.l[x] as usize, v)
+                self._pre(self.t[x].l, v)
             }
         }
     }
@@ -230,19 +411,19 @@ where
         self._pre(self.root, v)
     }
 
-    fn _nxt(&self, x: usize, v: &T) -> Option<&T> {
-        if x == 0 {
+    fn _nxt(&self, x: NodeId, v: &T) -> Option<&T> {
+        if x.is_null() {
             None
         } else {
-            if self.val[x] > *v {
-                let fnd = self._nxt(self.l[x] as usize, v);
+            if self.value(x) > v {
+                let fnd = self._nxt(self.t[x].l, v);
                 if fnd.is_none() {
-                    Some(&self.val[x])
+                    Some(self.value(x))
                 } else {
                     fnd
                 }
             } else {
-                self._nxt(self.r[x] as usize, v)
+                self._nxt(self.t[x].r, v)
             }
         }
     }
@@ -250,4 +431,670 @@ where
     pub fn nxt(&self, v: &T) -> Option<&T> {
         self._nxt(self.root, v)
     }
+
+    // aggregate of values >= lo in subtree x
+    fn _query_ge(&self, x: NodeId, lo: &T) -> M::S {
+        if x.is_null() {
+            M::identity()
+        } else if self.value(x) < lo {
+            self._query_ge(self.t[x].r, lo)
+        } else {
+            let s = self._query_ge(self.t[x].l, lo);
+            let s = M::binary_operation(&s, &M::lift(self.value(x), self.t[x].w));
+            M::binary_operation(&s, &self.t[self.t[x].r].agg)
+        }
+    }
+
+    // aggregate of values <= hi in subtree x
+    fn _query_le(&self, x: NodeId, hi: &T) -> M::S {
+        if x.is_null() {
+            M::identity()
+        } else if self.value(x) > hi {
+            self._query_le(self.t[x].l, hi)
+        } else {
+            let s = M::binary_operation(
+                &self.t[self.t[x].l].agg,
+                &M::lift(self.value(x), self.t[x].w),
+            );
+            M::binary_operation(&s, &self._query_le(self.t[x].r, hi))
+        }
+    }
+
+    fn _query(&self, x: NodeId, lo: &T, hi: &T) -> M::S {
+        if x.is_null() {
+            M::identity()
+        } else if self.value(x) < lo {
+            self._query(self.t[x].r, lo, hi)
+        } else if self.value(x) > hi {
+            self._query(self.t[x].l, lo, hi)
+        } else {
+            let s = self._query_ge(self.t[x].l, lo);
+            let s = M::binary_operation(&s, &M::lift(self.value(x), self.t[x].w));
+            M::binary_operation(&s, &self._query_le(self.t[x].r, hi))
+        }
+    }
+
+    /// aggregate of all values v with lo <= v <= hi, in increasing order
+    pub fn query_range(&self, lo: &T, hi: &T) -> M::S {
+        self._query(self.root, lo, hi)
+    }
+
+    // move the values of subtree x out in order, w copies each
+    fn take_values(&mut self, x: NodeId, res: &mut Vec<T>)
+    where
+        T: Clone,
+    {
+        if x.is_null() {
+            return;
+        }
+        self.take_values(self.t[x].l, res);
+        let v = self.release(x).unwrap();
+        res.extend(std::iter::repeat_n(v, self.t[x].w as usize));
+        self.take_values(self.t[x].r, res);
+    }
+
+    /// remove all values in [lo, hi], return them in order
+    pub fn drain_range(&mut self, lo: &T, hi: &T) -> Vec<T>
+    where
+        T: Clone,
+    {
+        let mut res = vec![];
+        if lo > hi {
+            return res;
+        }
+        let (a, b) = self._split(self.root, lo, false);
+        let (b, c) = self._split(b, hi, true);
+        self.take_values(b, &mut res);
+        self.root = self._merge(a, c);
+        res
+    }
+
+    /// aggregate of all values
+    pub fn query_all(&self) -> M::S {
+        self.t[self.root].agg.clone()
+    }
+
+    // split subtree x into (< v, >= v), or (<= v, > v) if inclusive
+    fn _split(&mut self, x: NodeId, v: &T, inclusive: bool) -> (NodeId, NodeId) {
+        if x.is_null() {
+            return (NodeId::NULL, NodeId::NULL);
+        }
+        if self.value(x) < v || inclusive && self.value(x) == v {
+            let (a, b) = self._split(self.t[x].r, v, inclusive);
+            self.t[x].r = a;
+            self.pushup(x);
+            (x, b)
+        } else {
+            let (a, b) = self._split(self.t[x].l, v, inclusive);
+            self.t[x].l = b;
+            self.pushup(x);
+            (a, x)
+        }
+    }
+
+    // all values in a should be less than those in b
+    fn _merge(&mut self, a: NodeId, b: NodeId) -> NodeId {
+        if a.is_null() {
+            return b;
+        }
+        if b.is_null() {
+            return a;
+        }
+        if self.t[a].rnd < self.t[b].rnd {
+            let t = self._merge(self.t[a].r, b);
+            self.t[a].r = t;
+            self.pushup(a);
+            a
+        } else {
+            let t = self._merge(a, self.t[b].l);
+            self.t[b].l = t;
+            self.pushup(b);
+            b
+        }
+    }
+
+    // union of two subtrees of this arena, equal values add their counts
+    fn _union(&mut self, a: NodeId, b: NodeId) -> NodeId {
+        if a.is_null() {
+            return b;
+        }
+        if b.is_null() {
+            return a;
+        }
+        let (a, b) = if self.t[a].rnd < self.t[b].rnd {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        let v = self.t[a].val.take().unwrap();
+        let (lt, ge) = self._split(b, &v, false);
+        let (eq, gt) = self._split(ge, &v, true);
+        self.t[a].val = Some(v);
+        // values are distinct within b, so eq is a single node
+        if !eq.is_null() {
+            self.t[a].w += self.t[eq].w;
+            self.release(eq);
+        }
+        let l = self._union(self.t[a].l, lt);
+        let r = self._union(self.t[a].r, gt);
+        self.t[a].l = l;
+        self.t[a].r = r;
+        self.pushup(a);
+        a
+    }
+
+    // move subtree x of src into self, keeping its shape
+    fn adopt(&mut self, src: &mut Self, x: NodeId) -> NodeId {
+        if x.is_null() {
+            return NodeId::NULL;
+        }
+        let l = self.adopt(src, src.t[x].l);
+        let y = self.new_node(src.release(x).unwrap(), src.t[x].w, src.t[x].rnd);
+        let r = self.adopt(src, src.t[x].r);
+        self.t[y].l = l;
+        self.t[y].r = r;
+        self.pushup(y);
+        y
+    }
+
+    /// smallest value
+    pub fn first(&self) -> Option<&T> {
+        self.min_val()
+    }
+
+    /// largest value
+    pub fn last(&self) -> Option<&T> {
+        self.max_val()
+    }
+
+    /// remove every copy of the smallest value, return it with its count
+    pub fn pop_first(&mut self) -> Option<(T, usize)> {
+        if self.root.is_null() {
+            return None;
+        }
+        let (root, x) = self.unlink_end(self.root, false);
+        self.root = root;
+        let w = self.t[x].w as usize;
+        self.release(x).map(|v| (v, w))
+    }
+
+    /// remove every copy of the largest value, return it with its count
+    pub fn pop_last(&mut self) -> Option<(T, usize)> {
+        if self.root.is_null() {
+            return None;
+        }
+        let (root, x) = self.unlink_end(self.root, true);
+        self.root = root;
+        let w = self.t[x].w as usize;
+        self.release(x).map(|v| (v, w))
+    }
+
+    // unlink the leftmost (rightmost if last) node of nonempty subtree x,
+    // return the new subtree and that node
+    fn unlink_end(&mut self, x: NodeId, last: bool) -> (NodeId, NodeId) {
+        let (l, r) = (self.t[x].l, self.t[x].r);
+        if last {
+            if r.is_null() {
+                return (l, x);
+            }
+            let (r, y) = self.unlink_end(r, last);
+            self.t[x].r = r;
+            self.pushup(x);
+            (x, y)
+        } else {
+            if l.is_null() {
+                return (r, x);
+            }
+            let (l, y) = self.unlink_end(l, last);
+            self.t[x].l = l;
+            self.pushup(x);
+            (x, y)
+        }
+    }
+
+    fn max_val(&self) -> Option<&T> {
+        let mut x = self.root;
+        while !x.is_null() && !self.t[x].r.is_null() {
+            x = self.t[x].r;
+        }
+        (!x.is_null()).then(|| self.value(x))
+    }
+
+    fn min_val(&self) -> Option<&T> {
+        let mut x = self.root;
+        while !x.is_null() && !self.t[x].l.is_null() {
+            x = self.t[x].l;
+        }
+        (!x.is_null()).then(|| self.value(x))
+    }
+
+    /// all values in self must be less than all values in other \
+    /// the smaller treap is moved into the larger one, O(log n + min size)
+    pub fn merge(mut self, mut other: Self) -> Self {
+        debug_assert!(match (self.max_val(), other.min_val()) {
+            (Some(a), Some(b)) => a < b,
+            _ => true,
+        });
+        if self.len() >= other.len() {
+            let x = other.root;
+            let y = self.adopt(&mut other, x);
+            self.root = self._merge(self.root, y);
+            self
+        } else {
+            let x = self.root;
+            let y = other.adopt(&mut self, x);
+            other.root = other._merge(y, other.root);
+            other
+        }
+    }
+
+    /// union of two multisets, counts of equal values are added \
+    /// the smaller treap is moved into the larger one, expected O(m log(n / m))
+    pub fn union(mut a: Self, mut b: Self) -> Self {
+        if a.len() < b.len() {
+            std::mem::swap(&mut a, &mut b);
+        }
+        let x = b.root;
+        let y = a.adopt(&mut b, x);
+        a.root = a._union(a.root, y);
+        a
+    }
+
+    /// split into (values < v, values >= v) \
+    /// the smaller part is moved out, O(log n + min size)
+    pub fn split_by_value(mut self, v: &T) -> (Self, Self) {
+        let (a, b) = self._split(self.root, v, false);
+        let (keep, moved) = if self.t[a].sz >= self.t[b].sz {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        let mut other = Self::with_monoid_and_seed(self.rd.gen());
+        other.root = other.adopt(&mut self, moved);
+        self.root = keep;
+        if keep == a {
+            (self, other)
+        } else {
+            (other, self)
+        }
+    }
+}
+
+impl<T, M> Treap<T, M>
+where
+    M: Monoid<T>,
+{
+    fn value(&self, x: NodeId) -> &T {
+        self.t[x].val.as_ref().unwrap()
+    }
+
+    /// number of copies held by node x
+    pub fn weight(&self, x: NodeId) -> usize {
+        self.t[x].w as usize
+    }
+
+    /// number of values in the subtree of node x, counting duplicates
+    pub fn subtree_len(&self, x: NodeId) -> usize {
+        self.t[x].sz as usize
+    }
+
+    /// all values in order, each repeated by its count
+    pub fn into_sorted_vec(self) -> Vec<T>
+    where
+        T: Clone,
+    {
+        self.into_iter()
+            .flat_map(|(v, n)| std::iter::repeat_n(v, n))
+            .collect()
+    }
+
+    pub fn iter(&self) -> Iter<'_, T, M> {
+        let mut it = Iter {
+            treap: self,
+            stack: vec![],
+        };
+        it.push_left(self.root);
+        it
+    }
+}
+
+pub struct Iter<'a, T, M>
+where
+    M: Monoid<T>,
+{
+    treap: &'a Treap<T, M>,
+    stack: Vec<NodeId>,
+}
+
+impl<T, M> Iter<'_, T, M>
+where
+    M: Monoid<T>,
+{
+    fn push_left(&mut self, mut x: NodeId) {
+        while !x.is_null() {
+            self.stack.push(x);
+            x = self.treap.t[x].l;
+        }
+    }
+
+    fn next_node(&mut self) -> Option<NodeId> {
+        let x = self.stack.pop()?;
+        self.push_left(self.treap.t[x].r);
+        Some(x)
+    }
+}
+
+impl<'a, T, M> Iterator for Iter<'a, T, M>
+where
+    M: Monoid<T>,
+{
+    type Item = (&'a T, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let x = self.next_node()?;
+        Some((self.treap.value(x), self.treap.t[x].w as usize))
+    }
+}
+
+impl<'a, T, M> IntoIterator for &'a Treap<T, M>
+where
+    M: Monoid<T>,
+{
+    type Item = (&'a T, usize);
+    type IntoIter = Iter<'a, T, M>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T, M> IntoIterator for Treap<T, M>
+where
+    M: Monoid<T>,
+{
+    type Item = (T, usize);
+    type IntoIter = std::vec::IntoIter<(T, usize)>;
+
+    fn into_iter(mut self) -> Self::IntoIter {
+        let mut order = vec![];
+        let mut it = self.iter();
+        while let Some(x) = it.next_node() {
+            order.push(x);
+        }
+        order
+            .into_iter()
+            .map(|x| (self.t[x].val.take().unwrap(), self.t[x].w as usize))
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use random::Random;
+    use test_support::NaiveMultiset;
+
+    use crate::{AddAssignSum, Additive, ImplicitTreap, Treap, TreapMap};
+
+    // number of copies of each value in 0..100
+    fn counts(ms: &NaiveMultiset<i64>) -> Vec<usize> {
+        let mut cnt = vec![0; 100];
+        ms.as_slice().iter().for_each(|&x| cnt[x as usize] += 1);
+        cnt
+    }
+
+    #[test]
+    fn differential_test() {
+        let mut rd = Random::new(1);
+        let mut tr = Treap::<i64, Additive<i64>>::with_monoid_and_seed(2);
+        let mut ms = NaiveMultiset::new();
+        for _ in 0..20000 {
+            let v = rd.next(100) as i64;
+            match rd.next(13) {
+                0 | 1 => {
+                    let n = rd.next_bounds(1, 3) as u32;
+                    tr.insert(v, n);
+                    ms.insert(v, n as usize);
+                }
+                2 => assert_eq!(tr.del(&v, 2), ms.del(&v, 2)),
+                3 => assert_eq!(tr.rnk(&v), ms.rnk(&v)),
+                4 => {
+                    let k = rd.next(ms.len() as u64 + 2) as usize;
+                    assert_eq!(tr.kth(k), ms.kth(k));
+                }
+                5 => {
+                    assert_eq!(tr.pre(&v), ms.pre(&v));
+                    assert_eq!(tr.nxt(&v), ms.nxt(&v));
+                }
+                6 => {
+                    // split at v and check both halves before gluing them back
+                    let t = std::mem::replace(&mut tr, Treap::with_monoid_and_seed(0));
+                    let (a, b) = t.split_by_value(&v);
+                    #[cfg(feature = "validate")]
+                    {
+                        a.validate();
+                        b.validate();
+                    }
+                    let (lo, hi) = ms.as_slice().split_at(ms.rnk(&v) - 1);
+                    assert_eq!((a.len(), b.len()), (lo.len(), hi.len()));
+                    assert_eq!(a.query_all(), lo.iter().sum::<i64>());
+                    assert_eq!(b.query_all(), hi.iter().sum::<i64>());
+                    let cnt = counts(&ms);
+                    assert!((0..100).all(|x| {
+                        let (p, q) = if x < v { (&a, &b) } else { (&b, &a) };
+                        p.count(&x) == cnt[x as usize] && q.count(&x) == 0
+                    }));
+                    tr = a.merge(b);
+                }
+                7 => {
+                    // overlapping values add their counts, in both argument orders
+                    let mut o = Treap::with_monoid_and_seed(rd.gen());
+                    for _ in 0..rd.next(20) {
+                        let x = rd.next(100) as i64;
+                        o.insert(x, 1);
+                        ms.insert(x, 1);
+                    }
+                    let t = std::mem::replace(&mut tr, Treap::with_monoid_and_seed(0));
+                    tr = if rd.next(2) == 0 {
+                        Treap::union(t, o)
+                    } else {
+                        Treap::union(o, t)
+                    };
+                    let cnt = counts(&ms);
+                    assert!((0..100).all(|x| tr.count(&x) == cnt[x as usize]));
+                    assert_eq!(tr.query_all(), ms.as_slice().iter().sum::<i64>());
+                }
+                8 => {
+                    assert_eq!(tr.erase_all(&v), ms.del(&v, usize::MAX));
+                    assert_eq!((tr.count(&v), tr.idx(&v)), (0, None));
+                }
+                9 => {
+                    // hi may fall below v, which drains nothing
+                    let hi = v + rd.next(40) as i64 - 10;
+                    let r = ms.range(&v, &hi);
+                    assert_eq!(tr.drain_range(&v, &hi), r);
+                    r.iter().for_each(|x| assert_eq!(ms.del(x, 1), 1));
+                    assert_eq!(tr.count_range(&v, &hi), 0);
+                }
+                10 => {
+                    assert_eq!(tr.first(), ms.kth(1));
+                    assert_eq!(tr.last(), ms.kth(ms.len()));
+                    let (p, e) = if rd.next(2) == 0 {
+                        (tr.pop_first(), ms.kth(1).copied())
+                    } else {
+                        (tr.pop_last(), ms.kth(ms.len()).copied())
+                    };
+                    assert_eq!(p, e.map(|x| (x, ms.count(&x))));
+                    if let Some(x) = e {
+                        ms.del(&x, usize::MAX);
+                    }
+                }
+                _ => {
+                    let hi = v + rd.next(30) as i64;
+                    let r = ms.range(&v, &hi);
+                    assert_eq!(tr.count_range(&v, &hi), r.len());
+                    assert_eq!(tr.query_range(&v, &hi), r.iter().sum::<i64>());
+                }
+            }
+            #[cfg(feature = "validate")]
+            tr.validate();
+            assert_eq!(tr.len(), ms.len());
+        }
+        let runs = ms.as_slice().chunk_by(|a, b| a == b);
+        assert!(tr.iter().eq(runs.map(|r| (&r[0], r.len()))));
+        assert!((&tr).into_iter().eq(tr.iter()));
+        assert_eq!(tr.into_sorted_vec(), ms.as_slice());
+        assert!(Treap::<i64>::with_seed(1).into_sorted_vec().is_empty());
+    }
+
+    #[test]
+    fn from_sorted_test() {
+        let mut rd = Random::new(2);
+        for n in 0..200 {
+            // few distinct values so equal runs get merged into one node
+            let mut a: Vec<i64> = (0..n).map(|_| rd.next(n / 4 + 1) as i64).collect();
+            a.sort();
+            let mut one = Treap::<i64, Additive<i64>>::with_monoid_and_seed(n);
+            a.iter().for_each(|&x| one.insert(x, 1));
+            let tr = Treap::<i64, Additive<i64>>::from_sorted_with_monoid(a.iter().copied());
+            let plain = Treap::from_sorted(a.iter().copied());
+            #[cfg(feature = "validate")]
+            {
+                tr.validate();
+                plain.validate();
+            }
+            assert_eq!((tr.len(), plain.len()), (a.len(), a.len()));
+            assert_eq!(tr.query_all(), one.query_all());
+            assert!((1..=a.len()).all(|k| tr.kth(k) == Some(&a[k - 1])));
+            assert!(tr.iter().eq(one.iter()));
+            assert!(plain.iter().eq(one.iter()));
+        }
+    }
+
+    #[test]
+    fn implicit_test() {
+        let mut rd = Random::new(3);
+        // tr takes every operation, plain only the ones moving elements around
+        let mut tr = ImplicitTreap::<i64, AddAssignSum<i64>>::with_action_and_seed(3);
+        let mut plain = ImplicitTreap::with_seed(4);
+        let (mut a, mut b): (Vec<i64>, Vec<i64>) = (vec![], vec![]);
+        for _ in 0..5000 {
+            let n = a.len() as u64;
+            let l = rd.next(n + 1) as usize;
+            let r = rd.next_bounds(l as u64, n) as usize;
+            let v = rd.next(2000) as i64 - 1000;
+            match rd.next(9) {
+                0 | 1 => {
+                    tr.insert_at(l, v);
+                    plain.insert_at(l, v);
+                    a.insert(l, v);
+                    b.insert(l, v);
+                }
+                2 if l < a.len() => {
+                    assert_eq!(tr.erase_at(l), a.remove(l));
+                    assert_eq!(plain.erase_at(l), b.remove(l));
+                }
+                3 => {
+                    tr.reverse(l, r);
+                    plain.reverse(l, r);
+                    a[l..r].reverse();
+                    b[l..r].reverse();
+                }
+                4 => {
+                    // split, reverse and update one side on its own, then glue back
+                    let (mut x, y) = tr.split_at(l);
+                    assert_eq!((x.len(), y.len()), (l, a.len() - l));
+                    x.reverse(0, l);
+                    x.add(0, l, v);
+                    a[..l].reverse();
+                    a[..l].iter_mut().for_each(|x| *x += v);
+                    tr = x.merge(y);
+                    let (mut x, y) = plain.split_at(l);
+                    x.reverse(0, l);
+                    b[..l].reverse();
+                    plain = y.merge(x);
+                    b.rotate_left(l);
+                }
+                5 => {
+                    tr.add(l, r, v);
+                    a[l..r].iter_mut().for_each(|x| *x += v);
+                }
+                6 => {
+                    tr.assign(l, r, v);
+                    a[l..r].fill(v);
+                }
+                7 => {
+                    assert_eq!(tr.sum(l, r), a[l..r].iter().sum::<i64>());
+                    assert_eq!(tr.query_all(), a.iter().sum::<i64>());
+                }
+                _ if l < a.len() => {
+                    assert_eq!(*tr.value_at(l), a[l]);
+                    assert_eq!(plain.get(l), Some(&b[l]));
+                }
+                _ => {}
+            }
+            assert_eq!((tr.len(), plain.len()), (a.len(), b.len()));
+        }
+        assert!((0..a.len()).all(|i| *tr.value_at(i) == a[i]));
+        assert!((0..b.len()).all(|i| plain.get(i) == Some(&b[i])));
+        assert_eq!(plain.get(b.len()), None);
+    }
+
+    #[test]
+    fn treap_map_test() {
+        let mut rd = Random::new(5);
+        let mut mp = TreapMap::with_seed(5);
+        let mut bt = BTreeMap::new();
+        for _ in 0..20000 {
+            // few keys, so removed nodes get reused and rotations move keys around
+            let (k, v) = (rd.next(60) as i32, rd.next(1000));
+            match rd.next(6) {
+                0 | 1 => assert_eq!(mp.insert(k, v), bt.insert(k, v)),
+                2 => assert_eq!(mp.remove(&k), bt.remove(&k)),
+                3 => {
+                    if let Some(x) = mp.get_mut(&k) {
+                        *x += v;
+                    }
+                    if let Some(x) = bt.get_mut(&k) {
+                        *x += v;
+                    }
+                }
+                4 => {
+                    let i = rd.next(bt.len() as u64 + 1) as usize;
+                    assert_eq!(mp.kth_entry(i), bt.iter().nth(i));
+                    assert_eq!(mp.rank_of_key(&k), bt.range(..k).count());
+                }
+                _ => {
+                    assert_eq!(mp.get(&k), bt.get(&k));
+                    assert_eq!(mp.contains_key(&k), bt.contains_key(&k));
+                }
+            }
+            assert_eq!(mp.len(), bt.len());
+        }
+        // every value is still found through its key after all the rotations
+        assert!(bt.iter().all(|(k, v)| mp.get(k) == Some(v)));
+        assert!(bt
+            .iter()
+            .enumerate()
+            .all(|(i, e)| mp.kth_entry(i) == Some(e)));
+    }
+
+    #[cfg(feature = "debug")]
+    #[test]
+    fn debug_test() {
+        let mut tr = Treap::<i64, Additive<i64>>::with_monoid_and_seed(1);
+        tr.insert(5, 2);
+        tr.insert(3, 1);
+        // the rnd priorities are fixed by the seed
+        assert_eq!(
+            format!("{:?}", tr),
+            concat!(
+                "Treap (len 3)\n",
+                "3 x1 ranks [0, 3) rnd 588839502 agg 13\n",
+                "  5 x2 ranks [1, 3) rnd 3144183656 agg 10\n",
+            )
+        );
+    }
 }
@@ -0,0 +1,88 @@
+use crate::Treap;
+
+/// ordered map with order statistics, ranks are 0-indexed \
+/// keys live in a `Treap`, values are stored by the node index of their key
+pub struct TreapMap<K, V> {
+    keys: Treap<K>,
+    vals: Vec<Option<V>>,
+}
+
+impl<K, V> Default for TreapMap<K, V>
+where
+    K: Ord,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V> TreapMap<K, V>
+where
+    K: Ord,
+{
+    pub fn new() -> Self {
+        Self {
+            keys: Treap::new(),
+            vals: vec![],
+        }
+    }
+
+    /// same seed, same tree shapes
+    pub fn with_seed(seed: u64) -> Self {
+        Self {
+            keys: Treap::with_seed(seed),
+            vals: vec![],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    pub fn contains_key(&self, k: &K) -> bool {
+        self.keys.idx(k).is_some()
+    }
+
+    /// return the old value of k if there was one
+    pub fn insert(&mut self, k: K, v: V) -> Option<V> {
+        if let Some(x) = self.keys.idx(&k) {
+            return self.vals[x.index()].replace(v);
+        }
+        // rotations and deletions of other keys never move a key to another node
+        let x = self.keys.insert_node(k, 1).index();
+        if self.vals.len() <= x {
+            self.vals.resize_with(x + 1, || None);
+        }
+        self.vals[x] = Some(v);
+        None
+    }
+
+    pub fn get(&self, k: &K) -> Option<&V> {
+        self.vals[self.keys.idx(k)?.index()].as_ref()
+    }
+
+    pub fn get_mut(&mut self, k: &K) -> Option<&mut V> {
+        self.vals[self.keys.idx(k)?.index()].as_mut()
+    }
+
+    pub fn remove(&mut self, k: &K) -> Option<V> {
+        let x = self.keys.idx(k)?;
+        self.keys.del(k, 1);
+        self.vals[x.index()].take()
+    }
+
+    /// the entry with exactly k smaller keys
+    pub fn kth_entry(&self, k: usize) -> Option<(&K, &V)> {
+        let x = self.keys._kth(self.keys.root, k + 1)?;
+        Some((self.keys.value(x), self.vals[x.index()].as_ref().unwrap()))
+    }
+
+    /// number of keys less than k
+    pub fn rank_of_key(&self, k: &K) -> usize {
+        self.keys.count_below(k, false)
+    }
+}
@@ -0,0 +1,85 @@
+use std::marker::PhantomData;
+use std::ops::Add;
+
+/// aggregate maintained on every subtree of `Treap`, `()` maintains nothing
+pub trait Monoid<T> {
+    type S: Clone;
+    fn identity() -> Self::S;
+    fn binary_operation(a: &Self::S, b: &Self::S) -> Self::S;
+    /// aggregate of n copies of v
+    fn lift(v: &T, n: u32) -> Self::S;
+}
+
+impl<T> Monoid<T> for () {
+    type S = ();
+    fn identity() -> Self::S {}
+    fn binary_operation(_: &Self::S, _: &Self::S) -> Self::S {}
+    fn lift(_: &T, _: u32) -> Self::S {}
+}
+
+pub struct Additive<T>(PhantomData<fn() -> T>);
+impl<T> Monoid<T> for Additive<T>
+where
+    T: Clone + Default + Add<Output = T>,
+{
+    type S = T;
+    fn identity() -> Self::S {
+        T::default()
+    }
+    fn binary_operation(a: &Self::S, b: &Self::S) -> Self::S {
+        a.clone() + b.clone()
+    }
+    fn lift(v: &T, mut n: u32) -> Self::S {
+        let (mut res, mut base) = (T::default(), v.clone());
+        while n > 0 {
+            if n & 1 == 1 {
+                res = res + base.clone();
+            }
+            base = base.clone() + base;
+            n >>= 1;
+        }
+        res
+    }
+}
+
+/// None for an empty range
+pub struct Min<T>(PhantomData<fn() -> T>);
+impl<T> Monoid<T> for Min<T>
+where
+    T: Clone + Ord,
+{
+    type S = Option<T>;
+    fn identity() -> Self::S {
+        None
+    }
+    fn binary_operation(a: &Self::S, b: &Self::S) -> Self::S {
+        match (a, b) {
+            (Some(a), Some(b)) => Some(a.min(b).clone()),
+            _ => a.clone().or_else(|| b.clone()),
+        }
+    }
+    fn lift(v: &T, _: u32) -> Self::S {
+        Some(v.clone())
+    }
+}
+
+/// None for an empty range
+pub struct Max<T>(PhantomData<fn() -> T>);
+impl<T> Monoid<T> for Max<T>
+where
+    T: Clone + Ord,
+{
+    type S = Option<T>;
+    fn identity() -> Self::S {
+        None
+    }
+    fn binary_operation(a: &Self::S, b: &Self::S) -> Self::S {
+        match (a, b) {
+            (Some(a), Some(b)) => Some(a.max(b).clone()),
+            _ => a.clone().or_else(|| b.clone()),
+        }
+    }
+    fn lift(v: &T, _: u32) -> Self::S {
+        Some(v.clone())
+    }
+}
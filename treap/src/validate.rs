@@ -0,0 +1,47 @@
+use arena::NodeId;
+
+use crate::{Monoid, Treap};
+
+impl<T, M> Treap<T, M>
+where
+    T: Ord,
+    M: Monoid<T>,
+{
+    // check subtree x, return its smallest and largest nodes
+    fn check(&self, x: NodeId) -> (NodeId, NodeId) {
+        let (l, r) = (self.t[x].l, self.t[x].r);
+        assert!(self.t[x].val.is_some(), "node {} has no value", x);
+        assert!(self.t[x].w > 0, "node {} has count 0", x);
+        assert_eq!(
+            self.t[x].sz,
+            self.t[l].sz + self.t[r].sz + self.t[x].w,
+            "sz of node {} is wrong",
+            x
+        );
+        let (mut lo, mut hi) = (x, x);
+        if !l.is_null() {
+            assert!(self.t[l].rnd >= self.t[x].rnd, "heap order broken at {}", x);
+            let (a, b) = self.check(l);
+            assert!(self.value(b) < self.value(x), "value order broken at {}", x);
+            lo = a;
+        }
+        if !r.is_null() {
+            assert!(self.t[r].rnd >= self.t[x].rnd, "heap order broken at {}", x);
+            let (a, b) = self.check(r);
+            assert!(self.value(x) < self.value(a), "value order broken at {}", x);
+            hi = b;
+        }
+        (lo, hi)
+    }
+
+    /// panic if the heap order, value order or subtree sizes are broken
+    pub fn validate(&self) {
+        assert_eq!(self.t[NodeId::NULL].sz, 0, "sentinel node changed");
+        if !self.root.is_null() {
+            self.check(self.root);
+        }
+        for &x in self.t.freed() {
+            assert!(self.t[x].val.is_none(), "free node {} in use", x);
+        }
+    }
+}
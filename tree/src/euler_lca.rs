@@ -0,0 +1,57 @@
+use sparse_table::SparseTable;
+
+/// lca by a sparse table over the Euler tour, min of (depth, vertex) \
+/// O(n log n) to build, O(1) per query
+pub struct EulerLca {
+    depth: Vec<usize>,
+    first: Vec<usize>, // first[v] is the first position of v in the tour
+    st: SparseTable<sparse_table::Min<(usize, usize)>>,
+}
+
+impl EulerLca {
+    pub fn new(adj: &[Vec<usize>], root: usize) -> Self {
+        let n = adj.len();
+        let (mut depth, mut first) = (vec![0; n], vec![usize::MAX; n]);
+        let mut tour = Vec::with_capacity(2 * n);
+        first[root] = 0;
+        tour.push((0, root));
+        // (vertex, index of the next edge to visit)
+        let mut stack = vec![(root, 0)];
+        while let Some(&(u, i)) = stack.last() {
+            if let Some(&v) = adj[u].get(i) {
+                stack.last_mut().unwrap().1 += 1;
+                if first[v] == usize::MAX {
+                    depth[v] = depth[u] + 1;
+                    first[v] = tour.len();
+                    tour.push((depth[v], v));
+                    stack.push((v, 0));
+                }
+                continue;
+            }
+            stack.pop();
+            // back in the parent after leaving u
+            if let Some(&(p, _)) = stack.last() {
+                tour.push((depth[p], p));
+            }
+        }
+        Self {
+            depth,
+            first,
+            st: SparseTable::new(tour),
+        }
+    }
+
+    pub fn depth(&self, v: usize) -> usize {
+        self.depth[v]
+    }
+
+    pub fn lca(&self, u: usize, v: usize) -> usize {
+        let (a, b) = (self.first[u], self.first[v]);
+        self.st.query(a.min(b)..=a.max(b)).1
+    }
+
+    /// number of edges between u and v
+    pub fn dist(&self, u: usize, v: usize) -> usize {
+        self.depth[u] + self.depth[v] - 2 * self.depth[self.lca(u, v)]
+    }
+}
@@ -0,0 +1,144 @@
+use std::ops::{Add, Sub};
+
+use fenwick_tree::Fenwick;
+
+use crate::Lifting;
+
+/// entry and exit times of a DFS from root, times are 1..=n \
+/// the subtree of v is exactly the times [tin(v), tout(v)]
+pub struct EulerTour {
+    tin: Vec<usize>,
+    tout: Vec<usize>,
+}
+
+impl EulerTour {
+    pub fn new(adj: &[Vec<usize>], root: usize) -> Self {
+        let n = adj.len();
+        let (mut tin, mut tout) = (vec![0; n], vec![0; n]);
+        let mut t = 1;
+        tin[root] = t;
+        // (vertex, index of the next edge to visit)
+        let mut stack = vec![(root, 0)];
+        while let Some(&(u, i)) = stack.last() {
+            if let Some(&v) = adj[u].get(i) {
+                stack.last_mut().unwrap().1 += 1;
+                if tin[v] == 0 {
+                    t += 1;
+                    tin[v] = t;
+                    stack.push((v, 0));
+                }
+                continue;
+            }
+            tout[u] = t;
+            stack.pop();
+        }
+        Self { tin, tout }
+    }
+
+    pub fn tin(&self, v: usize) -> usize {
+        self.tin[v]
+    }
+
+    pub fn tout(&self, v: usize) -> usize {
+        self.tout[v]
+    }
+
+    /// whether u is an ancestor of v, u is an ancestor of itself
+    pub fn is_ancestor(&self, u: usize, v: usize) -> bool {
+        self.tin[u] <= self.tin[v] && self.tout[v] <= self.tout[u]
+    }
+}
+
+/// vertex values with point add and subtree sum
+pub struct SubtreeSum<T> {
+    et: EulerTour,
+    fw: Fenwick<T>,
+}
+
+impl<T> SubtreeSum<T>
+where
+    T: Copy + Default + Add<Output = T> + Sub<Output = T>,
+{
+    /// all values start at zero
+    pub fn new(adj: &[Vec<usize>], root: usize) -> Self {
+        Self {
+            et: EulerTour::new(adj, root),
+            fw: Fenwick::new(adj.len()),
+        }
+    }
+
+    pub fn add(&mut self, v: usize, x: T) {
+        self.fw.add(self.et.tin(v), x);
+    }
+
+    pub fn subtree_sum(&self, v: usize) -> T {
+        self.fw.query(self.et.tin(v), self.et.tout(v))
+    }
+}
+
+/// vertex values with subtree add and point query
+pub struct SubtreeAdd<T> {
+    et: EulerTour,
+    fw: Fenwick<T>, // differences over the times
+}
+
+impl<T> SubtreeAdd<T>
+where
+    T: Copy + Default + Add<Output = T> + Sub<Output = T>,
+{
+    /// all values start at zero
+    pub fn new(adj: &[Vec<usize>], root: usize) -> Self {
+        Self {
+            et: EulerTour::new(adj, root),
+            fw: Fenwick::new(adj.len() + 1),
+        }
+    }
+
+    /// add x to every vertex in the subtree of v
+    pub fn add_subtree(&mut self, v: usize, x: T) {
+        self.fw.add(self.et.tin(v), x);
+        self.fw.add(self.et.tout(v) + 1, T::default() - x);
+    }
+
+    pub fn get(&self, v: usize) -> T {
+        self.fw.sum(self.et.tin(v))
+    }
+}
+
+/// vertex values with path add and point query, by differences on the tree \
+/// the value of v is the sum of the differences in its subtree
+pub struct PathAdd<T> {
+    et: EulerTour,
+    lift: Lifting<()>,
+    fw: Fenwick<T>,
+}
+
+impl<T> PathAdd<T>
+where
+    T: Copy + Default + Add<Output = T> + Sub<Output = T>,
+{
+    /// all values start at zero
+    pub fn new(adj: &[Vec<usize>], root: usize) -> Self {
+        Self {
+            et: EulerTour::new(adj, root),
+            lift: Lifting::new(adj, root),
+            fw: Fenwick::new(adj.len()),
+        }
+    }
+
+    /// add x to every vertex on the path between u and v, both included
+    pub fn add_path(&mut self, u: usize, v: usize, x: T) {
+        let l = self.lift.lca(u, v);
+        let neg = T::default() - x;
+        self.fw.add(self.et.tin(u), x);
+        self.fw.add(self.et.tin(v), x);
+        self.fw.add(self.et.tin(l), neg);
+        if let Some(p) = self.lift.parent(l) {
+            self.fw.add(self.et.tin(p), neg);
+        }
+    }
+
+    pub fn get(&self, v: usize) -> T {
+        self.fw.query(self.et.tin(v), self.et.tout(v))
+    }
+}
@@ -0,0 +1,166 @@
+mod euler_lca;
+mod euler_tour;
+mod lifting;
+mod monoid;
+mod rerooting;
+
+pub use euler_lca::EulerLca;
+pub use euler_tour::{EulerTour, PathAdd, SubtreeAdd, SubtreeSum};
+pub use lifting::Lifting;
+pub use monoid::{Additive, Max, Min, Monoid};
+pub use rerooting::rerooting;
+
+#[cfg(test)]
+mod test {
+    use crate::{rerooting, Additive, EulerLca, Lifting, Max, PathAdd, SubtreeAdd, SubtreeSum};
+    use random::Random;
+
+    #[test]
+    fn lifting_test() {
+        let mut rd = Random::new(5);
+        for n in [1, 2, 10, 60] {
+            let mut adj = vec![vec![]; n];
+            let mut par = vec![(0, 0); n];
+            for v in 1..n {
+                let (p, w) = (rd.next(v as u64) as usize, rd.next(100) as i64);
+                adj[p].push((v, w));
+                adj[v].push((p, w));
+                par[v] = (p, w);
+            }
+            let mx = Lifting::<i64, Max<i64>>::with_weights(&adj, 0);
+            let sm = Lifting::<i64, Additive<i64>>::with_weights(&adj, 0);
+            let path = |mut u: usize| {
+                let mut res = vec![u];
+                while u != 0 {
+                    u = par[u].0;
+                    res.push(u);
+                }
+                res
+            };
+            for u in 0..n {
+                let pu = path(u);
+                for k in 0..=pu.len() {
+                    assert_eq!(mx.kth_ancestor(u, k), pu.get(k).copied());
+                }
+                for v in 0..n {
+                    let pv = path(v);
+                    let l = *pu.iter().find(|x| pv.contains(x)).unwrap();
+                    assert_eq!(mx.lca(u, v), l);
+                    let ws: Vec<i64> = [&pu, &pv]
+                        .iter()
+                        .flat_map(|p| p.iter().take_while(|&&x| x != l).map(|&x| par[x].1))
+                        .collect();
+                    assert_eq!(mx.query_path(u, v), ws.iter().max().copied());
+                    assert_eq!(sm.query_path(u, v), ws.iter().sum::<i64>());
+                    assert_eq!(sm.dist(u, v), ws.len());
+                }
+            }
+        }
+        let l = Lifting::new(&[vec![1], vec![0, 2], vec![1]], 1);
+        assert_eq!((l.lca(0, 2), l.parent(1), l.depth(2)), (1, None, 1));
+    }
+
+    #[test]
+    fn euler_tour_test() {
+        let mut rd = Random::new(8);
+        let n = 80;
+        let mut adj = vec![vec![]; n];
+        let mut par = vec![0; n];
+        for v in 1..n {
+            par[v] = rd.next(v as u64) as usize;
+            adj[par[v]].push(v);
+            adj[v].push(par[v]);
+        }
+        // u is in the subtree of v, parents have smaller labels
+        let inside = |mut u: usize, v: usize| {
+            while u > v {
+                u = par[u];
+            }
+            u == v
+        };
+        let (mut ss, mut sa, mut pa) = (
+            SubtreeSum::new(&adj, 0),
+            SubtreeAdd::new(&adj, 0),
+            PathAdd::new(&adj, 0),
+        );
+        let (mut a, mut b, mut c) = (vec![0i64; n], vec![0i64; n], vec![0i64; n]);
+        for _ in 0..300 {
+            let (u, v, x) = (
+                rd.next(n as u64) as usize,
+                rd.next(n as u64) as usize,
+                rd.next(100) as i64 - 50,
+            );
+            ss.add(u, x);
+            a[u] += x;
+            sa.add_subtree(u, x);
+            pa.add_path(u, v, x);
+            for (w, y) in b.iter_mut().enumerate() {
+                if inside(w, u) {
+                    *y += x;
+                }
+            }
+            let l = (0..n)
+                .rev()
+                .find(|&w| inside(u, w) && inside(v, w))
+                .unwrap();
+            for (w, y) in c.iter_mut().enumerate() {
+                if inside(w, l) && (inside(u, w) || inside(v, w)) {
+                    *y += x;
+                }
+            }
+            let w = rd.next(n as u64) as usize;
+            let sub: i64 = (0..n).filter(|&y| inside(y, w)).map(|y| a[y]).sum();
+            assert_eq!((ss.subtree_sum(w), sa.get(w), pa.get(w)), (sub, b[w], c[w]));
+        }
+    }
+
+    #[test]
+    fn euler_lca_test() {
+        let mut rd = Random::new(7);
+        for n in [1, 2, 30, 200] {
+            let mut adj = vec![vec![]; n];
+            for v in 1..n {
+                let p = rd.next(v as u64) as usize;
+                adj[p].push(v);
+                adj[v].push(p);
+            }
+            let root = rd.next(n as u64) as usize;
+            let (e, l) = (EulerLca::new(&adj, root), Lifting::new(&adj, root));
+            for u in 0..n {
+                for v in 0..n {
+                    assert_eq!((e.lca(u, v), e.dist(u, v)), (l.lca(u, v), l.dist(u, v)));
+                }
+                assert_eq!(e.depth(u), l.depth(u));
+            }
+        }
+    }
+
+    #[test]
+    fn rerooting_test() {
+        let mut rd = Random::new(11);
+        for n in [1, 2, 40] {
+            let mut adj = vec![vec![]; n];
+            for v in 1..n {
+                let p = rd.next(v as u64) as usize;
+                adj[p].push(v);
+                adj[v].push(p);
+            }
+            // (sum of distances, subtree size) and the height of every root
+            let dist = rerooting(
+                &adj,
+                (0usize, 0usize),
+                |a, b| (a.0 + b.0, a.1 + b.1),
+                |x, _, _| (x.0 + x.1, x.1),
+                |x, _| (x.0, x.1 + 1),
+            );
+            let height = rerooting(&adj, 0usize, |a, b| *a.max(b), |x, _, _| x + 1, |x, _| *x);
+            let l = Lifting::new(&adj, 0);
+            for r in 0..n {
+                let d: Vec<usize> = (0..n).map(|v| l.dist(r, v)).collect();
+                assert_eq!(dist[r], (d.iter().sum(), n));
+                assert_eq!(height[r], *d.iter().max().unwrap());
+            }
+        }
+        assert!(rerooting(&[], 0, |a, b| a + b, |x, _, _| *x, |x, _| *x).is_empty());
+    }
+}
@@ -0,0 +1,135 @@
+use std::marker::PhantomData;
+
+use crate::Monoid;
+
+/// binary lifting on a tree rooted at root, edge weights aggregated by the monoid M \
+/// O(n log n) to build, O(log n) per query
+pub struct Lifting<T, M = ()>
+where
+    M: Monoid<T>,
+{
+    depth: Vec<usize>,
+    up: Vec<Vec<usize>>, // up[j][v] is the 2^j-th ancestor of v, or the root
+    agg: Vec<Vec<M::S>>, // agg[j][v] is the product of the edges from v to up[j][v]
+    _t: PhantomData<fn() -> T>,
+}
+
+impl Lifting<()> {
+    /// unweighted tree given by adjacency lists
+    pub fn new(adj: &[Vec<usize>], root: usize) -> Self {
+        let adj: Vec<Vec<(usize, ())>> = adj
+            .iter()
+            .map(|e| e.iter().map(|&v| (v, ())).collect())
+            .collect();
+        Self::with_weights(&adj, root)
+    }
+}
+
+impl<T, M> Lifting<T, M>
+where
+    M: Monoid<T>,
+{
+    /// adj\[u\] holds (v, w) for the edge u - v of weight w, both directions present
+    pub fn with_weights(adj: &[Vec<(usize, T)>], root: usize) -> Self {
+        let n = adj.len();
+        let mut depth = vec![0; n];
+        let mut parent = vec![root; n];
+        let mut a0 = vec![M::identity(); n];
+        let mut seen = vec![false; n];
+        seen[root] = true;
+        let mut stack = vec![root];
+        while let Some(u) = stack.pop() {
+            for (v, w) in &adj[u] {
+                if !seen[*v] {
+                    seen[*v] = true;
+                    depth[*v] = depth[u] + 1;
+                    parent[*v] = u;
+                    a0[*v] = M::lift(w);
+                    stack.push(*v);
+                }
+            }
+        }
+        let (mut up, mut agg) = (vec![parent], vec![a0]);
+        let lg = usize::BITS - n.max(1).leading_zeros();
+        for j in 1..lg as usize {
+            let (pu, pa) = (&up[j - 1], &agg[j - 1]);
+            let nu = (0..n).map(|v| pu[pu[v]]).collect();
+            let na = (0..n)
+                .map(|v| M::binary_operation(&pa[v], &pa[pu[v]]))
+                .collect();
+            up.push(nu);
+            agg.push(na);
+        }
+        Self {
+            depth,
+            up,
+            agg,
+            _t: PhantomData,
+        }
+    }
+
+    /// number of edges from the root
+    pub fn depth(&self, v: usize) -> usize {
+        self.depth[v]
+    }
+
+    pub fn parent(&self, v: usize) -> Option<usize> {
+        (self.depth[v] > 0).then(|| self.up[0][v])
+    }
+
+    // climb k edges, with the product of the edges passed
+    fn climb(&self, mut v: usize, k: usize) -> (usize, M::S) {
+        let mut s = M::identity();
+        for j in 0..self.up.len() {
+            if k >> j & 1 == 1 {
+                s = M::binary_operation(&s, &self.agg[j][v]);
+                v = self.up[j][v];
+            }
+        }
+        (v, s)
+    }
+
+    /// the ancestor k edges above v, None if k > depth(v)
+    pub fn kth_ancestor(&self, v: usize, k: usize) -> Option<usize> {
+        (k <= self.depth[v]).then(|| self.climb(v, k).0)
+    }
+
+    // lca of u and v, with the product of the edges on the path
+    fn meet(&self, u: usize, v: usize) -> (usize, M::S) {
+        let (u, v) = if self.depth[u] < self.depth[v] {
+            (v, u)
+        } else {
+            (u, v)
+        };
+        let (mut u, mut s) = self.climb(u, self.depth[u] - self.depth[v]);
+        let mut v = v;
+        if u == v {
+            return (u, s);
+        }
+        for j in (0..self.up.len()).rev() {
+            if self.up[j][u] != self.up[j][v] {
+                s = M::binary_operation(&s, &self.agg[j][u]);
+                s = M::binary_operation(&s, &self.agg[j][v]);
+                u = self.up[j][u];
+                v = self.up[j][v];
+            }
+        }
+        s = M::binary_operation(&s, &self.agg[0][u]);
+        s = M::binary_operation(&s, &self.agg[0][v]);
+        (self.up[0][u], s)
+    }
+
+    pub fn lca(&self, u: usize, v: usize) -> usize {
+        self.meet(u, v).0
+    }
+
+    /// number of edges between u and v
+    pub fn dist(&self, u: usize, v: usize) -> usize {
+        self.depth[u] + self.depth[v] - 2 * self.depth[self.lca(u, v)]
+    }
+
+    /// product of the edge weights on the path between u and v
+    pub fn query_path(&self, u: usize, v: usize) -> M::S {
+        self.meet(u, v).1
+    }
+}
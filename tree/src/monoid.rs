@@ -0,0 +1,77 @@
+use std::marker::PhantomData;
+use std::ops::Add;
+
+/// aggregate of edge weights along a path, `()` aggregates nothing \
+/// must be commutative, path products are not taken in path order
+pub trait Monoid<T> {
+    type S: Clone;
+    fn identity() -> Self::S;
+    fn binary_operation(a: &Self::S, b: &Self::S) -> Self::S;
+    fn lift(v: &T) -> Self::S;
+}
+
+impl<T> Monoid<T> for () {
+    type S = ();
+    fn identity() -> Self::S {}
+    fn binary_operation(_: &Self::S, _: &Self::S) -> Self::S {}
+    fn lift(_: &T) -> Self::S {}
+}
+
+pub struct Additive<T>(PhantomData<fn() -> T>);
+impl<T> Monoid<T> for Additive<T>
+where
+    T: Clone + Default + Add<Output = T>,
+{
+    type S = T;
+    fn identity() -> Self::S {
+        T::default()
+    }
+    fn binary_operation(a: &Self::S, b: &Self::S) -> Self::S {
+        a.clone() + b.clone()
+    }
+    fn lift(v: &T) -> Self::S {
+        v.clone()
+    }
+}
+
+/// None for an empty path
+pub struct Min<T>(PhantomData<fn() -> T>);
+impl<T> Monoid<T> for Min<T>
+where
+    T: Clone + Ord,
+{
+    type S = Option<T>;
+    fn identity() -> Self::S {
+        None
+    }
+    fn binary_operation(a: &Self::S, b: &Self::S) -> Self::S {
+        match (a, b) {
+            (Some(a), Some(b)) => Some(a.min(b).clone()),
+            _ => a.clone().or_else(|| b.clone()),
+        }
+    }
+    fn lift(v: &T) -> Self::S {
+        Some(v.clone())
+    }
+}
+
+/// None for an empty path
+pub struct Max<T>(PhantomData<fn() -> T>);
+impl<T> Monoid<T> for Max<T>
+where
+    T: Clone + Ord,
+{
+    type S = Option<T>;
+    fn identity() -> Self::S {
+        None
+    }
+    fn binary_operation(a: &Self::S, b: &Self::S) -> Self::S {
+        match (a, b) {
+            (Some(a), Some(b)) => Some(a.max(b).clone()),
+            _ => a.clone().or_else(|| b.clone()),
+        }
+    }
+    fn lift(v: &T) -> Self::S {
+        Some(v.clone())
+    }
+}
@@ -0,0 +1,74 @@
+/// the tree DP value for every root at once, O(n) calls of each closure \
+/// with root r, dp(v) = add_vertex(merge of add_edge(dp(c), v, c) over children c, v),
+/// merge must be associative and commutative with identity as its unit \
+/// return dp(r) with r as the root, for every r
+pub fn rerooting<S, M, E, V>(
+    adj: &[Vec<usize>],
+    identity: S,
+    merge: M,
+    add_edge: E,
+    add_vertex: V,
+) -> Vec<S>
+where
+    S: Clone,
+    M: Fn(&S, &S) -> S,
+    E: Fn(&S, usize, usize) -> S,
+    V: Fn(&S, usize) -> S,
+{
+    let n = adj.len();
+    if n == 0 {
+        return vec![];
+    }
+    // BFS order from 0, so parents come before children
+    let mut fa = vec![n; n];
+    let mut ord = vec![0];
+    // mark 0 as seen until the BFS is done
+    fa[0] = 0;
+    for i in 0..n {
+        let u = ord[i];
+        for &v in &adj[u] {
+            if fa[v] == n {
+                fa[v] = u;
+                ord.push(v);
+            }
+        }
+    }
+    fa[0] = n;
+    // down[v] = dp(v) rooted at 0
+    let mut down: Vec<Option<S>> = vec![None; n];
+    for &u in ord.iter().rev() {
+        let acc = adj[u]
+            .iter()
+            .filter(|&&v| v != fa[u])
+            .fold(identity.clone(), |acc, &v| {
+                merge(&acc, &add_edge(down[v].as_ref().unwrap(), u, v))
+            });
+        down[u] = Some(add_vertex(&acc, u));
+    }
+    // up[v] = dp(fa[v]) when v is cut off and fa[v] is the root
+    let mut up: Vec<Option<S>> = vec![None; n];
+    let mut res: Vec<Option<S>> = vec![None; n];
+    for &u in &ord {
+        let vals: Vec<S> = adj[u]
+            .iter()
+            .map(|&v| {
+                let sub = if v == fa[u] { &up[u] } else { &down[v] };
+                add_edge(sub.as_ref().unwrap(), u, v)
+            })
+            .collect();
+        // suf[i] = merge of vals[i..]
+        let mut suf = vec![identity.clone(); vals.len() + 1];
+        for i in (0..vals.len()).rev() {
+            suf[i] = merge(&vals[i], &suf[i + 1]);
+        }
+        res[u] = Some(add_vertex(&suf[0], u));
+        let mut pre = identity.clone();
+        for (i, &v) in adj[u].iter().enumerate() {
+            if v != fa[u] {
+                up[v] = Some(add_vertex(&merge(&pre, &suf[i + 1]), u));
+            }
+            pre = merge(&pre, &vals[i]);
+        }
+    }
+    res.into_iter().map(Option::unwrap).collect()
+}